@@ -0,0 +1,6 @@
+DROP TABLE counter_assignments_progress;
+DROP TABLE counter_assignments;
+DROP TABLE pass_fail_assignments_progress;
+DROP TABLE pass_fail_assignments;
+
+ALTER TABLE assignments DROP COLUMN assignment_type;
@@ -0,0 +1,47 @@
+-- Which of the *_assignments tables holds the type-specific data,
+-- so lookups don't have to try every table in turn
+--   0 - grade_assignments
+--   1 - point_assignments
+--   2 - pass_fail_assignments
+--   3 - counter_assignments
+ALTER TABLE assignments ADD COLUMN assignment_type TINYINT UNSIGNED NOT NULL DEFAULT 0 AFTER url;
+
+UPDATE assignments SET assignment_type = 1
+  WHERE id IN (SELECT assignment FROM point_assignments);
+
+ALTER TABLE assignments ALTER COLUMN assignment_type DROP DEFAULT;
+
+CREATE TABLE pass_fail_assignments (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  assignment INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_pass_fail_assignments_assignment FOREIGN KEY (assignment) REFERENCES assignments(id)
+);
+
+CREATE TABLE pass_fail_assignments_progress (
+  assignment INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_pass_fail_assignments_progress_assignment FOREIGN KEY (assignment) REFERENCES pass_fail_assignments(id),
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_pass_fail_assignments_progress_student FOREIGN KEY (student) REFERENCES users(id),
+  CONSTRAINT PRIMARY KEY (assignment, student),
+
+  passed BOOL NOT NULL
+);
+
+-- Attendance, labs completed and similar "n out of max_count" tallies
+CREATE TABLE counter_assignments (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  assignment INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_counter_assignments_assignment FOREIGN KEY (assignment) REFERENCES assignments(id),
+
+  max_count INTEGER UNSIGNED NOT NULL
+);
+
+CREATE TABLE counter_assignments_progress (
+  assignment INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_counter_assignments_progress_assignment FOREIGN KEY (assignment) REFERENCES counter_assignments(id),
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_counter_assignments_progress_student FOREIGN KEY (student) REFERENCES users(id),
+  CONSTRAINT PRIMARY KEY (assignment, student),
+
+  count INTEGER UNSIGNED NOT NULL
+);
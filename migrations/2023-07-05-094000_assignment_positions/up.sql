@@ -0,0 +1,5 @@
+-- Order in which the assignments of a course are listed
+ALTER TABLE assignments ADD COLUMN position INTEGER UNSIGNED NOT NULL DEFAULT 0 AFTER assignment_type;
+
+-- Keep the existing creation order
+UPDATE assignments SET position = id;
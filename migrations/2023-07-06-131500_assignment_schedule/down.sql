@@ -0,0 +1,4 @@
+ALTER TABLE assignments
+  DROP COLUMN due_at,
+  DROP COLUMN opens_at,
+  DROP COLUMN description;
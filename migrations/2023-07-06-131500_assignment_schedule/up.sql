@@ -0,0 +1,4 @@
+ALTER TABLE assignments
+  ADD COLUMN description TEXT NULL AFTER position,
+  ADD COLUMN opens_at DATETIME NULL AFTER description,
+  ADD COLUMN due_at DATETIME NULL AFTER opens_at;
@@ -0,0 +1,10 @@
+-- Secret tokens for the per-user iCalendar feed
+CREATE TABLE calendar_tokens (
+  user INTEGER UNSIGNED PRIMARY KEY,
+
+  token BINARY(32) NOT NULL UNIQUE,
+  script VARCHAR(16) NOT NULL,
+  created_on DATETIME NOT NULL,
+
+  CONSTRAINT fk_calendar_tokens_user FOREIGN KEY (user) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+-- Grades of unpublished assignments are hidden from students
+ALTER TABLE assignments ADD COLUMN published BOOL NOT NULL DEFAULT FALSE AFTER due_at;
+
+-- Students could already see the existing grades
+UPDATE assignments SET published = TRUE;
@@ -0,0 +1,27 @@
+DROP TRIGGER bu_counter_assignments_progress;
+DROP TABLE counter_assignments_progress_revisions;
+ALTER TABLE counter_assignments_progress
+  DROP FOREIGN KEY fk_counter_assignments_progress_graded_by,
+  DROP COLUMN graded_on,
+  DROP COLUMN graded_by;
+
+DROP TRIGGER bu_pass_fail_assignments_progress;
+DROP TABLE pass_fail_assignments_progress_revisions;
+ALTER TABLE pass_fail_assignments_progress
+  DROP FOREIGN KEY fk_pass_fail_assignments_progress_graded_by,
+  DROP COLUMN graded_on,
+  DROP COLUMN graded_by;
+
+DROP TRIGGER bu_grade_assignments_progress;
+DROP TABLE grade_assignments_progress_revisions;
+ALTER TABLE grade_assignments_progress
+  DROP FOREIGN KEY fk_grade_assignments_progress_graded_by,
+  DROP COLUMN graded_on,
+  DROP COLUMN graded_by;
+
+DROP TRIGGER bu_point_assignments_progress;
+DROP TABLE point_assignments_progress_revisions;
+ALTER TABLE point_assignments_progress
+  DROP FOREIGN KEY fk_point_assignments_progress_graded_by,
+  DROP COLUMN graded_on,
+  DROP COLUMN graded_by;
@@ -0,0 +1,168 @@
+-- Who entered a grade and when, kept for every grade that gets overwritten
+
+ALTER TABLE point_assignments_progress
+  ADD COLUMN graded_by INTEGER UNSIGNED DEFAULT NULL,
+  ADD COLUMN graded_on DATETIME DEFAULT NULL,
+  ADD CONSTRAINT fk_point_assignments_progress_graded_by FOREIGN KEY (graded_by) REFERENCES users(id);
+
+CREATE TABLE point_assignments_progress_revisions (
+  assignment INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_point_assignments_progress_revisions_progress FOREIGN KEY (assignment, student) REFERENCES point_assignments_progress(assignment, student),
+  revision INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT PRIMARY KEY (assignment, student, revision),
+
+  created DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+  points INTEGER UNSIGNED NOT NULL,
+  graded_by INTEGER UNSIGNED,
+  graded_on DATETIME
+);
+
+CREATE TRIGGER bu_point_assignments_progress BEFORE UPDATE ON point_assignments_progress FOR EACH ROW BEGIN
+  INSERT INTO point_assignments_progress_revisions (
+    assignment,
+    student,
+    revision,
+    created,
+    points,
+    graded_by,
+    graded_on
+  ) SELECT
+    OLD.assignment,
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(point_assignments_progress_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.points,
+    OLD.graded_by,
+    OLD.graded_on
+    FROM point_assignments_progress_revisions
+    WHERE point_assignments_progress_revisions.assignment = OLD.assignment AND point_assignments_progress_revisions.student = OLD.student;
+END;
+
+ALTER TABLE grade_assignments_progress
+  ADD COLUMN graded_by INTEGER UNSIGNED DEFAULT NULL,
+  ADD COLUMN graded_on DATETIME DEFAULT NULL,
+  ADD CONSTRAINT fk_grade_assignments_progress_graded_by FOREIGN KEY (graded_by) REFERENCES users(id);
+
+CREATE TABLE grade_assignments_progress_revisions (
+  assignment INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_grade_assignments_progress_revisions_progress FOREIGN KEY (assignment, student) REFERENCES grade_assignments_progress(assignment, student),
+  revision INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT PRIMARY KEY (assignment, student, revision),
+
+  created DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+  grade_major TINYINT UNSIGNED NOT NULL,
+  grade_minor TINYINT UNSIGNED NOT NULL,
+  graded_by INTEGER UNSIGNED,
+  graded_on DATETIME
+);
+
+CREATE TRIGGER bu_grade_assignments_progress BEFORE UPDATE ON grade_assignments_progress FOR EACH ROW BEGIN
+  INSERT INTO grade_assignments_progress_revisions (
+    assignment,
+    student,
+    revision,
+    created,
+    grade_major,
+    grade_minor,
+    graded_by,
+    graded_on
+  ) SELECT
+    OLD.assignment,
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(grade_assignments_progress_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.grade_major,
+    OLD.grade_minor,
+    OLD.graded_by,
+    OLD.graded_on
+    FROM grade_assignments_progress_revisions
+    WHERE grade_assignments_progress_revisions.assignment = OLD.assignment AND grade_assignments_progress_revisions.student = OLD.student;
+END;
+
+ALTER TABLE pass_fail_assignments_progress
+  ADD COLUMN graded_by INTEGER UNSIGNED DEFAULT NULL,
+  ADD COLUMN graded_on DATETIME DEFAULT NULL,
+  ADD CONSTRAINT fk_pass_fail_assignments_progress_graded_by FOREIGN KEY (graded_by) REFERENCES users(id);
+
+CREATE TABLE pass_fail_assignments_progress_revisions (
+  assignment INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_pass_fail_assignments_progress_revisions_progress FOREIGN KEY (assignment, student) REFERENCES pass_fail_assignments_progress(assignment, student),
+  revision INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT PRIMARY KEY (assignment, student, revision),
+
+  created DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+  passed BOOL NOT NULL,
+  graded_by INTEGER UNSIGNED,
+  graded_on DATETIME
+);
+
+CREATE TRIGGER bu_pass_fail_assignments_progress BEFORE UPDATE ON pass_fail_assignments_progress FOR EACH ROW BEGIN
+  INSERT INTO pass_fail_assignments_progress_revisions (
+    assignment,
+    student,
+    revision,
+    created,
+    passed,
+    graded_by,
+    graded_on
+  ) SELECT
+    OLD.assignment,
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(pass_fail_assignments_progress_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.passed,
+    OLD.graded_by,
+    OLD.graded_on
+    FROM pass_fail_assignments_progress_revisions
+    WHERE pass_fail_assignments_progress_revisions.assignment = OLD.assignment AND pass_fail_assignments_progress_revisions.student = OLD.student;
+END;
+
+ALTER TABLE counter_assignments_progress
+  ADD COLUMN graded_by INTEGER UNSIGNED DEFAULT NULL,
+  ADD COLUMN graded_on DATETIME DEFAULT NULL,
+  ADD CONSTRAINT fk_counter_assignments_progress_graded_by FOREIGN KEY (graded_by) REFERENCES users(id);
+
+CREATE TABLE counter_assignments_progress_revisions (
+  assignment INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_counter_assignments_progress_revisions_progress FOREIGN KEY (assignment, student) REFERENCES counter_assignments_progress(assignment, student),
+  revision INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT PRIMARY KEY (assignment, student, revision),
+
+  created DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+  count INTEGER UNSIGNED NOT NULL,
+  graded_by INTEGER UNSIGNED,
+  graded_on DATETIME
+);
+
+CREATE TRIGGER bu_counter_assignments_progress BEFORE UPDATE ON counter_assignments_progress FOR EACH ROW BEGIN
+  INSERT INTO counter_assignments_progress_revisions (
+    assignment,
+    student,
+    revision,
+    created,
+    count,
+    graded_by,
+    graded_on
+  ) SELECT
+    OLD.assignment,
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(counter_assignments_progress_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.count,
+    OLD.graded_by,
+    OLD.graded_on
+    FROM counter_assignments_progress_revisions
+    WHERE counter_assignments_progress_revisions.assignment = OLD.assignment AND counter_assignments_progress_revisions.student = OLD.student;
+END;
@@ -0,0 +1,8 @@
+-- URLs a course had before it was renamed, so old links keep working
+CREATE TABLE course_url_redirects (
+  url VARCHAR(255) PRIMARY KEY,
+
+  course INTEGER UNSIGNED NOT NULL,
+
+  CONSTRAINT fk_course_url_redirects_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+-- Teachers of a course, role is 0 for the owner, 1 for lecturers and 2 for assistants
+CREATE TABLE course_staff (
+  course INTEGER UNSIGNED NOT NULL,
+  user INTEGER UNSIGNED NOT NULL,
+
+  role TINYINT UNSIGNED NOT NULL,
+
+  PRIMARY KEY (course, user),
+  CONSTRAINT fk_course_staff_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE,
+  CONSTRAINT fk_course_staff_user FOREIGN KEY (user) REFERENCES users(id)
+);
+
+INSERT INTO course_staff (course, user, role)
+SELECT id, professor, 0 FROM courses;
@@ -0,0 +1,2 @@
+DROP TABLE enrolment_requests;
+DROP TABLE course_self_enrolment;
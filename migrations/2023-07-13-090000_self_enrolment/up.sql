@@ -0,0 +1,21 @@
+-- How students can enrol themselves in a course, mode is 0 when they
+-- can't, 1 for anyone, 2 with the enrolment key and 3 after approval
+CREATE TABLE course_self_enrolment (
+  course INTEGER UNSIGNED NOT NULL PRIMARY KEY,
+
+  mode TINYINT UNSIGNED NOT NULL,
+  enrolment_key VARCHAR(255),
+
+  CONSTRAINT fk_course_self_enrolment_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE
+);
+
+CREATE TABLE enrolment_requests (
+  course INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+
+  created DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  PRIMARY KEY (course, student),
+  CONSTRAINT fk_enrolment_requests_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE,
+  CONSTRAINT fk_enrolment_requests_student FOREIGN KEY (student) REFERENCES users(id)
+);
@@ -0,0 +1,4 @@
+ALTER TABLE assignments DROP FOREIGN KEY fk_assignments_exam_period;
+ALTER TABLE assignments DROP COLUMN exam_period;
+DROP TABLE exam_registrations;
+DROP TABLE exam_periods;
@@ -0,0 +1,26 @@
+-- Exam periods of the faculty (January, June, September...), students
+-- register for the exams of their courses while registration is open
+CREATE TABLE exam_periods (
+  id INTEGER UNSIGNED NOT NULL AUTO_INCREMENT PRIMARY KEY,
+  name VARCHAR(255) NOT NULL UNIQUE,
+
+  registration_opens DATETIME NOT NULL,
+  registration_closes DATETIME NOT NULL
+);
+
+CREATE TABLE exam_registrations (
+  exam_period INTEGER UNSIGNED NOT NULL,
+  course INTEGER UNSIGNED NOT NULL,
+  student INTEGER UNSIGNED NOT NULL,
+
+  created DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  PRIMARY KEY (exam_period, course, student),
+  CONSTRAINT fk_exam_registrations_exam_period FOREIGN KEY (exam_period) REFERENCES exam_periods(id) ON DELETE CASCADE,
+  CONSTRAINT fk_exam_registrations_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE,
+  CONSTRAINT fk_exam_registrations_student FOREIGN KEY (student) REFERENCES users(id)
+);
+
+-- Grades of an assignment bound to an exam period belong to that period
+ALTER TABLE assignments ADD COLUMN exam_period INTEGER UNSIGNED AFTER published;
+ALTER TABLE assignments ADD CONSTRAINT fk_assignments_exam_period FOREIGN KEY (exam_period) REFERENCES exam_periods(id) ON DELETE SET NULL;
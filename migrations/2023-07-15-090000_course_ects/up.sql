@@ -0,0 +1,2 @@
+-- ECTS credits a student earns by passing the course
+ALTER TABLE courses ADD COLUMN ects INTEGER UNSIGNED NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+DROP TABLE program_courses;
+ALTER TABLE courses DROP COLUMN semester;
@@ -0,0 +1,14 @@
+-- Semester of the program the course is taught in
+ALTER TABLE courses ADD COLUMN semester TINYINT UNSIGNED;
+
+-- Courses making up the curriculum of a program
+CREATE TABLE program_courses (
+  program INTEGER UNSIGNED NOT NULL,
+  course INTEGER UNSIGNED NOT NULL,
+
+  mandatory BOOL NOT NULL,
+
+  PRIMARY KEY (program, course),
+  CONSTRAINT fk_program_courses_program FOREIGN KEY (program) REFERENCES programs(id) ON DELETE CASCADE,
+  CONSTRAINT fk_program_courses_course FOREIGN KEY (course) REFERENCES courses(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+ALTER TABLE courses DROP FOREIGN KEY fk_courses_academic_year;
+DROP TABLE academic_years;
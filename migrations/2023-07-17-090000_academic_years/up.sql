@@ -0,0 +1,23 @@
+-- Status: 0 planned, 1 active, 2 closed
+CREATE TABLE academic_years (
+  id INTEGER UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  year INTEGER UNSIGNED UNIQUE NOT NULL,
+  starts DATE NOT NULL,
+  ends DATE NOT NULL,
+  status TINYINT UNSIGNED NOT NULL DEFAULT 0
+);
+
+-- Every year courses were taught in so far, the latest one is active
+INSERT INTO academic_years (year, starts, ends, status)
+SELECT
+  year,
+  STR_TO_DATE(CONCAT(year, '-10-01'), '%Y-%m-%d'),
+  STR_TO_DATE(CONCAT(year + 1, '-09-30'), '%Y-%m-%d'),
+  2
+FROM courses
+GROUP BY year;
+
+UPDATE academic_years SET status = 1 ORDER BY year DESC LIMIT 1;
+
+ALTER TABLE courses
+  ADD CONSTRAINT fk_courses_academic_year FOREIGN KEY (year) REFERENCES academic_years(year);
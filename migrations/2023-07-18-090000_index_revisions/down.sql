@@ -0,0 +1,3 @@
+DROP TRIGGER bd_indicies;
+DROP TRIGGER bu_indicies;
+DROP TABLE indicies_revisions;
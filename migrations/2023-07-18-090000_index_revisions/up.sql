@@ -0,0 +1,53 @@
+-- Indices a student had before theirs was corrected, changed or removed.
+-- Kept per student, as the index itself is gone once it is removed.
+
+CREATE TABLE indicies_revisions (
+  student INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT fk_indicies_revisions_student FOREIGN KEY (student) REFERENCES users(id),
+  revision INTEGER UNSIGNED NOT NULL,
+  CONSTRAINT PRIMARY KEY (student, revision),
+
+  created DATETIME DEFAULT CURRENT_TIMESTAMP,
+
+  program INTEGER UNSIGNED NOT NULL,
+  generation INTEGER UNSIGNED NOT NULL,
+  number INTEGER UNSIGNED NOT NULL
+);
+
+CREATE TRIGGER bu_indicies BEFORE UPDATE ON indicies FOR EACH ROW BEGIN
+  INSERT INTO indicies_revisions (
+    student,
+    revision,
+    created,
+    program,
+    generation,
+    number
+  ) SELECT
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(indicies_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.program,
+    OLD.generation,
+    OLD.number
+    FROM indicies_revisions WHERE indicies_revisions.student = OLD.student;
+END;
+
+CREATE TRIGGER bd_indicies BEFORE DELETE ON indicies FOR EACH ROW BEGIN
+  INSERT INTO indicies_revisions (
+    student,
+    revision,
+    created,
+    program,
+    generation,
+    number
+  ) SELECT
+    OLD.student,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(indicies_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.program,
+    OLD.generation,
+    OLD.number
+    FROM indicies_revisions WHERE indicies_revisions.student = OLD.student;
+END;
@@ -0,0 +1,2 @@
+DROP TABLE student_status_changes;
+ALTER TABLE users DROP COLUMN student_status;
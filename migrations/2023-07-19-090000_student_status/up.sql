@@ -0,0 +1,19 @@
+-- Status: 0 active, 1 on hold, 2 graduated, 3 expelled. Only students
+-- ever leave the active status.
+ALTER TABLE users ADD COLUMN student_status TINYINT UNSIGNED NOT NULL DEFAULT 0;
+
+-- Every status change with the reason for it, graduations also keep the
+-- date and the thesis grade
+CREATE TABLE student_status_changes (
+  id INTEGER UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+  student INTEGER UNSIGNED NOT NULL,
+  status TINYINT UNSIGNED NOT NULL,
+  reason TEXT NOT NULL,
+  graduated_on DATE,
+  thesis_grade TINYINT UNSIGNED,
+  changed_by INTEGER UNSIGNED NOT NULL,
+  created DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  CONSTRAINT fk_student_status_changes_student FOREIGN KEY (student) REFERENCES users(id) ON DELETE CASCADE,
+  CONSTRAINT fk_student_status_changes_changed_by FOREIGN KEY (changed_by) REFERENCES users(id)
+);
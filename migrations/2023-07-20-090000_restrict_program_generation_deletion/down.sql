@@ -0,0 +1,4 @@
+ALTER TABLE indicies DROP FOREIGN KEY fk_program;
+ALTER TABLE indicies DROP FOREIGN KEY fk_generation;
+ALTER TABLE indicies ADD CONSTRAINT fk_program FOREIGN KEY (program) REFERENCES programs(id) ON DELETE CASCADE;
+ALTER TABLE indicies ADD CONSTRAINT fk_generation FOREIGN KEY (generation) REFERENCES generations(id) ON DELETE CASCADE;
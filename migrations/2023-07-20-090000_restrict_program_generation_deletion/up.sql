@@ -0,0 +1,5 @@
+-- Deleting a program or a generation used to delete every index in it
+ALTER TABLE indicies DROP FOREIGN KEY fk_program;
+ALTER TABLE indicies DROP FOREIGN KEY fk_generation;
+ALTER TABLE indicies ADD CONSTRAINT fk_program FOREIGN KEY (program) REFERENCES programs(id) ON DELETE RESTRICT;
+ALTER TABLE indicies ADD CONSTRAINT fk_generation FOREIGN KEY (generation) REFERENCES generations(id) ON DELETE RESTRICT;
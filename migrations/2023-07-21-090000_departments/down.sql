@@ -0,0 +1,4 @@
+ALTER TABLE courses DROP FOREIGN KEY fk_courses_department, DROP COLUMN department;
+ALTER TABLE programs DROP FOREIGN KEY fk_programs_department, DROP COLUMN department;
+ALTER TABLE users DROP FOREIGN KEY fk_users_department, DROP COLUMN department;
+DROP TABLE departments;
@@ -0,0 +1,12 @@
+CREATE TABLE departments (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  name VARCHAR(255) UNIQUE NOT NULL
+);
+
+-- Administrators without a department manage every department
+ALTER TABLE users ADD COLUMN department INTEGER UNSIGNED NULL,
+  ADD CONSTRAINT fk_users_department FOREIGN KEY (department) REFERENCES departments(id) ON DELETE RESTRICT;
+ALTER TABLE programs ADD COLUMN department INTEGER UNSIGNED NULL,
+  ADD CONSTRAINT fk_programs_department FOREIGN KEY (department) REFERENCES departments(id) ON DELETE RESTRICT;
+ALTER TABLE courses ADD COLUMN department INTEGER UNSIGNED NULL,
+  ADD CONSTRAINT fk_courses_department FOREIGN KEY (department) REFERENCES departments(id) ON DELETE RESTRICT;
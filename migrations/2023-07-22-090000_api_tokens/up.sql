@@ -0,0 +1,17 @@
+-- Personal access tokens for the API, only a hash of the token is kept
+CREATE TABLE api_tokens (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  user INTEGER UNSIGNED NOT NULL,
+
+  name VARCHAR(255) NOT NULL,
+  token_hash BINARY(32) NOT NULL UNIQUE,
+  read_grades BOOLEAN NOT NULL DEFAULT FALSE,
+  write_grades BOOLEAN NOT NULL DEFAULT FALSE,
+  manage_users BOOLEAN NOT NULL DEFAULT FALSE,
+  -- The last day the token works on, it never expires without one
+  expires_on DATE NULL,
+  last_used DATETIME NULL,
+  created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  CONSTRAINT fk_api_tokens_user FOREIGN KEY (user) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,39 @@
+-- Outgoing webhooks, every payload is signed with the webhook's secret
+CREATE TABLE webhooks (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+
+  url VARCHAR(2048) NOT NULL,
+  secret CHAR(64) NOT NULL,
+  grade_changed BOOLEAN NOT NULL DEFAULT FALSE,
+  enrolment_created BOOLEAN NOT NULL DEFAULT FALSE,
+  user_created BOOLEAN NOT NULL DEFAULT FALSE,
+  course_deleted BOOLEAN NOT NULL DEFAULT FALSE,
+  created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_deliveries (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  webhook INTEGER UNSIGNED NOT NULL,
+
+  event TINYINT UNSIGNED NOT NULL,
+  payload TEXT NOT NULL,
+  -- When the next attempt is due, NULL once delivered or given up on
+  next_attempt_on DATETIME NULL,
+  delivered_on DATETIME NULL,
+  created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  INDEX (next_attempt_on),
+  CONSTRAINT fk_webhook_deliveries_webhook FOREIGN KEY (webhook) REFERENCES webhooks(id) ON DELETE CASCADE
+);
+
+CREATE TABLE webhook_delivery_attempts (
+  id INTEGER UNSIGNED PRIMARY KEY AUTO_INCREMENT,
+  delivery INTEGER UNSIGNED NOT NULL,
+
+  -- NULL when there was no response at all
+  response_status SMALLINT UNSIGNED NULL,
+  error VARCHAR(255) NULL,
+  attempted_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+  CONSTRAINT fk_webhook_delivery_attempts_delivery FOREIGN KEY (delivery) REFERENCES webhook_deliveries(id) ON DELETE CASCADE
+);
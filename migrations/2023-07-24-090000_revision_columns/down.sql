@@ -0,0 +1,56 @@
+DROP TRIGGER bu_courses;
+CREATE TRIGGER bu_courses BEFORE UPDATE ON courses FOR EACH ROW BEGIN
+  INSERT INTO courses_revisions (
+    id,
+    revision,
+    created,
+    year,
+    name,
+    url,
+    professor,
+    deleted
+  ) SELECT
+    OLD.id,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(courses_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.year,
+    OLD.name,
+    OLD.url,
+    OLD.professor,
+    OLD.deleted
+    FROM courses_revisions WHERE courses_revisions.id = OLD.id;
+END;
+
+DROP TRIGGER bu_users;
+CREATE TRIGGER bu_users BEFORE UPDATE ON users FOR EACH ROW BEGIN
+  INSERT INTO users_revisions (
+    id,
+    revision,
+    created,
+    password,
+    email,
+    account_type,
+    password_reset_required,
+    first_name,
+    last_name,
+    last_login_time,
+    deleted
+  ) SELECT
+    OLD.id,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(users_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.password,
+    OLD.email,
+    OLD.account_type,
+    OLD.password_reset_required,
+    OLD.first_name,
+    OLD.last_name,
+    OLD.last_login_time,
+    OLD.deleted
+    FROM users_revisions WHERE users_revisions.id = OLD.id;
+END;
+
+ALTER TABLE courses_revisions DROP COLUMN department, DROP COLUMN semester, DROP COLUMN ects;
+ALTER TABLE users_revisions DROP COLUMN department, DROP COLUMN student_status;
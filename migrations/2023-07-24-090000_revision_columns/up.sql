@@ -0,0 +1,81 @@
+-- Columns added to users and courses after their revision tables were
+-- created. Revisions from before this have the values the row has now, as
+-- nothing older was kept.
+
+ALTER TABLE users_revisions ADD COLUMN student_status TINYINT UNSIGNED NOT NULL DEFAULT 0,
+  ADD COLUMN department INTEGER UNSIGNED NULL;
+UPDATE users_revisions JOIN users ON users.id = users_revisions.id
+  SET users_revisions.student_status = users.student_status,
+    users_revisions.department = users.department;
+
+ALTER TABLE courses_revisions ADD COLUMN ects INTEGER UNSIGNED NOT NULL DEFAULT 0,
+  ADD COLUMN semester TINYINT UNSIGNED NULL,
+  ADD COLUMN department INTEGER UNSIGNED NULL;
+UPDATE courses_revisions JOIN courses ON courses.id = courses_revisions.id
+  SET courses_revisions.ects = courses.ects,
+    courses_revisions.semester = courses.semester,
+    courses_revisions.department = courses.department;
+
+DROP TRIGGER bu_users;
+CREATE TRIGGER bu_users BEFORE UPDATE ON users FOR EACH ROW BEGIN
+  INSERT INTO users_revisions (
+    id,
+    revision,
+    created,
+    password,
+    email,
+    account_type,
+    password_reset_required,
+    first_name,
+    last_name,
+    last_login_time,
+    deleted,
+    student_status,
+    department
+  ) SELECT
+    OLD.id,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(users_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.password,
+    OLD.email,
+    OLD.account_type,
+    OLD.password_reset_required,
+    OLD.first_name,
+    OLD.last_name,
+    OLD.last_login_time,
+    OLD.deleted,
+    OLD.student_status,
+    OLD.department
+    FROM users_revisions WHERE users_revisions.id = OLD.id;
+END;
+
+DROP TRIGGER bu_courses;
+CREATE TRIGGER bu_courses BEFORE UPDATE ON courses FOR EACH ROW BEGIN
+  INSERT INTO courses_revisions (
+    id,
+    revision,
+    created,
+    year,
+    name,
+    url,
+    professor,
+    deleted,
+    ects,
+    semester,
+    department
+  ) SELECT
+    OLD.id,
+    -- AUTO_INCREMENT the revision
+    IFNULL(MAX(courses_revisions.revision), 0) + 1,
+    NOW(),
+    OLD.year,
+    OLD.name,
+    OLD.url,
+    OLD.professor,
+    OLD.deleted,
+    OLD.ects,
+    OLD.semester,
+    OLD.department
+    FROM courses_revisions WHERE courses_revisions.id = OLD.id;
+END;
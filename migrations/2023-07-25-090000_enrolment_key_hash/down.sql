@@ -0,0 +1,4 @@
+-- The keys can't be recovered from their hashes, courses enrolled into with
+-- a key need a new one
+ALTER TABLE course_self_enrolment ADD COLUMN enrolment_key VARCHAR(255);
+ALTER TABLE course_self_enrolment DROP COLUMN enrolment_key_hash;
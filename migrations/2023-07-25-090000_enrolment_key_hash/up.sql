@@ -0,0 +1,5 @@
+-- Only a hash of the enrolment key is kept, the same as with API tokens
+ALTER TABLE course_self_enrolment ADD COLUMN enrolment_key_hash BINARY(32) NULL;
+UPDATE course_self_enrolment SET enrolment_key_hash = UNHEX(SHA2(enrolment_key, 256))
+  WHERE enrolment_key IS NOT NULL;
+ALTER TABLE course_self_enrolment DROP COLUMN enrolment_key;
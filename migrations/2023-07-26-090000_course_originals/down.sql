@@ -0,0 +1 @@
+ALTER TABLE courses DROP FOREIGN KEY fk_courses_original, DROP COLUMN original;
@@ -0,0 +1,12 @@
+-- The course a rolled-over course was first copied from, which every year
+-- of the same course shares. NULL for courses that weren't rolled over.
+ALTER TABLE courses ADD COLUMN original INTEGER UNSIGNED NULL,
+  ADD CONSTRAINT fk_courses_original FOREIGN KEY (original) REFERENCES courses(id);
+
+-- Courses rolled over before this kept no link to the one they were copied
+-- from, the ones with the same name are taken to be the same course
+UPDATE courses
+  JOIN (SELECT name, MIN(id) AS first FROM courses GROUP BY name) AS firsts
+    ON firsts.name = courses.name
+  SET courses.original = firsts.first
+  WHERE courses.id <> firsts.first;
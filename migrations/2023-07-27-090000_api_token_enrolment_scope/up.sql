@@ -0,0 +1,4 @@
+-- Enrolling students had been allowed by the grading scope, the tokens
+-- that have it keep being able to
+ALTER TABLE api_tokens ADD COLUMN manage_enrolments BOOLEAN NOT NULL DEFAULT FALSE AFTER write_grades;
+UPDATE api_tokens SET manage_enrolments = write_grades;
@@ -0,0 +1,205 @@
+use chrono::NaiveDate;
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    prelude::*,
+    serialize::ToSql,
+    sql_types::{TinyInt, Unsigned},
+    AsExpression, FromSqlRow,
+};
+use rocket::form::FromFormField;
+use serde::Serialize;
+
+use crate::{database::Connection, error::Error, schema::academic_years};
+
+/// Courses are only taught in the active year, grades of a closed year
+/// can no longer be changed
+#[repr(u8)]
+#[derive(FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum AcademicYearStatus {
+    Planned = 0,
+    Active = 1,
+    Closed = 2,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for AcademicYearStatus
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid AcademicYearStatus value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for AcademicYearStatus
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::Planned => 0.to_sql(out),
+            Self::Active => 1.to_sql(out),
+            Self::Closed => 2.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for AcademicYearStatus {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AcademicYearStatus::Planned),
+            1 => Ok(AcademicYearStatus::Active),
+            2 => Ok(AcademicYearStatus::Closed),
+            _ => Err(Error::InvalidAcademicYearStatusValue),
+        }
+    }
+}
+
+impl AcademicYearStatus {
+    /// A closed year can't be planned again. It can be activated again, so
+    /// that activating the wrong year, which closes the active one, can be
+    /// undone, and its grades can then be changed again.
+    pub fn can_change_to(self, status: AcademicYearStatus) -> bool {
+        !(self == AcademicYearStatus::Closed && status == AcademicYearStatus::Planned)
+    }
+}
+
+/// An academic year, known by the calendar year it starts in
+#[derive(Clone, Debug, Queryable, Selectable, Serialize, Identifiable)]
+#[diesel(table_name = academic_years)]
+pub struct AcademicYear {
+    pub id: u32,
+    pub year: u32,
+    pub starts: NaiveDate,
+    pub ends: NaiveDate,
+    pub status: AcademicYearStatus,
+}
+
+impl AcademicYear {
+    pub fn create(
+        connection: &mut Connection,
+        year: u32,
+        starts: NaiveDate,
+        ends: NaiveDate,
+    ) -> Result<(), Error> {
+        diesel::insert_into(academic_years::table)
+            .values((
+                academic_years::year.eq(year),
+                academic_years::starts.eq(starts),
+                academic_years::ends.eq(ends),
+                academic_years::status.eq(AcademicYearStatus::Planned),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn get_by_id(connection: &mut Connection, id: u32) -> Result<Self, Error> {
+        academic_years::table
+            .filter(academic_years::id.eq(id))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get_active(connection: &mut Connection) -> Result<Option<Self>, Error> {
+        academic_years::table
+            .filter(academic_years::status.eq(AcademicYearStatus::Active))
+            .first(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// The given year, or the active one if there is no given year. `None`
+    /// when neither is there, which means every year.
+    pub fn year_or_active(
+        connection: &mut Connection,
+        year: Option<u32>,
+    ) -> Result<Option<u32>, Error> {
+        match year {
+            Some(year) => Ok(Some(year)),
+            None => Ok(Self::get_active(connection)?.map(|active| active.year)),
+        }
+    }
+
+    /// Whether grades of the courses taught in the year are read-only
+    pub fn is_closed(connection: &mut Connection, year: u32) -> Result<bool, Error> {
+        academic_years::table
+            .filter(academic_years::year.eq(year))
+            .select(academic_years::status)
+            .first::<AcademicYearStatus>(connection)
+            .optional()
+            .map(|status| status == Some(AcademicYearStatus::Closed))
+            .map_err(Error::from)
+    }
+
+    /// Whether courses can be created in the year or moved into it, which
+    /// it has to exist and not be closed for
+    pub fn is_open(connection: &mut Connection, year: u32) -> Result<bool, Error> {
+        academic_years::table
+            .filter(academic_years::year.eq(year))
+            .select(academic_years::status)
+            .first::<AcademicYearStatus>(connection)
+            .optional()
+            .map(|status| {
+                matches!(
+                    status,
+                    Some(AcademicYearStatus::Planned | AcademicYearStatus::Active)
+                )
+            })
+            .map_err(Error::from)
+    }
+
+    /// Only one year is active at a time, activating a year closes the one
+    /// that was active before it
+    pub fn set_status(
+        &self,
+        connection: &mut Connection,
+        status: AcademicYearStatus,
+    ) -> Result<(), Error> {
+        if status == AcademicYearStatus::Active {
+            diesel::update(
+                academic_years::table
+                    .filter(academic_years::status.eq(AcademicYearStatus::Active))
+                    .filter(academic_years::id.ne(self.id)),
+            )
+            .set(academic_years::status.eq(AcademicYearStatus::Closed))
+            .execute(connection)?;
+        }
+
+        diesel::update(self)
+            .set(academic_years::status.eq(status))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct AcademicYears(pub Vec<AcademicYear>);
+
+impl AcademicYears {
+    /// Newest first
+    pub fn get(connection: &mut Connection) -> Result<Self, Error> {
+        academic_years::table
+            .order(academic_years::year.desc())
+            .load(connection)
+            .map(AcademicYears)
+            .map_err(Error::from)
+    }
+
+    /// Years new courses can be created in
+    pub fn get_open(connection: &mut Connection) -> Result<Self, Error> {
+        academic_years::table
+            .filter(academic_years::status.ne(AcademicYearStatus::Closed))
+            .order(academic_years::year.desc())
+            .load(connection)
+            .map(AcademicYears)
+            .map_err(Error::from)
+    }
+}
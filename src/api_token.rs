@@ -0,0 +1,156 @@
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use rand::{Fill, SeedableRng};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    database::Connection,
+    error::Error,
+    schema::{api_tokens, users},
+    user::{User, UserId},
+};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Scope {
+    /// Progress and who is enrolled in a course
+    ReadGrades,
+    /// Grading
+    WriteGrades,
+    /// Changing who is enrolled in a course
+    ManageEnrolments,
+    /// Other users' accounts
+    ManageUsers,
+}
+
+/// What a token is allowed to do through the API, on top of what its user
+/// is allowed to do. Signing in with a session has every scope.
+#[derive(Serialize, Debug, Clone, Copy, Queryable, Selectable)]
+#[diesel(table_name = api_tokens)]
+pub struct ApiTokenScopes {
+    pub read_grades: bool,
+    pub write_grades: bool,
+    pub manage_enrolments: bool,
+    pub manage_users: bool,
+}
+
+impl ApiTokenScopes {
+    pub fn has(self, scope: Scope) -> bool {
+        match scope {
+            Scope::ReadGrades => self.read_grades,
+            Scope::WriteGrades => self.write_grades,
+            Scope::ManageEnrolments => self.manage_enrolments,
+            Scope::ManageUsers => self.manage_users,
+        }
+    }
+
+    /// Drops the scopes the user's account couldn't use anyway
+    pub fn allowed_for(self, user: &User) -> Self {
+        Self {
+            read_grades: self.read_grades,
+            write_grades: self.write_grades && user.is_professor(),
+            manage_enrolments: self.manage_enrolments
+                && (user.is_professor() || user.is_administrator()),
+            manage_users: self.manage_users && user.is_administrator(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable)]
+#[diesel(table_name = api_tokens)]
+pub struct ApiToken {
+    pub id: u32,
+    pub user: UserId,
+    pub name: String,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub scopes: ApiTokenScopes,
+    pub expires_on: Option<NaiveDate>,
+    pub last_used: Option<NaiveDateTime>,
+    pub created_on: NaiveDateTime,
+}
+
+fn hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+impl ApiToken {
+    /// Creates the token and returns it, which is the only time it can be
+    /// seen as only its hash is stored
+    pub fn create(
+        connection: &mut Connection,
+        user: UserId,
+        name: &str,
+        scopes: ApiTokenScopes,
+        expires_on: Option<NaiveDate>,
+    ) -> Result<String, Error> {
+        let mut token = [0u8; 32];
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        token.try_fill(&mut rng).map_err(Error::from)?;
+        let token = hex::encode(token);
+
+        diesel::insert_into(api_tokens::table)
+            .values((
+                api_tokens::user.eq(user),
+                api_tokens::name.eq(name),
+                api_tokens::token_hash.eq(hash(&token)),
+                api_tokens::read_grades.eq(scopes.read_grades),
+                api_tokens::write_grades.eq(scopes.write_grades),
+                api_tokens::manage_enrolments.eq(scopes.manage_enrolments),
+                api_tokens::manage_users.eq(scopes.manage_users),
+                api_tokens::expires_on.eq(expires_on),
+            ))
+            .execute(connection)?;
+
+        Ok(token)
+    }
+
+    /// Newest first, expired ones included
+    pub fn get_all(connection: &mut Connection, user: UserId) -> Result<Vec<Self>, Error> {
+        api_tokens::table
+            .filter(api_tokens::user.eq(user))
+            .order(api_tokens::id.desc())
+            .select(ApiToken::as_select())
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    /// Finds the user of a token that hasn't expired and notes that it was
+    /// used
+    pub fn authenticate(connection: &mut Connection, token: &str) -> Result<(Self, User), Error> {
+        let today = Local::now().date_naive();
+        let (token, user) = api_tokens::table
+            .inner_join(users::table)
+            .filter(api_tokens::token_hash.eq(hash(token)))
+            .filter(
+                api_tokens::expires_on
+                    .is_null()
+                    .or(api_tokens::expires_on.ge(today)),
+            )
+            .filter(users::deleted.eq(false))
+            .select((ApiToken::as_select(), User::as_select()))
+            .first::<(ApiToken, User)>(connection)?;
+
+        diesel::update(api_tokens::table.filter(api_tokens::id.eq(token.id)))
+            .set(api_tokens::last_used.eq(Local::now().naive_local()))
+            .execute(connection)?;
+
+        Ok((token, user))
+    }
+
+    /// Deletes one of the user's tokens, it stops working right away
+    pub fn revoke(connection: &mut Connection, user: UserId, id: u32) -> Result<(), Error> {
+        let deleted = diesel::delete(
+            api_tokens::table
+                .filter(api_tokens::id.eq(id))
+                .filter(api_tokens::user.eq(user)),
+        )
+        .execute(connection)?;
+
+        if deleted == 0 {
+            return Err(Error::DatabaseEntryNotFound);
+        }
+
+        Ok(())
+    }
+}
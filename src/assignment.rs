@@ -1,25 +1,102 @@
 use crate::{
-    schema::{assignments, grade_assignments_progress, point_assignments_progress},
+    course::Course,
+    schema::{
+        assignments, counter_assignments_progress, counter_assignments_progress_revisions, courses,
+        grade_assignments_progress, grade_assignments_progress_revisions,
+        pass_fail_assignments_progress, pass_fail_assignments_progress_revisions,
+        point_assignments_progress, point_assignments_progress_revisions, users,
+    },
     user::UserId,
 };
-use diesel::{prelude::*, Insertable, Queryable, Selectable};
+use chrono::{Local, Months, NaiveDateTime};
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    prelude::*,
+    serialize::ToSql,
+    sql_types::{TinyInt, Unsigned},
+    AsExpression, FromSqlRow, Insertable, Queryable, Selectable,
+};
 use rocket::{FromForm, FromFormField};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::{
     course::CourseId,
     database::Connection,
     error::Error,
-    schema::{grade_assignments, point_assignments},
+    exam::ExamPeriodId,
+    schema::{counter_assignments, grade_assignments, pass_fail_assignments, point_assignments},
+    webhook::{self, GradeChanged, WebhookEvent},
 };
 
-#[derive(Serialize, Debug, Queryable, Insertable, Selectable)]
+#[repr(u8)]
+#[derive(
+    Serialize, Debug, FromFormField, AsExpression, FromSqlRow, PartialEq, Clone, Copy, ToSchema,
+)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum AssignmentType {
+    Grade = 0,
+    Point = 1,
+    PassFail = 2,
+    Counter = 3,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for AssignmentType
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid AssignmentType value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for AssignmentType
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::Grade => 0.to_sql(out),
+            Self::Point => 1.to_sql(out),
+            Self::PassFail => 2.to_sql(out),
+            Self::Counter => 3.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for AssignmentType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AssignmentType::Grade),
+            1 => Ok(AssignmentType::Point),
+            2 => Ok(AssignmentType::PassFail),
+            3 => Ok(AssignmentType::Counter),
+            _ => Err(Error::InvalidAssignmentTypeValue),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Insertable, Selectable, Identifiable, ToSchema)]
 #[diesel(table_name = assignments)]
 pub struct BaseAssignment {
     pub id: u32,
     pub course: u32,
     pub name: String,
     pub url: String,
+    pub assignment_type: AssignmentType,
+    pub position: u32,
+    pub description: Option<String>,
+    pub opens_at: Option<NaiveDateTime>,
+    pub due_at: Option<NaiveDateTime>,
+    pub published: bool,
+    #[schema(value_type = Option<u32>)]
+    pub exam_period: Option<ExamPeriodId>,
     pub deleted: bool,
 }
 
@@ -36,32 +113,201 @@ impl BaseAssignment {
             .map_err(Error::from)
     }
 
+    pub fn get_by_id(connection: &mut Connection, id: u32) -> Result<Self, Error> {
+        assignments::table
+            .filter(assignments::id.eq(id))
+            .limit(1)
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// The assignments of the course that aren't deleted, in their listed
+    /// order
+    pub fn get_by_course(
+        connection: &mut Connection,
+        course: CourseId,
+    ) -> Result<Vec<Self>, Error> {
+        assignments::table
+            .filter(assignments::course.eq(course))
+            .filter(assignments::deleted.eq(false))
+            .order(assignments::position.asc())
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get_by_url(connection: &mut Connection, course: u32, url: &str) -> Result<Self, Error> {
+        assignments::table
+            .filter(assignments::course.eq(course).and(assignments::url.eq(url)))
+            .limit(1)
+            .first(connection)
+            .map_err(Error::from)
+    }
+
     pub fn create(
         connection: &mut Connection,
         course: u32,
         name: &str,
         url: &str,
+        assignment_type: AssignmentType,
     ) -> Result<(), Error> {
+        // New assignments go to the end of the list
+        let position = assignments::table
+            .filter(assignments::course.eq(course))
+            .select(diesel::dsl::max(assignments::position))
+            .first::<Option<u32>>(connection)
+            .map_err(Error::from)?
+            .map_or(0, |p| p + 1);
+
         diesel::insert_into(assignments::table)
             .values(&(
                 assignments::course.eq(course),
                 assignments::name.eq(name),
                 assignments::url.eq(url),
+                assignments::assignment_type.eq(assignment_type),
+                assignments::position.eq(position),
             ))
             .execute(connection)
             .map_err(Error::from)
             .map(|_| ())
     }
+
+    pub fn update_name<'a>(
+        &self,
+        connection: &mut Connection,
+        name: &'a str,
+        url: &'a str,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set((assignments::name.eq(name), assignments::url.eq(url)))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_position(&self, connection: &mut Connection, position: u32) -> Result<(), Error> {
+        diesel::update(self)
+            .set(assignments::position.eq(position))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_deleted(&self, connection: &mut Connection, deleted: bool) -> Result<(), Error> {
+        diesel::update(self)
+            .set(assignments::deleted.eq(deleted))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_published(
+        &self,
+        connection: &mut Connection,
+        published: bool,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(assignments::published.eq(published))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_exam_period(
+        &self,
+        connection: &mut Connection,
+        exam_period: Option<ExamPeriodId>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(assignments::exam_period.eq(exam_period))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_schedule(
+        &self,
+        connection: &mut Connection,
+        description: Option<&str>,
+        opens_at: Option<NaiveDateTime>,
+        due_at: Option<NaiveDateTime>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set((
+                assignments::description.eq(description),
+                assignments::opens_at.eq(opens_at),
+                assignments::due_at.eq(due_at),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
 }
 
 #[derive(Serialize, Debug, Queryable, Selectable)]
+pub struct Deadline {
+    #[diesel(embed)]
+    pub course: Course,
+    #[diesel(embed)]
+    pub assignment: BaseAssignment,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Deadlines(pub Vec<Deadline>);
+
+impl Deadlines {
+    /// Assignments of the given courses that are due between `from` and
+    /// `until`, soonest first
+    pub fn get(
+        connection: &mut Connection,
+        courses: &[CourseId],
+        from: NaiveDateTime,
+        until: Option<NaiveDateTime>,
+    ) -> Result<Self, Error> {
+        let mut query = assignments::table
+            .inner_join(courses::table)
+            .filter(assignments::course.eq_any(courses))
+            .filter(assignments::deleted.eq(false))
+            .filter(assignments::due_at.ge(from))
+            .into_boxed();
+
+        if let Some(until) = until {
+            query = query.filter(assignments::due_at.lt(until));
+        }
+
+        query
+            .order(assignments::due_at.asc())
+            .select(Deadline::as_select())
+            .load(connection)
+            .map_err(Error::from)
+            .map(Deadlines)
+    }
+
+    /// Every assignment of the given courses that has an opening or due date
+    pub fn get_dated(connection: &mut Connection, courses: &[CourseId]) -> Result<Self, Error> {
+        assignments::table
+            .inner_join(courses::table)
+            .filter(assignments::course.eq_any(courses))
+            .filter(assignments::deleted.eq(false))
+            .filter(
+                assignments::opens_at
+                    .is_not_null()
+                    .or(assignments::due_at.is_not_null()),
+            )
+            .select(Deadline::as_select())
+            .load(connection)
+            .map_err(Error::from)
+            .map(Deadlines)
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = grade_assignments)]
 pub struct GradeAssignmentData {
     pub id: u32,
     pub assignment: u32,
 }
 
-#[derive(Serialize, Debug, Queryable, Selectable)]
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = grade_assignments)]
 pub struct GradeAssignment {
     #[serde(flatten)]
@@ -93,7 +339,7 @@ impl GradeAssignment {
         url: &str,
     ) -> Result<(), Error> {
         diesel::Connection::transaction(connection, |connection| {
-            BaseAssignment::create(connection, course, name, url)?;
+            BaseAssignment::create(connection, course, name, url, AssignmentType::Grade)?;
             let assignment = BaseAssignment::get(connection, course, name)?;
             diesel::insert_into(grade_assignments::table)
                 .values(grade_assignments::assignment.eq(assignment.id))
@@ -108,6 +354,7 @@ impl GradeAssignment {
         grade_assignment_id: u32,
         student: UserId,
         grade: GradeAssignmentGrade,
+        graded_by: UserId,
     ) -> Result<(), Error> {
         #[derive(Selectable, Queryable, Identifiable)]
         #[diesel(table_name = grade_assignments_progress)]
@@ -126,14 +373,18 @@ impl GradeAssignment {
                     .eq(grade_assignment_id)
                     .and(grade_assignments_progress::student.eq(student)),
             )
+            .select(Grade::as_select())
             .limit(1)
             .first::<Grade>(connection)
             .map_err(Error::from);
+        let now = Local::now().naive_local();
         match previous_grade {
             Ok(previous_grade) => diesel::update(&previous_grade)
                 .set((
                     grade_assignments_progress::grade_minor.eq(grade.minor),
                     grade_assignments_progress::grade_major.eq(grade.major),
+                    grade_assignments_progress::graded_by.eq(graded_by),
+                    grade_assignments_progress::graded_on.eq(now),
                 ))
                 .execute(connection)
                 .map_err(Error::from)
@@ -145,17 +396,41 @@ impl GradeAssignment {
                         grade_assignments_progress::student.eq(student),
                         grade_assignments_progress::grade_minor.eq(grade.minor),
                         grade_assignments_progress::grade_major.eq(grade.major),
+                        grade_assignments_progress::graded_by.eq(graded_by),
+                        grade_assignments_progress::graded_on.eq(now),
                     ))
                     .execute(connection)
                     .map_err(Error::from)
                     .map(|_| ())
             }
             Err(e) => Err(e),
+        }?;
+
+        let (assignment, course, published) = grade_assignments::table
+            .inner_join(assignments::table)
+            .filter(grade_assignments::id.eq(grade_assignment_id))
+            .select((assignments::id, assignments::course, assignments::published))
+            .first::<(u32, CourseId, bool)>(connection)?;
+        // Grades of unpublished assignments are sent once they're published
+        if !published {
+            return Ok(());
         }
+        webhook::trigger(
+            connection,
+            WebhookEvent::GradeChanged,
+            &GradeChanged {
+                course,
+                assignment,
+                student,
+                graded_by: Some(graded_by),
+                grade: Some(grade),
+                ..GradeChanged::default()
+            },
+        )
     }
 }
 
-#[derive(Serialize, Debug, Queryable, Selectable)]
+#[derive(Serialize, Debug, Queryable, Selectable, Identifiable, ToSchema)]
 #[diesel(table_name = point_assignments)]
 pub struct PointAssignmentData {
     pub id: u32,
@@ -163,7 +438,21 @@ pub struct PointAssignmentData {
     pub max_points: u32,
 }
 
-#[derive(Serialize, Debug, Queryable, Selectable)]
+impl PointAssignmentData {
+    pub fn update_max_points(
+        &self,
+        connection: &mut Connection,
+        max_points: u32,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(point_assignments::max_points.eq(max_points))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
 #[diesel(table_name = point_assignments)]
 pub struct PointAssignment {
     #[serde(flatten)]
@@ -196,7 +485,7 @@ impl PointAssignment {
         max_points: u32,
     ) -> Result<(), Error> {
         diesel::Connection::transaction(connection, |connection| {
-            BaseAssignment::create(connection, course, name, url)?;
+            BaseAssignment::create(connection, course, name, url, AssignmentType::Point)?;
             let assignment = BaseAssignment::get(connection, course, name)?;
             diesel::insert_into(point_assignments::table)
                 .values((
@@ -214,6 +503,7 @@ impl PointAssignment {
         points_assignment_id: u32,
         student: UserId,
         points: u32,
+        graded_by: UserId,
     ) -> Result<(), Error> {
         #[derive(Selectable, Queryable, Identifiable)]
         #[diesel(table_name = point_assignments_progress)]
@@ -230,12 +520,18 @@ impl PointAssignment {
                     .eq(points_assignment_id)
                     .and(point_assignments_progress::student.eq(student)),
             )
+            .select(Grade::as_select())
             .limit(1)
             .first::<Grade>(connection)
             .map_err(Error::from);
+        let now = Local::now().naive_local();
         match previous_grade {
             Ok(previous_grade) => diesel::update(&previous_grade)
-                .set(point_assignments_progress::points.eq(points))
+                .set((
+                    point_assignments_progress::points.eq(points),
+                    point_assignments_progress::graded_by.eq(graded_by),
+                    point_assignments_progress::graded_on.eq(now),
+                ))
                 .execute(connection)
                 .map_err(Error::from)
                 .map(|_| ()),
@@ -245,20 +541,317 @@ impl PointAssignment {
                         point_assignments_progress::assignment.eq(points_assignment_id),
                         point_assignments_progress::student.eq(student),
                         point_assignments_progress::points.eq(points),
+                        point_assignments_progress::graded_by.eq(graded_by),
+                        point_assignments_progress::graded_on.eq(now),
+                    ))
+                    .execute(connection)
+                    .map_err(Error::from)
+                    .map(|_| ())
+            }
+            Err(e) => Err(e),
+        }?;
+
+        let (assignment, course, published) = point_assignments::table
+            .inner_join(assignments::table)
+            .filter(point_assignments::id.eq(points_assignment_id))
+            .select((assignments::id, assignments::course, assignments::published))
+            .first::<(u32, CourseId, bool)>(connection)?;
+        // Grades of unpublished assignments are sent once they're published
+        if !published {
+            return Ok(());
+        }
+        webhook::trigger(
+            connection,
+            WebhookEvent::GradeChanged,
+            &GradeChanged {
+                course,
+                assignment,
+                student,
+                graded_by: Some(graded_by),
+                points: Some(points),
+                ..GradeChanged::default()
+            },
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
+#[diesel(table_name = pass_fail_assignments)]
+pub struct PassFailAssignmentData {
+    pub id: u32,
+    pub assignment: u32,
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
+#[diesel(table_name = pass_fail_assignments)]
+pub struct PassFailAssignment {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub data: PassFailAssignmentData,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub base: BaseAssignment,
+}
+
+impl PassFailAssignment {
+    pub fn get(
+        connection: &mut Connection,
+        course: CourseId,
+        url: &str,
+    ) -> Result<PassFailAssignment, Error> {
+        pass_fail_assignments::table
+            .inner_join(assignments::table)
+            .filter(assignments::course.eq(course).and(assignments::url.eq(url)))
+            .limit(1)
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn create(
+        connection: &mut Connection,
+        course: CourseId,
+        name: &str,
+        url: &str,
+    ) -> Result<(), Error> {
+        diesel::Connection::transaction(connection, |connection| {
+            BaseAssignment::create(connection, course, name, url, AssignmentType::PassFail)?;
+            let assignment = BaseAssignment::get(connection, course, name)?;
+            diesel::insert_into(pass_fail_assignments::table)
+                .values(pass_fail_assignments::assignment.eq(assignment.id))
+                .execute(connection)
+                .map_err(Error::from)
+        })
+        .map(|_| ())
+    }
+
+    pub fn grade(
+        connection: &mut Connection,
+        pass_fail_assignment_id: u32,
+        student: UserId,
+        passed: bool,
+        graded_by: UserId,
+    ) -> Result<(), Error> {
+        #[derive(Selectable, Queryable, Identifiable)]
+        #[diesel(table_name = pass_fail_assignments_progress)]
+        #[diesel(primary_key(assignment, student))]
+        struct Grade {
+            assignment: u32,
+            student: u32,
+            #[diesel(column_name = "passed")]
+            _passed: bool,
+        }
+        let previous_grade = pass_fail_assignments_progress::table
+            .filter(
+                pass_fail_assignments_progress::assignment
+                    .eq(pass_fail_assignment_id)
+                    .and(pass_fail_assignments_progress::student.eq(student)),
+            )
+            .select(Grade::as_select())
+            .limit(1)
+            .first::<Grade>(connection)
+            .map_err(Error::from);
+        let now = Local::now().naive_local();
+        match previous_grade {
+            Ok(previous_grade) => diesel::update(&previous_grade)
+                .set((
+                    pass_fail_assignments_progress::passed.eq(passed),
+                    pass_fail_assignments_progress::graded_by.eq(graded_by),
+                    pass_fail_assignments_progress::graded_on.eq(now),
+                ))
+                .execute(connection)
+                .map_err(Error::from)
+                .map(|_| ()),
+            Err(Error::DatabaseEntryNotFound) => {
+                diesel::insert_into(pass_fail_assignments_progress::table)
+                    .values((
+                        pass_fail_assignments_progress::assignment.eq(pass_fail_assignment_id),
+                        pass_fail_assignments_progress::student.eq(student),
+                        pass_fail_assignments_progress::passed.eq(passed),
+                        pass_fail_assignments_progress::graded_by.eq(graded_by),
+                        pass_fail_assignments_progress::graded_on.eq(now),
                     ))
                     .execute(connection)
                     .map_err(Error::from)
                     .map(|_| ())
             }
             Err(e) => Err(e),
+        }?;
+
+        let (assignment, course, published) = pass_fail_assignments::table
+            .inner_join(assignments::table)
+            .filter(pass_fail_assignments::id.eq(pass_fail_assignment_id))
+            .select((assignments::id, assignments::course, assignments::published))
+            .first::<(u32, CourseId, bool)>(connection)?;
+        // Grades of unpublished assignments are sent once they're published
+        if !published {
+            return Ok(());
         }
+        webhook::trigger(
+            connection,
+            WebhookEvent::GradeChanged,
+            &GradeChanged {
+                course,
+                assignment,
+                student,
+                graded_by: Some(graded_by),
+                passed: Some(passed),
+                ..GradeChanged::default()
+            },
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable, Identifiable, ToSchema)]
+#[diesel(table_name = counter_assignments)]
+pub struct CounterAssignmentData {
+    pub id: u32,
+    pub assignment: u32,
+    pub max_count: u32,
+}
+
+impl CounterAssignmentData {
+    pub fn update_max_count(
+        &self,
+        connection: &mut Connection,
+        max_count: u32,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(counter_assignments::max_count.eq(max_count))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Serialize, Debug, Queryable, Selectable, ToSchema)]
+#[diesel(table_name = counter_assignments)]
+pub struct CounterAssignment {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub data: CounterAssignmentData,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub base: BaseAssignment,
+}
+
+impl CounterAssignment {
+    pub fn get(
+        connection: &mut Connection,
+        course: CourseId,
+        url: &str,
+    ) -> Result<CounterAssignment, Error> {
+        counter_assignments::table
+            .inner_join(assignments::table)
+            .filter(assignments::course.eq(course).and(assignments::url.eq(url)))
+            .limit(1)
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn create<'a>(
+        connection: &mut Connection,
+        course: CourseId,
+        name: &'a str,
+        url: &'a str,
+        max_count: u32,
+    ) -> Result<(), Error> {
+        diesel::Connection::transaction(connection, |connection| {
+            BaseAssignment::create(connection, course, name, url, AssignmentType::Counter)?;
+            let assignment = BaseAssignment::get(connection, course, name)?;
+            diesel::insert_into(counter_assignments::table)
+                .values((
+                    counter_assignments::assignment.eq(assignment.id),
+                    counter_assignments::max_count.eq(max_count),
+                ))
+                .execute(connection)
+                .map_err(Error::from)
+        })
+        .map(|_| ())
+    }
+
+    pub fn grade(
+        connection: &mut Connection,
+        counter_assignment_id: u32,
+        student: UserId,
+        count: u32,
+        graded_by: UserId,
+    ) -> Result<(), Error> {
+        #[derive(Selectable, Queryable, Identifiable)]
+        #[diesel(table_name = counter_assignments_progress)]
+        #[diesel(primary_key(assignment, student))]
+        struct Grade {
+            assignment: u32,
+            student: u32,
+            #[diesel(column_name = "count")]
+            _count: u32,
+        }
+        let previous_grade = counter_assignments_progress::table
+            .filter(
+                counter_assignments_progress::assignment
+                    .eq(counter_assignment_id)
+                    .and(counter_assignments_progress::student.eq(student)),
+            )
+            .select(Grade::as_select())
+            .limit(1)
+            .first::<Grade>(connection)
+            .map_err(Error::from);
+        let now = Local::now().naive_local();
+        match previous_grade {
+            Ok(previous_grade) => diesel::update(&previous_grade)
+                .set((
+                    counter_assignments_progress::count.eq(count),
+                    counter_assignments_progress::graded_by.eq(graded_by),
+                    counter_assignments_progress::graded_on.eq(now),
+                ))
+                .execute(connection)
+                .map_err(Error::from)
+                .map(|_| ()),
+            Err(Error::DatabaseEntryNotFound) => {
+                diesel::insert_into(counter_assignments_progress::table)
+                    .values((
+                        counter_assignments_progress::assignment.eq(counter_assignment_id),
+                        counter_assignments_progress::student.eq(student),
+                        counter_assignments_progress::count.eq(count),
+                        counter_assignments_progress::graded_by.eq(graded_by),
+                        counter_assignments_progress::graded_on.eq(now),
+                    ))
+                    .execute(connection)
+                    .map_err(Error::from)
+                    .map(|_| ())
+            }
+            Err(e) => Err(e),
+        }?;
+
+        let (assignment, course, published) = counter_assignments::table
+            .inner_join(assignments::table)
+            .filter(counter_assignments::id.eq(counter_assignment_id))
+            .select((assignments::id, assignments::course, assignments::published))
+            .first::<(u32, CourseId, bool)>(connection)?;
+        // Grades of unpublished assignments are sent once they're published
+        if !published {
+            return Ok(());
+        }
+        webhook::trigger(
+            connection,
+            WebhookEvent::GradeChanged,
+            &GradeChanged {
+                course,
+                assignment,
+                student,
+                graded_by: Some(graded_by),
+                count: Some(count),
+                ..GradeChanged::default()
+            },
+        )
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
 pub enum Assignment {
     Grade(GradeAssignment),
     Point(PointAssignment),
+    PassFail(PassFailAssignment),
+    Counter(CounterAssignment),
 }
 
 impl Assignment {
@@ -267,11 +860,170 @@ impl Assignment {
         course: CourseId,
         url: &str,
     ) -> Result<Assignment, Error> {
-        PointAssignment::get(connection, course, url)
-            .map(|p| Assignment::Point(p))
-            .or_else(|_| {
-                GradeAssignment::get(connection, course, url).map(|g| Assignment::Grade(g))
-            })
+        let base = BaseAssignment::get_by_url(connection, course, url)?;
+        // Deleted assignments can only be restored, which goes through
+        // BaseAssignment
+        if base.deleted {
+            return Err(Error::DatabaseEntryNotFound);
+        }
+        match base.assignment_type {
+            AssignmentType::Grade => {
+                GradeAssignment::get(connection, course, url).map(Assignment::Grade)
+            }
+            AssignmentType::Point => {
+                PointAssignment::get(connection, course, url).map(Assignment::Point)
+            }
+            AssignmentType::PassFail => {
+                PassFailAssignment::get(connection, course, url).map(Assignment::PassFail)
+            }
+            AssignmentType::Counter => {
+                CounterAssignment::get(connection, course, url).map(Assignment::Counter)
+            }
+        }
+    }
+
+    /// Creates the same assignment in another course, with its dates moved by
+    /// the given number of years. Progress and publishing are not copied.
+    pub fn copy_to(
+        &self,
+        connection: &mut Connection,
+        course: CourseId,
+        years: i32,
+    ) -> Result<(), Error> {
+        let base = self.base();
+        match self {
+            Assignment::Grade(_) => {
+                GradeAssignment::create(connection, course, &base.name, &base.url)
+            }
+            Assignment::Point(assignment) => PointAssignment::create(
+                connection,
+                course,
+                &base.name,
+                &base.url,
+                assignment.data.max_points,
+            ),
+            Assignment::PassFail(_) => {
+                PassFailAssignment::create(connection, course, &base.name, &base.url)
+            }
+            Assignment::Counter(assignment) => CounterAssignment::create(
+                connection,
+                course,
+                &base.name,
+                &base.url,
+                assignment.data.max_count,
+            ),
+        }?;
+
+        let months = Months::new(12 * years.unsigned_abs());
+        let shift = |date: NaiveDateTime| {
+            if years < 0 {
+                date.checked_sub_months(months)
+            } else {
+                date.checked_add_months(months)
+            }
+        };
+
+        BaseAssignment::get_by_url(connection, course, &base.url)?.update_schedule(
+            connection,
+            base.description.as_deref(),
+            base.opens_at.and_then(shift),
+            base.due_at.and_then(shift),
+        )
+    }
+
+    pub fn base(&self) -> &BaseAssignment {
+        match self {
+            Assignment::Grade(assignment) => &assignment.base,
+            Assignment::Point(assignment) => &assignment.base,
+            Assignment::PassFail(assignment) => &assignment.base,
+            Assignment::Counter(assignment) => &assignment.base,
+        }
+    }
+
+    /// Sends `grade.changed` for every grade of the assignment, grades given
+    /// before it's published are sent this way when it's published
+    pub fn trigger_grades_changed(&self, connection: &mut Connection) -> Result<(), Error> {
+        let grades: Vec<GradeChanged> = match self {
+            Assignment::Grade(assignment) => grade_assignments_progress::table
+                .filter(grade_assignments_progress::assignment.eq(assignment.data.id))
+                .select((
+                    grade_assignments_progress::student,
+                    grade_assignments_progress::graded_by,
+                    GradeAssignmentGrade::as_select(),
+                ))
+                .load::<(UserId, Option<UserId>, GradeAssignmentGrade)>(connection)?
+                .into_iter()
+                .map(|(student, graded_by, grade)| GradeChanged {
+                    student,
+                    graded_by,
+                    grade: Some(grade),
+                    ..GradeChanged::default()
+                })
+                .collect(),
+            Assignment::Point(assignment) => point_assignments_progress::table
+                .filter(point_assignments_progress::assignment.eq(assignment.data.id))
+                .select((
+                    point_assignments_progress::student,
+                    point_assignments_progress::graded_by,
+                    point_assignments_progress::points,
+                ))
+                .load::<(UserId, Option<UserId>, u32)>(connection)?
+                .into_iter()
+                .map(|(student, graded_by, points)| GradeChanged {
+                    student,
+                    graded_by,
+                    points: Some(points),
+                    ..GradeChanged::default()
+                })
+                .collect(),
+            Assignment::PassFail(assignment) => pass_fail_assignments_progress::table
+                .filter(pass_fail_assignments_progress::assignment.eq(assignment.data.id))
+                .select((
+                    pass_fail_assignments_progress::student,
+                    pass_fail_assignments_progress::graded_by,
+                    pass_fail_assignments_progress::passed,
+                ))
+                .load::<(UserId, Option<UserId>, bool)>(connection)?
+                .into_iter()
+                .map(|(student, graded_by, passed)| GradeChanged {
+                    student,
+                    graded_by,
+                    passed: Some(passed),
+                    ..GradeChanged::default()
+                })
+                .collect(),
+            Assignment::Counter(assignment) => counter_assignments_progress::table
+                .filter(counter_assignments_progress::assignment.eq(assignment.data.id))
+                .select((
+                    counter_assignments_progress::student,
+                    counter_assignments_progress::graded_by,
+                    counter_assignments_progress::count,
+                ))
+                .load::<(UserId, Option<UserId>, u32)>(connection)?
+                .into_iter()
+                .map(|(student, graded_by, count)| GradeChanged {
+                    student,
+                    graded_by,
+                    count: Some(count),
+                    ..GradeChanged::default()
+                })
+                .collect(),
+        };
+
+        let base = self.base();
+        for grade in grades {
+            webhook::trigger(
+                connection,
+                WebhookEvent::GradeChanged,
+                &GradeChanged {
+                    course: base.course,
+                    assignment: base.id,
+                    ..grade
+                },
+            )?;
+        }
+
+        Ok(())
     }
 }
 pub struct Assignments(pub Vec<Assignment>);
@@ -285,6 +1037,20 @@ impl Assignments {
             .map(|a| a.into_iter().map(Assignment::Point).collect())
             .map_err(Error::from)?;
 
+        let mut pass_fail_assignments = pass_fail_assignments::table
+            .inner_join(assignments::table)
+            .filter(assignments::course.eq(course))
+            .load::<PassFailAssignment>(connection)
+            .map(|a| a.into_iter().map(Assignment::PassFail).collect())
+            .map_err(Error::from)?;
+
+        let mut counter_assignments = counter_assignments::table
+            .inner_join(assignments::table)
+            .filter(assignments::course.eq(course))
+            .load::<CounterAssignment>(connection)
+            .map(|a| a.into_iter().map(Assignment::Counter).collect())
+            .map_err(Error::from)?;
+
         grade_assignments::table
             .inner_join(assignments::table)
             .filter(assignments::course.eq(course))
@@ -293,15 +1059,31 @@ impl Assignments {
             .map_err(Error::from)
             .map(|mut a: Vec<Assignment>| {
                 a.append(&mut point_assignments);
+                a.append(&mut pass_fail_assignments);
+                a.append(&mut counter_assignments);
+                a.sort_by_key(|a| a.base().position);
                 Assignments { 0: a }
             })
     }
 }
 
+pub const GRADE_MAJOR_MIN: u8 = 5;
 pub const GRADE_MAJOR_MAX: u8 = 10;
+pub const GRADE_MAJOR_PASSING: u8 = 6;
 pub const GRADE_MINOR_MAX: u8 = 99;
 
-#[derive(Serialize, Debug, Selectable, Queryable, Clone, FromForm, PartialEq, Default)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Selectable,
+    Queryable,
+    Clone,
+    FromForm,
+    PartialEq,
+    Default,
+    ToSchema,
+)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 #[diesel(table_name = grade_assignments_progress)]
 pub struct GradeAssignmentGrade {
@@ -313,7 +1095,7 @@ pub struct GradeAssignmentGrade {
     pub minor: u8,
 }
 
-#[derive(Serialize, Debug, Selectable, Queryable)]
+#[derive(Serialize, Debug, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct GradedGradeAssignment {
     #[serde(flatten)]
@@ -324,7 +1106,7 @@ pub struct GradedGradeAssignment {
     pub grade: Option<GradeAssignmentGrade>,
 }
 
-#[derive(Serialize, Debug, Selectable, Queryable)]
+#[derive(Serialize, Debug, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct GradedPointAssignment {
     #[serde(flatten)]
@@ -335,10 +1117,76 @@ pub struct GradedPointAssignment {
     pub points: Option<u32>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Selectable, Queryable, ToSchema)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct GradedPassFailAssignment {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub assignment: PassFailAssignment,
+    #[diesel(select_expression = pass_fail_assignments_progress::passed.nullable())]
+    #[diesel(select_expression_type = diesel::dsl::Nullable<pass_fail_assignments_progress::passed>)]
+    pub passed: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Selectable, Queryable, ToSchema)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct GradedCounterAssignment {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub assignment: CounterAssignment,
+    #[diesel(select_expression = counter_assignments_progress::count.nullable())]
+    #[diesel(select_expression_type = diesel::dsl::Nullable<counter_assignments_progress::count>)]
+    pub count: Option<u32>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
 pub enum GradedAssignment {
     Grade(GradedGradeAssignment),
     Point(GradedPointAssignment),
+    PassFail(GradedPassFailAssignment),
+    Counter(GradedCounterAssignment),
+}
+
+impl GradedAssignment {
+    pub fn base(&self) -> &BaseAssignment {
+        match self {
+            GradedAssignment::Grade(graded) => &graded.assignment.base,
+            GradedAssignment::Point(graded) => &graded.assignment.base,
+            GradedAssignment::PassFail(graded) => &graded.assignment.base,
+            GradedAssignment::Counter(graded) => &graded.assignment.base,
+        }
+    }
+
+    /// Drops the grade while the assignment is still being graded, so
+    /// students only see it once it's published
+    fn hide_unpublished(self) -> Self {
+        if self.base().published {
+            return self;
+        }
+
+        match self {
+            GradedAssignment::Grade(graded) => GradedAssignment::Grade(GradedGradeAssignment {
+                grade: None,
+                ..graded
+            }),
+            GradedAssignment::Point(graded) => GradedAssignment::Point(GradedPointAssignment {
+                points: None,
+                ..graded
+            }),
+            GradedAssignment::PassFail(graded) => {
+                GradedAssignment::PassFail(GradedPassFailAssignment {
+                    passed: None,
+                    ..graded
+                })
+            }
+            GradedAssignment::Counter(graded) => {
+                GradedAssignment::Counter(GradedCounterAssignment {
+                    count: None,
+                    ..graded
+                })
+            }
+        }
+    }
 }
 
 pub struct GradedAssignments(pub Vec<GradedAssignment>);
@@ -366,6 +1214,40 @@ impl GradedAssignments {
             .map(|a| a.into_iter().map(GradedAssignment::Point).collect())
             .map_err(Error::from)?;
 
+        let mut pass_fail_assignments: Vec<GradedAssignment> = pass_fail_assignments::table
+            .left_join(
+                pass_fail_assignments_progress::table.on(pass_fail_assignments::id
+                    .eq(pass_fail_assignments_progress::assignment)
+                    .and(pass_fail_assignments_progress::student.eq(student))),
+            )
+            .inner_join(assignments::table)
+            .filter(
+                assignments::course
+                    .eq(course)
+                    .and(assignments::deleted.eq(false)),
+            )
+            .select(GradedPassFailAssignment::as_select())
+            .load::<GradedPassFailAssignment>(connection)
+            .map(|a| a.into_iter().map(GradedAssignment::PassFail).collect())
+            .map_err(Error::from)?;
+
+        let mut counter_assignments: Vec<GradedAssignment> = counter_assignments::table
+            .left_join(
+                counter_assignments_progress::table.on(counter_assignments::id
+                    .eq(counter_assignments_progress::assignment)
+                    .and(counter_assignments_progress::student.eq(student))),
+            )
+            .inner_join(assignments::table)
+            .filter(
+                assignments::course
+                    .eq(course)
+                    .and(assignments::deleted.eq(false)),
+            )
+            .select(GradedCounterAssignment::as_select())
+            .load::<GradedCounterAssignment>(connection)
+            .map(|a| a.into_iter().map(GradedAssignment::Counter).collect())
+            .map_err(Error::from)?;
+
         grade_assignments::table
             .inner_join(assignments::table)
             .left_join(
@@ -384,13 +1266,282 @@ impl GradedAssignments {
             .map_err(Error::from)
             .map(|mut a: Vec<GradedAssignment>| {
                 a.append(&mut point_assignments);
-                GradedAssignments { 0: a }
+                a.append(&mut pass_fail_assignments);
+                a.append(&mut counter_assignments);
+                a.sort_by_key(|a| a.base().position);
+                GradedAssignments {
+                    0: a.into_iter()
+                        .map(GradedAssignment::hide_unpublished)
+                        .collect(),
+                }
             })
     }
 }
 
-#[derive(Serialize, Debug, FromFormField, Clone)]
-pub enum AssignmentType {
-    Grade,
-    Point,
+type RevisionRow<T> = (T, Option<String>, Option<NaiveDateTime>);
+
+#[derive(Serialize, Debug)]
+pub struct GradeRevision<T> {
+    pub value: T,
+    /// Email of the user who entered the grade
+    pub graded_by: Option<String>,
+    pub graded_on: Option<NaiveDateTime>,
+}
+
+impl<T> From<RevisionRow<T>> for GradeRevision<T> {
+    fn from((value, graded_by, graded_on): RevisionRow<T>) -> Self {
+        Self {
+            value,
+            graded_by,
+            graded_on,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub enum GradeHistory {
+    Grade(Vec<GradeRevision<GradeAssignmentGrade>>),
+    Point(Vec<GradeRevision<u32>>),
+    PassFail(Vec<GradeRevision<bool>>),
+    Counter(Vec<GradeRevision<u32>>),
+}
+
+impl GradeHistory {
+    /// Every grade the student has had on the assignment, oldest first and
+    /// ending with the current one
+    pub fn get(
+        connection: &mut Connection,
+        assignment: &Assignment,
+        student: UserId,
+    ) -> Result<Self, Error> {
+        match assignment {
+            Assignment::Grade(assignment) => Self::get_grade(connection, assignment, student),
+            Assignment::Point(assignment) => Self::get_point(connection, assignment, student),
+            Assignment::PassFail(assignment) => {
+                Self::get_pass_fail(connection, assignment, student)
+            }
+            Assignment::Counter(assignment) => Self::get_counter(connection, assignment, student),
+        }
+    }
+
+    fn get_grade(
+        connection: &mut Connection,
+        assignment: &GradeAssignment,
+        student: UserId,
+    ) -> Result<Self, Error> {
+        let mut revisions = grade_assignments_progress_revisions::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(grade_assignments_progress_revisions::graded_by)),
+            )
+            .filter(
+                grade_assignments_progress_revisions::assignment
+                    .eq(assignment.data.id)
+                    .and(grade_assignments_progress_revisions::student.eq(student)),
+            )
+            .order(grade_assignments_progress_revisions::revision.asc())
+            .select((
+                (
+                    grade_assignments_progress_revisions::grade_major,
+                    grade_assignments_progress_revisions::grade_minor,
+                ),
+                users::email.nullable(),
+                grade_assignments_progress_revisions::graded_on,
+            ))
+            .load::<RevisionRow<(u8, u8)>>(connection)
+            .map_err(Error::from)?;
+
+        let current = grade_assignments_progress::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(grade_assignments_progress::graded_by)),
+            )
+            .filter(
+                grade_assignments_progress::assignment
+                    .eq(assignment.data.id)
+                    .and(grade_assignments_progress::student.eq(student)),
+            )
+            .select((
+                (
+                    grade_assignments_progress::grade_major,
+                    grade_assignments_progress::grade_minor,
+                ),
+                users::email.nullable(),
+                grade_assignments_progress::graded_on,
+            ))
+            .first::<RevisionRow<(u8, u8)>>(connection)
+            .optional()
+            .map_err(Error::from)?;
+
+        revisions.extend(current);
+
+        Ok(GradeHistory::Grade(
+            revisions
+                .into_iter()
+                .map(|((major, minor), graded_by, graded_on)| GradeRevision {
+                    value: GradeAssignmentGrade { major, minor },
+                    graded_by,
+                    graded_on,
+                })
+                .collect(),
+        ))
+    }
+
+    fn get_point(
+        connection: &mut Connection,
+        assignment: &PointAssignment,
+        student: UserId,
+    ) -> Result<Self, Error> {
+        let mut revisions = point_assignments_progress_revisions::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(point_assignments_progress_revisions::graded_by)),
+            )
+            .filter(
+                point_assignments_progress_revisions::assignment
+                    .eq(assignment.data.id)
+                    .and(point_assignments_progress_revisions::student.eq(student)),
+            )
+            .order(point_assignments_progress_revisions::revision.asc())
+            .select((
+                point_assignments_progress_revisions::points,
+                users::email.nullable(),
+                point_assignments_progress_revisions::graded_on,
+            ))
+            .load::<RevisionRow<u32>>(connection)
+            .map_err(Error::from)?;
+
+        let current = point_assignments_progress::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(point_assignments_progress::graded_by)),
+            )
+            .filter(
+                point_assignments_progress::assignment
+                    .eq(assignment.data.id)
+                    .and(point_assignments_progress::student.eq(student)),
+            )
+            .select((
+                point_assignments_progress::points,
+                users::email.nullable(),
+                point_assignments_progress::graded_on,
+            ))
+            .first::<RevisionRow<u32>>(connection)
+            .optional()
+            .map_err(Error::from)?;
+
+        revisions.extend(current);
+
+        Ok(GradeHistory::Point(
+            revisions.into_iter().map(GradeRevision::from).collect(),
+        ))
+    }
+
+    fn get_pass_fail(
+        connection: &mut Connection,
+        assignment: &PassFailAssignment,
+        student: UserId,
+    ) -> Result<Self, Error> {
+        let mut revisions = pass_fail_assignments_progress_revisions::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(pass_fail_assignments_progress_revisions::graded_by)),
+            )
+            .filter(
+                pass_fail_assignments_progress_revisions::assignment
+                    .eq(assignment.data.id)
+                    .and(pass_fail_assignments_progress_revisions::student.eq(student)),
+            )
+            .order(pass_fail_assignments_progress_revisions::revision.asc())
+            .select((
+                pass_fail_assignments_progress_revisions::passed,
+                users::email.nullable(),
+                pass_fail_assignments_progress_revisions::graded_on,
+            ))
+            .load::<RevisionRow<bool>>(connection)
+            .map_err(Error::from)?;
+
+        let current = pass_fail_assignments_progress::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(pass_fail_assignments_progress::graded_by)),
+            )
+            .filter(
+                pass_fail_assignments_progress::assignment
+                    .eq(assignment.data.id)
+                    .and(pass_fail_assignments_progress::student.eq(student)),
+            )
+            .select((
+                pass_fail_assignments_progress::passed,
+                users::email.nullable(),
+                pass_fail_assignments_progress::graded_on,
+            ))
+            .first::<RevisionRow<bool>>(connection)
+            .optional()
+            .map_err(Error::from)?;
+
+        revisions.extend(current);
+
+        Ok(GradeHistory::PassFail(
+            revisions.into_iter().map(GradeRevision::from).collect(),
+        ))
+    }
+
+    fn get_counter(
+        connection: &mut Connection,
+        assignment: &CounterAssignment,
+        student: UserId,
+    ) -> Result<Self, Error> {
+        let mut revisions = counter_assignments_progress_revisions::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(counter_assignments_progress_revisions::graded_by)),
+            )
+            .filter(
+                counter_assignments_progress_revisions::assignment
+                    .eq(assignment.data.id)
+                    .and(counter_assignments_progress_revisions::student.eq(student)),
+            )
+            .order(counter_assignments_progress_revisions::revision.asc())
+            .select((
+                counter_assignments_progress_revisions::count,
+                users::email.nullable(),
+                counter_assignments_progress_revisions::graded_on,
+            ))
+            .load::<RevisionRow<u32>>(connection)
+            .map_err(Error::from)?;
+
+        let current = counter_assignments_progress::table
+            .left_join(
+                users::table.on(users::id
+                    .nullable()
+                    .eq(counter_assignments_progress::graded_by)),
+            )
+            .filter(
+                counter_assignments_progress::assignment
+                    .eq(assignment.data.id)
+                    .and(counter_assignments_progress::student.eq(student)),
+            )
+            .select((
+                counter_assignments_progress::count,
+                users::email.nullable(),
+                counter_assignments_progress::graded_on,
+            ))
+            .first::<RevisionRow<u32>>(connection)
+            .optional()
+            .map_err(Error::from)?;
+
+        revisions.extend(current);
+
+        Ok(GradeHistory::Counter(
+            revisions.into_iter().map(GradeRevision::from).collect(),
+        ))
+    }
 }
@@ -11,7 +11,7 @@ impl BaseLayoutContext {
     pub async fn new(script: Script, user: &User) -> Result<Self, Error> {
         Ok(Self {
             user: user.clone(),
-            script
+            script,
         })
     }
 }
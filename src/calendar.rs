@@ -0,0 +1,256 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use rand::{Fill, SeedableRng};
+
+use crate::{
+    assignment::Deadline,
+    course::Course,
+    database::Connection,
+    error::Error,
+    exam::{ExamPeriod, ExamPeriodId},
+    localization::Script,
+    schema::{calendar_tokens, users},
+    user::UserId,
+};
+
+const LINE_LENGTH_LIMIT: usize = 75;
+
+#[derive(Clone, Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = calendar_tokens)]
+pub struct CalendarToken {
+    pub user: UserId,
+    pub token: Vec<u8>,
+    pub script: String,
+    pub created_on: NaiveDateTime,
+}
+
+impl CalendarToken {
+    pub fn get(connection: &mut Connection, user: UserId) -> Result<Option<Self>, Error> {
+        calendar_tokens::table
+            .filter(calendar_tokens::user.eq(user))
+            .first(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// Tokens of deleted users don't serve feeds anymore
+    pub fn get_by_token(connection: &mut Connection, token: &[u8]) -> Result<Self, Error> {
+        calendar_tokens::table
+            .inner_join(users::table)
+            .filter(calendar_tokens::token.eq(token))
+            .filter(users::deleted.eq(false))
+            .select(CalendarToken::as_select())
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Gives the user a new token, which stops every subscription made with
+    /// the old one
+    pub fn regenerate(
+        connection: &mut Connection,
+        user: UserId,
+        script: &Script,
+    ) -> Result<Self, Error> {
+        let mut token = [0u8; 32];
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        token.try_fill(&mut rng).map_err(Error::from)?;
+
+        let calendar_token = CalendarToken {
+            user,
+            token: token.to_vec(),
+            script: script.code().to_owned(),
+            created_on: Utc::now().naive_utc(),
+        };
+
+        diesel::replace_into(calendar_tokens::table)
+            .values(&calendar_token)
+            .execute(connection)
+            .map_err(Error::from)?;
+
+        Ok(calendar_token)
+    }
+
+    pub fn script(&self) -> Script {
+        Script::from_code(&self.script).unwrap_or(Script::Latin)
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.ics", hex::encode(&self.token))
+    }
+}
+
+/// Builds an RFC 5545 calendar with an event for every opening and due date,
+/// and one for every exam registration period listing the registered exams
+pub fn feed(
+    deadlines: &[Deadline],
+    exam_periods: &[ExamPeriod],
+    registrations: &[(ExamPeriodId, Course)],
+    script: &Script,
+) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//e-Student//Calendar//SR".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "METHOD:PUBLISH".to_owned(),
+        format!("X-WR-CALNAME:{}", escape_text("e-Student")),
+    ];
+
+    for deadline in deadlines {
+        let assignment = &deadline.assignment;
+        let events = [
+            ("opens", script.localize("Отварање"), assignment.opens_at),
+            ("due", script.localize("Рок"), assignment.due_at),
+        ];
+
+        for (kind, caption, date) in events {
+            let Some(date) = date else {
+                continue;
+            };
+
+            lines.push("BEGIN:VEVENT".to_owned());
+            lines.push(format!("UID:assignment-{}-{kind}@e-student", assignment.id));
+            lines.push(format!("DTSTAMP:{stamp}"));
+            // Dates are kept in local time, so they're written as floating times
+            lines.push(format!("DTSTART:{}", date.format("%Y%m%dT%H%M%S")));
+            lines.push(format!(
+                "SUMMARY:{}",
+                escape_text(&format!(
+                    "{caption}: {} ({})",
+                    script.localize(&assignment.name),
+                    script.localize(&deadline.course.name)
+                ))
+            ));
+            if let Some(description) = &assignment.description {
+                lines.push(format!(
+                    "DESCRIPTION:{}",
+                    escape_text(&script.localize(description))
+                ));
+            }
+            lines.push("END:VEVENT".to_owned());
+        }
+    }
+
+    for exam_period in exam_periods {
+        let registered: Vec<_> = registrations
+            .iter()
+            .filter(|(registered_in, _)| *registered_in == exam_period.id)
+            .map(|(_, course)| script.localize(&course.name))
+            .collect();
+
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:exam-period-{}@e-student", exam_period.id));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!(
+            "DTSTART:{}",
+            exam_period.registration_opens.format("%Y%m%dT%H%M%S")
+        ));
+        lines.push(format!(
+            "DTEND:{}",
+            exam_period.registration_closes.format("%Y%m%dT%H%M%S")
+        ));
+        lines.push(format!(
+            "SUMMARY:{}",
+            escape_text(&format!(
+                "{}: {}",
+                script.localize("Пријава испита"),
+                script.localize(&exam_period.name)
+            ))
+        ));
+        if !registered.is_empty() {
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                escape_text(&format!(
+                    "{}: {}",
+                    script.localize("Пријављени испити"),
+                    registered.join(", ")
+                ))
+            ));
+        }
+        lines.push("END:VEVENT".to_owned());
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits lines longer than 75 octets, continuation lines start with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_text, fold_line, LINE_LENGTH_LIMIT};
+
+    #[test]
+    fn escapes_separators_and_newlines() {
+        assert_eq!(escape_text("a;b,c\\d\ne\r\nf"), "a\\;b\\,c\\\\d\\ne\\nf");
+    }
+
+    #[test]
+    fn leaves_short_lines_alone() {
+        let line = "a".repeat(LINE_LENGTH_LIMIT);
+        assert_eq!(fold_line(&line), line);
+    }
+
+    #[test]
+    fn folds_at_75_octets() {
+        let folded = fold_line(&"a".repeat(LINE_LENGTH_LIMIT + 1));
+        assert_eq!(folded, format!("{}\r\n a", "a".repeat(LINE_LENGTH_LIMIT)));
+    }
+
+    #[test]
+    fn never_splits_multibyte_characters() {
+        // 74 octets leave room for only one more, so the two octet "ш" moves
+        // to the continuation line whole
+        let line = format!("{}шш", "a".repeat(LINE_LENGTH_LIMIT - 1));
+        let folded = fold_line(&line);
+        assert_eq!(
+            folded,
+            format!("{}\r\n шш", "a".repeat(LINE_LENGTH_LIMIT - 1))
+        );
+
+        for folded_line in folded.split("\r\n") {
+            assert!(folded_line.len() <= LINE_LENGTH_LIMIT);
+        }
+    }
+
+    #[test]
+    fn continuation_lines_count_their_leading_space() {
+        let folded = fold_line(&"ш".repeat(LINE_LENGTH_LIMIT));
+        let lines: Vec<_> = folded.split("\r\n").collect();
+
+        assert!(lines.len() > 1);
+        for line in &lines {
+            assert!(line.len() <= LINE_LENGTH_LIMIT);
+        }
+        assert_eq!(
+            lines.concat().replace(' ', ""),
+            "ш".repeat(LINE_LENGTH_LIMIT)
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use rocket::{catch, response::Redirect, Request, Responder};
 use rocket_dyn_templates::{context, Template};
 
-use crate::user::User;
+use crate::{course::Course, database::Database, user::User};
 
 #[derive(Responder)]
 pub enum TemplateOrRedirect {
@@ -11,9 +11,41 @@ pub enum TemplateOrRedirect {
 
 #[catch(404)]
 pub async fn not_found(req: &Request<'_>) -> TemplateOrRedirect {
+    if let Some(redirect) = moved_course(req).await {
+        return TemplateOrRedirect::Redirect(redirect);
+    }
+
     if req.guard::<&User>().await.is_success() {
         TemplateOrRedirect::Template(Template::render("catchers/not_found", context!()))
     } else {
         TemplateOrRedirect::Redirect(Redirect::to("/login"))
     }
 }
+
+/// Sends requests for the URL a course had before it was renamed to the
+/// same page under its current URL
+async fn moved_course(req: &Request<'_>) -> Option<Redirect> {
+    let path = req.uri().path();
+    let rest = path.as_str().strip_prefix("/course/")?;
+    let (old_url, rest) = rest.split_once('/').unwrap_or((rest, ""));
+
+    let database = req.guard::<Database>().await.succeeded()?;
+    let old_url = old_url.to_owned();
+    let course = database
+        .run(move |c| Course::get_by_old_url(c, &old_url))
+        .await
+        .ok()?;
+
+    let mut location = format!("/course/{}", course.url);
+    if !rest.is_empty() {
+        location.push('/');
+        location.push_str(rest);
+    }
+    if let Some(query) = req.uri().query() {
+        location.push('?');
+        location.push_str(query.as_str());
+    }
+
+    // Temporary, so the same URL is still free for a course created later
+    Some(Redirect::temporary(location))
+}
@@ -3,13 +3,18 @@ use serde::Serialize;
 
 use crate::{
     assignment::GradeAssignmentGrade,
+    course::CourseId,
     database::{Database, SortDirection},
     error::Error,
+    exam::ExamPeriodId,
     index::IndexNumber,
+    student_status::StudentStatus,
     user::{
-        AccountType, UserId, UserWithIndex, UserWithIndexAndEnrolment,
-        UserWithIndexAndGradeProgress, UserWithIndexAndPointProgress, UsersRetrievalOptions,
-        UsersWithIndex, UsersWithIndexAndEnrolment, UsersWithIndexAndGradeProgress,
+        AccountType, RetrievalFilters, UserId, UserWithIndex, UserWithIndexAndCounterProgress,
+        UserWithIndexAndEnrolment, UserWithIndexAndGradeProgress, UserWithIndexAndPassFailProgress,
+        UserWithIndexAndPointProgress, UsersRetrievalOptions, UsersWithIndex,
+        UsersWithIndexAndCounterProgress, UsersWithIndexAndEnrolment,
+        UsersWithIndexAndGradeProgress, UsersWithIndexAndPassFailProgress,
         UsersWithIndexAndPointProgress,
     },
 };
@@ -34,12 +39,24 @@ pub struct GradeProgressData {
     users: Vec<UserWithIndexAndGradeProgress>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct PassFailProgressData {
+    users: Vec<UserWithIndexAndPassFailProgress>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CounterProgressData {
+    users: Vec<UserWithIndexAndCounterProgress>,
+}
+
 #[derive(Serialize, Debug)]
 pub enum ControlType {
     Edit(EditData),
     Enrol(EnrolData),
     PointProgress(PointProgressData),
     GradeProgress(GradeProgressData),
+    PassFailProgress(PassFailProgressData),
+    CounterProgress(CounterProgressData),
 }
 
 #[derive(Serialize, Debug)]
@@ -92,6 +109,8 @@ where
 
 pub type PointProgress = UserValueDifference<Option<u32>>;
 pub type GradeProgress = UserValueDifference<Option<GradeAssignmentGrade>>;
+pub type PassFailProgress = UserValueDifference<Option<bool>>;
+pub type CounterProgress = UserValueDifference<Option<u32>>;
 pub type EnrolDropdown = UserValueDifference<bool>;
 
 #[derive(Serialize, FromForm, Debug, Clone)]
@@ -104,6 +123,9 @@ pub struct FormData {
     filter_index_number: Option<IndexNumber>,
     filter_program: String,
     filter_generation: Option<u32>,
+    filter_student_status_enabled: bool,
+    filter_student_status: StudentStatus,
+    filter_deleted: bool,
 
     sort_first_name: FormSortDirection,
     sort_last_name: FormSortDirection,
@@ -117,9 +139,31 @@ pub struct FormData {
     enrol_dropdowns: Vec<EnrolDropdown>,
     point_progresses: Vec<PointProgress>,
     grade_progresses: Vec<GradeProgress>,
+    pass_fail_progresses: Vec<PassFailProgress>,
+    counter_progresses: Vec<CounterProgress>,
 }
 
 impl FormData {
+    /// The filters entered in the form, deleted users are never included
+    pub fn retrieval_filters(&self) -> RetrievalFilters {
+        let mut filters = RetrievalFilters::new();
+
+        filters.filter_email = Some(self.filter_email.clone()).filter(|s| !s.is_empty());
+
+        filters.filter_account_type =
+            Some(self.filter_account_type).filter(|_| self.filter_account_type_enabled);
+
+        filters.filter_first_name = Some(self.filter_first_name.clone()).filter(|s| !s.is_empty());
+        filters.filter_last_name = Some(self.filter_last_name.clone()).filter(|s| !s.is_empty());
+        filters.filter_program = Some(self.filter_program.clone()).filter(|s| !s.is_empty());
+        filters.filter_index_number = self.filter_index_number;
+        filters.filter_generation = self.filter_generation;
+        filters.filter_student_status =
+            Some(self.filter_student_status).filter(|_| self.filter_student_status_enabled);
+
+        filters
+    }
+
     pub fn enrol_dropdowns(&self) -> &Vec<EnrolDropdown> {
         self.enrol_dropdowns.as_ref()
     }
@@ -129,6 +173,17 @@ impl FormData {
     pub fn grade_progresses(&self) -> &Vec<GradeProgress> {
         self.grade_progresses.as_ref()
     }
+    pub fn pass_fail_progresses(&self) -> &Vec<PassFailProgress> {
+        self.pass_fail_progresses.as_ref()
+    }
+    pub fn counter_progresses(&self) -> &Vec<CounterProgress> {
+        self.counter_progresses.as_ref()
+    }
+}
+
+pub struct EditOptions {
+    /// Lists only the department's users
+    pub department: Option<u32>,
 }
 
 pub struct EnrolOptions {
@@ -143,11 +198,60 @@ pub struct GradeProgressOptions {
     pub assignment: u32,
 }
 
+pub struct PassFailProgressOptions {
+    pub assignment: u32,
+}
+
+pub struct CounterProgressOptions {
+    pub assignment: u32,
+}
+
 pub enum ControlTypeOptions {
-    Edit,
+    Edit(EditOptions),
     Enrol(EnrolOptions),
     PointProgress(PointProgressOptions),
     GradeProgress(GradeProgressOptions),
+    PassFailProgress(PassFailProgressOptions),
+    CounterProgress(CounterProgressOptions),
+}
+
+/// The course and exam period of a graded assignment, progress lists only
+/// have the students registered for the exam of an exam-bound one
+async fn exam(
+    database: &Database,
+    control_type: &ControlTypeOptions,
+) -> Result<Option<(CourseId, Option<ExamPeriodId>)>, Error> {
+    match control_type {
+        ControlTypeOptions::Edit(_) | ControlTypeOptions::Enrol(_) => Ok(None),
+        ControlTypeOptions::PointProgress(settings) => {
+            let assignment = settings.assignment;
+            database
+                .run(move |c| UsersWithIndexAndPointProgress::exam(c, assignment))
+                .await
+                .map(Some)
+        }
+        ControlTypeOptions::GradeProgress(settings) => {
+            let assignment = settings.assignment;
+            database
+                .run(move |c| UsersWithIndexAndGradeProgress::exam(c, assignment))
+                .await
+                .map(Some)
+        }
+        ControlTypeOptions::PassFailProgress(settings) => {
+            let assignment = settings.assignment;
+            database
+                .run(move |c| UsersWithIndexAndPassFailProgress::exam(c, assignment))
+                .await
+                .map(Some)
+        }
+        ControlTypeOptions::CounterProgress(settings) => {
+            let assignment = settings.assignment;
+            database
+                .run(move |c| UsersWithIndexAndCounterProgress::exam(c, assignment))
+                .await
+                .map(Some)
+        }
+    }
 }
 
 const DEFAULT_USERS_PER_PAGE: u32 = 10;
@@ -161,20 +265,10 @@ impl LayoutContext {
         let mut options = UsersRetrievalOptions::new(0, DEFAULT_USERS_PER_PAGE);
 
         if let Some(form) = &form {
-            options.filters.filter_email =
-                Some(form.filter_email.clone()).filter(|s| !s.is_empty());
-
-            options.filters.filter_account_type =
-                Some(form.filter_account_type).filter(|_| form.filter_account_type_enabled);
-
-            options.filters.filter_first_name =
-                Some(form.filter_first_name.clone()).filter(|s| !s.is_empty());
-            options.filters.filter_last_name =
-                Some(form.filter_last_name.clone()).filter(|s| !s.is_empty());
-            options.filters.filter_program =
-                Some(form.filter_program.clone()).filter(|s| !s.is_empty());
-            options.filters.filter_index_number = form.filter_index_number;
-            options.filters.filter_generation = form.filter_generation;
+            options.filters = form.retrieval_filters();
+            // Only administrators managing accounts get to see deleted users
+            options.filters.filter_deleted =
+                form.filter_deleted && matches!(control_type, ControlTypeOptions::Edit(_));
 
             options.sorts.sort_by_first_name = (&form.sort_first_name).into();
             options.sorts.sort_by_last_name = (&form.sort_last_name).into();
@@ -186,14 +280,22 @@ impl LayoutContext {
             options.max_per_page = form.max_per_page;
         }
 
+        if matches!(control_type, ControlTypeOptions::Enrol(_)) {
+            options.filters = options.filters.active_students_by_default();
+        }
+        if let ControlTypeOptions::Edit(settings) = &control_type {
+            options.filters.filter_department = settings.department;
+        }
+
+        let exam = exam(&database, &control_type).await?;
         let filters = options.filters.clone();
         let max_per_page = options.max_per_page;
         let number_of_pages = database
-            .run(move |c| UsersWithIndex::get_number_of_pages(c, filters, max_per_page))
+            .run(move |c| UsersWithIndex::get_number_of_pages(c, filters, exam, max_per_page))
             .await?;
 
         let control_type = match control_type {
-            ControlTypeOptions::Edit => {
+            ControlTypeOptions::Edit(_) => {
                 let users = database
                     .run(move |c| UsersWithIndex::get(c, options))
                     .await?;
@@ -221,6 +323,22 @@ impl LayoutContext {
                     .await?;
                 ControlType::GradeProgress(GradeProgressData { users: users.0 })
             }
+            ControlTypeOptions::PassFailProgress(settings) => {
+                let users = database
+                    .run(move |c| {
+                        UsersWithIndexAndPassFailProgress::get(c, options, settings.assignment)
+                    })
+                    .await?;
+                ControlType::PassFailProgress(PassFailProgressData { users: users.0 })
+            }
+            ControlTypeOptions::CounterProgress(settings) => {
+                let users = database
+                    .run(move |c| {
+                        UsersWithIndexAndCounterProgress::get(c, options, settings.assignment)
+                    })
+                    .await?;
+                ControlType::CounterProgress(CounterProgressData { users: users.0 })
+            }
         };
 
         Ok(LayoutContext {
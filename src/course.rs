@@ -1,14 +1,34 @@
-use diesel::prelude::*;
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    prelude::*,
+    serialize::ToSql,
+    sql_types::{TinyInt, Unsigned},
+    AsExpression, FromSqlRow,
+};
+use rocket::form::FromFormField;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
 
 use crate::{
+    academic_year::AcademicYear,
+    assignment::Assignments,
+    curriculum::Curriculum,
     database::Connection,
     error::Error,
-    schema::{courses, enrolments},
-    user::{User, UserId},
+    schema::{
+        course_self_enrolment, course_staff, course_url_redirects, courses, enrolment_requests,
+        enrolments, indicies, users,
+    },
+    student_status::StudentStatus,
+    user::{AccountType, User, UserId},
+    util,
+    webhook::{self, WebhookEvent},
 };
 
-#[derive(Clone, Debug, Queryable, Selectable, Serialize)]
+#[derive(Clone, Debug, Queryable, Selectable, Serialize, ToSchema)]
 pub struct Enrolment {
     pub course: u32,
     pub student: u32,
@@ -25,9 +45,25 @@ impl Enrolment {
                 enrolments::course.eq(course),
                 enrolments::student.eq(student),
             ))
-            .execute(connection)
-            .map_err(Error::from)
-            .map(|_| ())
+            .execute(connection)?;
+
+        Self::created(connection, course, &[student])
+    }
+
+    /// Tells the webhooks about new enrolments
+    fn created(
+        connection: &mut Connection,
+        course: CourseId,
+        students: &[UserId],
+    ) -> Result<(), Error> {
+        for &student in students {
+            webhook::trigger(
+                connection,
+                WebhookEvent::EnrolmentCreated,
+                &Enrolment { course, student },
+            )?;
+        }
+        Ok(())
     }
 
     pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
@@ -58,19 +94,517 @@ impl Enrolment {
             .first(connection)
             .map_err(Error::from)
     }
+
+    /// Enrols those of the users who are active students and not enrolled
+    /// yet, returning how many were enrolled and how many were left out for
+    /// not being active
+    pub fn create_many(
+        connection: &mut Connection,
+        course: CourseId,
+        students: &[UserId],
+    ) -> Result<(usize, usize), Error> {
+        let (students, inactive): (Vec<_>, Vec<_>) = users::table
+            .filter(users::id.eq_any(students))
+            .filter(users::account_type.eq(AccountType::Student))
+            .filter(users::deleted.eq(false))
+            .select((users::id, users::student_status))
+            .load::<(UserId, StudentStatus)>(connection)?
+            .into_iter()
+            .partition(|(_, status)| *status == StudentStatus::Active);
+        let students: Vec<UserId> = students.into_iter().map(|(student, _)| student).collect();
+        let enrolled = enrolments::table
+            .filter(enrolments::course.eq(course))
+            .filter(enrolments::student.eq_any(&students))
+            .select(enrolments::student)
+            .load::<UserId>(connection)?;
+        let students: Vec<UserId> = students
+            .into_iter()
+            .filter(|student| !enrolled.contains(student))
+            .collect();
+
+        let enrolments: Vec<_> = students
+            .iter()
+            .map(|&student| {
+                (
+                    enrolments::course.eq(course),
+                    enrolments::student.eq(student),
+                )
+            })
+            .collect();
+
+        let created = diesel::insert_or_ignore_into(enrolments::table)
+            .values(&enrolments)
+            .execute(connection)?;
+
+        Self::created(connection, course, &students)?;
+        Ok((created, inactive.len()))
+    }
+
+    /// How many of the users are already enrolled in the course
+    pub fn count_enrolled(
+        connection: &mut Connection,
+        course: CourseId,
+        students: &[UserId],
+    ) -> Result<i64, Error> {
+        enrolments::table
+            .filter(enrolments::course.eq(course))
+            .filter(enrolments::student.eq_any(students))
+            .count()
+            .get_result(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn count(connection: &mut Connection, course: CourseId) -> Result<i64, Error> {
+        enrolments::table
+            .filter(enrolments::course.eq(course))
+            .count()
+            .get_result(connection)
+            .map_err(Error::from)
+    }
+
+    /// Un-enrols every student, returning how many there were
+    pub fn delete_all(connection: &mut Connection, course: CourseId) -> Result<usize, Error> {
+        diesel::delete(enrolments::table.filter(enrolments::course.eq(course)))
+            .execute(connection)
+            .map_err(Error::from)
+    }
+
+    /// Enrols every active student whose index belongs to one of the
+    /// programs and one of the generations
+    pub fn create_for_indices(
+        connection: &mut Connection,
+        course: CourseId,
+        programs: &[u32],
+        generations: &[u32],
+    ) -> Result<(), Error> {
+        let students = indicies::table
+            .inner_join(users::table)
+            .filter(indicies::program.eq_any(programs))
+            .filter(indicies::generation.eq_any(generations))
+            .filter(users::deleted.eq(false))
+            .filter(users::student_status.eq(StudentStatus::Active))
+            .select(indicies::student)
+            .distinct()
+            .load::<UserId>(connection)?;
+
+        let enrolments: Vec<_> = students
+            .iter()
+            .map(|&student| {
+                (
+                    enrolments::course.eq(course),
+                    enrolments::student.eq(student),
+                )
+            })
+            .collect();
+
+        diesel::insert_into(enrolments::table)
+            .values(&enrolments)
+            .execute(connection)?;
+
+        Enrolment::created(connection, course, &students)
+    }
 }
 
 pub type CourseId = u32;
 
-#[derive(Clone, Debug, Queryable, Selectable, Serialize, Identifiable)]
+/// What a member of the course staff is allowed to do. Owners manage the
+/// course itself and its staff, lecturers also manage assignments and
+/// enrolments, while assistants only grade.
+#[repr(u8)]
+#[derive(FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum CourseRole {
+    Owner = 0,
+    Lecturer = 1,
+    Assistant = 2,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for CourseRole
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid CourseRole value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for CourseRole
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::Owner => 0.to_sql(out),
+            Self::Lecturer => 1.to_sql(out),
+            Self::Assistant => 2.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for CourseRole {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CourseRole::Owner),
+            1 => Ok(CourseRole::Lecturer),
+            2 => Ok(CourseRole::Assistant),
+            _ => Err(Error::InvalidCourseRoleValue),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = course_staff)]
+pub struct CourseStaffMember {
+    pub course: CourseId,
+    pub user: UserId,
+    pub role: CourseRole,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CourseStaffMemberWithUser {
+    #[serde(flatten)]
+    pub member: CourseStaffMember,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+impl CourseStaffMember {
+    pub fn get_all(
+        connection: &mut Connection,
+        course: CourseId,
+    ) -> Result<Vec<CourseStaffMemberWithUser>, Error> {
+        course_staff::table
+            .inner_join(users::table)
+            .filter(course_staff::course.eq(course))
+            .order((course_staff::role.asc(), users::email.asc()))
+            .select((
+                CourseStaffMember::as_select(),
+                users::email,
+                users::first_name,
+                users::last_name,
+            ))
+            .load::<(CourseStaffMember, String, Option<String>, Option<String>)>(connection)
+            .map(|members| {
+                members
+                    .into_iter()
+                    .map(
+                        |(member, email, first_name, last_name)| CourseStaffMemberWithUser {
+                            member,
+                            email,
+                            first_name,
+                            last_name,
+                        },
+                    )
+                    .collect()
+            })
+            .map_err(Error::from)
+    }
+
+    pub fn get_role(
+        connection: &mut Connection,
+        course: CourseId,
+        user: UserId,
+    ) -> Result<Option<CourseRole>, Error> {
+        course_staff::table
+            .filter(course_staff::course.eq(course))
+            .filter(course_staff::user.eq(user))
+            .select(course_staff::role)
+            .first(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// Adds the user to the staff or changes their role. There is only one
+    /// owner, the one in `courses.professor`, so that role is set through
+    /// `Course::set_owner` instead.
+    pub fn set(
+        connection: &mut Connection,
+        course: CourseId,
+        user: UserId,
+        role: CourseRole,
+    ) -> Result<(), Error> {
+        diesel::replace_into(course_staff::table)
+            .values((
+                course_staff::course.eq(course),
+                course_staff::user.eq(user),
+                course_staff::role.eq(role),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn remove(
+        connection: &mut Connection,
+        course: CourseId,
+        user: UserId,
+    ) -> Result<(), Error> {
+        diesel::delete(
+            course_staff::table
+                .filter(course_staff::course.eq(course))
+                .filter(course_staff::user.eq(user))
+                .filter(course_staff::role.ne(CourseRole::Owner)),
+        )
+        .execute(connection)
+        .map(|_| ())
+        .map_err(Error::from)
+    }
+}
+
+/// How students can enrol themselves in a course
+#[repr(u8)]
+#[derive(FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum SelfEnrolmentMode {
+    Disabled = 0,
+    Open = 1,
+    Key = 2,
+    Approval = 3,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for SelfEnrolmentMode
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid SelfEnrolmentMode value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for SelfEnrolmentMode
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::Disabled => 0.to_sql(out),
+            Self::Open => 1.to_sql(out),
+            Self::Key => 2.to_sql(out),
+            Self::Approval => 3.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for SelfEnrolmentMode {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SelfEnrolmentMode::Disabled),
+            1 => Ok(SelfEnrolmentMode::Open),
+            2 => Ok(SelfEnrolmentMode::Key),
+            3 => Ok(SelfEnrolmentMode::Approval),
+            _ => Err(Error::InvalidSelfEnrolmentModeValue),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = course_self_enrolment)]
+pub struct SelfEnrolment {
+    pub course: CourseId,
+    pub mode: SelfEnrolmentMode,
+    /// Only the hash is kept, the key itself can't be shown again
+    #[serde(skip)]
+    pub enrolment_key_hash: Option<Vec<u8>>,
+}
+
+fn hash_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.trim().as_bytes()).to_vec()
+}
+
+impl SelfEnrolment {
+    /// Courses without a row don't allow self-enrolment
+    pub fn get(connection: &mut Connection, course: CourseId) -> Result<Self, Error> {
+        course_self_enrolment::table
+            .filter(course_self_enrolment::course.eq(course))
+            .first(connection)
+            .optional()
+            .map(|self_enrolment| {
+                self_enrolment.unwrap_or(SelfEnrolment {
+                    course,
+                    mode: SelfEnrolmentMode::Disabled,
+                    enrolment_key_hash: None,
+                })
+            })
+            .map_err(Error::from)
+    }
+
+    /// Without a new key the course keeps the one it has
+    pub fn set(
+        connection: &mut Connection,
+        course: CourseId,
+        mode: SelfEnrolmentMode,
+        enrolment_key: Option<&str>,
+    ) -> Result<(), Error> {
+        let enrolment_key_hash = match enrolment_key {
+            Some(key) => Some(hash_key(key)),
+            None => Self::get(connection, course)?.enrolment_key_hash,
+        };
+
+        diesel::replace_into(course_self_enrolment::table)
+            .values((
+                course_self_enrolment::course.eq(course),
+                course_self_enrolment::mode.eq(mode),
+                course_self_enrolment::enrolment_key_hash.eq(enrolment_key_hash),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn has_key(&self) -> bool {
+        self.enrolment_key_hash.is_some()
+    }
+
+    pub fn key_matches(&self, key: &str) -> bool {
+        self.enrolment_key_hash.as_deref() == Some(hash_key(key).as_slice())
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = enrolment_requests)]
+pub struct EnrolmentRequest {
+    pub course: CourseId,
+    pub student: UserId,
+    pub created: NaiveDateTime,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EnrolmentRequestWithUser {
+    #[serde(flatten)]
+    pub request: EnrolmentRequest,
+    pub email: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+impl EnrolmentRequest {
+    /// Asking again for the same course keeps the original request
+    pub fn create(
+        connection: &mut Connection,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<(), Error> {
+        diesel::insert_or_ignore_into(enrolment_requests::table)
+            .values((
+                enrolment_requests::course.eq(course),
+                enrolment_requests::student.eq(student),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn get_all(
+        connection: &mut Connection,
+        course: CourseId,
+    ) -> Result<Vec<EnrolmentRequestWithUser>, Error> {
+        enrolment_requests::table
+            .inner_join(users::table)
+            .filter(enrolment_requests::course.eq(course))
+            .filter(users::deleted.eq(false))
+            .order(enrolment_requests::created.asc())
+            .select((
+                EnrolmentRequest::as_select(),
+                users::email,
+                users::first_name,
+                users::last_name,
+            ))
+            .load::<(EnrolmentRequest, String, Option<String>, Option<String>)>(connection)
+            .map(|requests| {
+                requests
+                    .into_iter()
+                    .map(
+                        |(request, email, first_name, last_name)| EnrolmentRequestWithUser {
+                            request,
+                            email,
+                            first_name,
+                            last_name,
+                        },
+                    )
+                    .collect()
+            })
+            .map_err(Error::from)
+    }
+
+    /// Enrols the student and removes the request, returns
+    /// `DatabaseEntryNotFound` when there was no such request. The request
+    /// is kept and `false` returned when the student is no longer active or
+    /// the course is no longer in the active year, the same as with
+    /// enrolling without approval.
+    pub fn approve(
+        connection: &mut Connection,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<bool, Error> {
+        let active = AcademicYear::get_active(connection)?;
+        if active.map(|active| active.year) != Some(Course::get_by_id(connection, course)?.year)
+            || !User::get_by_id(connection, student)?.is_active_student()
+        {
+            return Ok(false);
+        }
+
+        Self::reject(connection, course, student)?;
+
+        let created = diesel::insert_or_ignore_into(enrolments::table)
+            .values((
+                enrolments::course.eq(course),
+                enrolments::student.eq(student),
+            ))
+            .execute(connection)?;
+
+        if created > 0 {
+            Enrolment::created(connection, course, &[student])?;
+        }
+        Ok(true)
+    }
+
+    pub fn reject(
+        connection: &mut Connection,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<(), Error> {
+        let deleted = diesel::delete(
+            enrolment_requests::table
+                .filter(enrolment_requests::course.eq(course))
+                .filter(enrolment_requests::student.eq(student)),
+        )
+        .execute(connection)?;
+
+        if deleted == 0 {
+            return Err(Error::DatabaseEntryNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize, Identifiable, ToSchema)]
 #[diesel(table_name = courses)]
 pub struct Course {
+    #[schema(value_type = u32)]
     pub id: CourseId,
     pub year: u32,
     pub name: String,
     pub url: String,
     pub professor: u32,
     pub deleted: bool,
+    pub ects: u32,
+    pub semester: Option<u8>,
+    pub department: Option<u32>,
+    /// The course this one was first rolled over from
+    #[schema(value_type = Option<u32>)]
+    pub original: Option<CourseId>,
 }
 
 #[derive(Insertable)]
@@ -80,21 +614,56 @@ pub struct NewCourse<'a> {
     pub name: &'a str,
     pub url: &'a str,
     pub professor: u32,
+    pub department: Option<u32>,
 }
 
 #[derive(Debug)]
 pub struct Courses(pub Vec<Course>);
 
 impl Courses {
-    pub fn get_all(connection: &mut Connection) -> Result<Self, Error> {
-        courses::table
+    /// Courses of the department, or every course without one
+    pub fn get_administered(
+        connection: &mut Connection,
+        department: Option<u32>,
+        deleted: bool,
+    ) -> Result<Self, Error> {
+        let mut query = courses::table
+            .filter(courses::deleted.eq(deleted))
+            .into_boxed();
+        if let Some(department) = department {
+            query = query.filter(courses::department.eq(department));
+        }
+
+        query
+            .load::<Course>(connection)
+            .map_err(Error::from)
+            .map(Courses)
+    }
+
+    /// Courses of the given year, or of the active one when no year is
+    /// given
+    pub fn get_enrolled(
+        connection: &mut Connection,
+        student: UserId,
+        year: Option<u32>,
+    ) -> Result<Self, Error> {
+        let mut query = courses::table
+            .inner_join(enrolments::table.on(enrolments::course.eq(courses::id)))
             .filter(courses::deleted.eq(false))
+            .filter(enrolments::student.eq(student))
+            .select(Course::as_select())
+            .into_boxed();
+        if let Some(year) = AcademicYear::year_or_active(connection, year)? {
+            query = query.filter(courses::year.eq(year));
+        }
+
+        query
             .load::<Course>(connection)
             .map_err(Error::from)
             .map(|c| Courses { 0: c })
     }
 
-    pub fn get_enrolled(connection: &mut Connection, student: UserId) -> Result<Self, Error> {
+    pub fn get_all_enrolled(connection: &mut Connection, student: UserId) -> Result<Self, Error> {
         courses::table
             .inner_join(enrolments::table.on(enrolments::course.eq(courses::id)))
             .filter(courses::deleted.eq(false))
@@ -105,10 +674,54 @@ impl Courses {
             .map(|c| Courses { 0: c })
     }
 
-    pub fn get_teaching(connection: &mut Connection, professor: UserId) -> Result<Self, Error> {
+    /// Courses of the year the students can enrol themselves in, with the
+    /// way they can do it
+    pub fn get_catalog(
+        connection: &mut Connection,
+        year: u32,
+    ) -> Result<Vec<(Course, SelfEnrolmentMode)>, Error> {
+        courses::table
+            .inner_join(course_self_enrolment::table)
+            .filter(courses::deleted.eq(false))
+            .filter(courses::year.eq(year))
+            .filter(course_self_enrolment::mode.ne(SelfEnrolmentMode::Disabled))
+            .order(courses::name.asc())
+            .select((Course::as_select(), course_self_enrolment::mode))
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    /// Courses the student asked to be enrolled in that nobody has approved
+    /// or rejected yet
+    pub fn get_requested(connection: &mut Connection, student: UserId) -> Result<Self, Error> {
         courses::table
+            .inner_join(enrolment_requests::table)
             .filter(courses::deleted.eq(false))
-            .filter(courses::professor.eq(professor))
+            .filter(enrolment_requests::student.eq(student))
+            .select(Course::as_select())
+            .load::<Course>(connection)
+            .map_err(Error::from)
+            .map(|c| Courses { 0: c })
+    }
+
+    /// Courses of the given year, or of the active one when no year is
+    /// given
+    pub fn get_teaching(
+        connection: &mut Connection,
+        professor: UserId,
+        year: Option<u32>,
+    ) -> Result<Self, Error> {
+        let mut query = courses::table
+            .inner_join(course_staff::table)
+            .filter(courses::deleted.eq(false))
+            .filter(course_staff::user.eq(professor))
+            .select(Course::as_select())
+            .into_boxed();
+        if let Some(year) = AcademicYear::year_or_active(connection, year)? {
+            query = query.filter(courses::year.eq(year));
+        }
+
+        query
             .load::<Course>(connection)
             .map_err(Error::from)
             .map(|c| Courses { 0: c })
@@ -122,6 +735,7 @@ impl Course {
         name: &'a str,
         url: &'a str,
         professor: UserId,
+        department: Option<u32>,
     ) -> Result<(), Error> {
         diesel::insert_into(courses::table)
             .values(NewCourse {
@@ -129,10 +743,16 @@ impl Course {
                 name,
                 url,
                 professor,
+                department,
             })
-            .execute(connection)
-            .map(|_| ())
-            .map_err(Error::from)
+            .execute(connection)?;
+
+        let course = courses::table
+            .filter(courses::url.eq(url))
+            .select(courses::id)
+            .first::<CourseId>(connection)?;
+
+        CourseStaffMember::set(connection, course, professor, CourseRole::Owner)
     }
 
     pub fn get_by_url<'a>(connection: &mut Connection, url: &'a str) -> Result<Self, Error> {
@@ -143,15 +763,308 @@ impl Course {
             .map_err(Error::from)
     }
 
+    pub fn get_by_id(connection: &mut Connection, id: CourseId) -> Result<Self, Error> {
+        courses::table
+            .filter(courses::id.eq(id))
+            .filter(courses::deleted.eq(false))
+            .first::<Course>(connection)
+            .map_err(Error::from)
+    }
+
+    /// Finds the course that used to be reachable at the URL before it was
+    /// renamed
+    pub fn get_by_old_url(connection: &mut Connection, url: &str) -> Result<Self, Error> {
+        course_url_redirects::table
+            .inner_join(courses::table)
+            .filter(course_url_redirects::url.eq(url))
+            .filter(courses::deleted.eq(false))
+            .select(Course::as_select())
+            .first::<Course>(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get_deleted_by_id(connection: &mut Connection, id: CourseId) -> Result<Self, Error> {
+        courses::table
+            .filter(courses::id.eq(id))
+            .filter(courses::deleted.eq(true))
+            .first::<Course>(connection)
+            .map_err(Error::from)
+    }
+
+    /// The department of a course, deleted or not
+    pub fn get_department(connection: &mut Connection, id: CourseId) -> Result<Option<u32>, Error> {
+        courses::table
+            .filter(courses::id.eq(id))
+            .select(courses::department)
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Finds the soft-deleted course that still holds a name for the year or
+    /// a URL, which keeps a new course from being created with them
+    pub fn get_deleted_conflicting(
+        connection: &mut Connection,
+        year: u32,
+        name: &str,
+        url: &str,
+    ) -> Result<Option<Self>, Error> {
+        courses::table
+            .filter(courses::deleted.eq(true))
+            .filter(
+                courses::url
+                    .eq(url)
+                    .or(courses::year.eq(year).and(courses::name.eq(name))),
+            )
+            .first::<Course>(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// Changes the name, year and professor of the course, the URL follows
+    /// the name and the old one keeps redirecting to the course
+    pub fn update(
+        &self,
+        connection: &mut Connection,
+        year: u32,
+        name: &str,
+        url: &str,
+        professor: UserId,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set((
+                courses::year.eq(year),
+                courses::name.eq(name),
+                courses::url.eq(url),
+                courses::professor.eq(professor),
+            ))
+            .execute(connection)?;
+
+        if self.professor != professor {
+            Course::set_owner(connection, self.id, professor)?;
+        }
+
+        if self.url != url {
+            diesel::delete(course_url_redirects::table.filter(course_url_redirects::url.eq(url)))
+                .execute(connection)?;
+
+            diesel::replace_into(course_url_redirects::table)
+                .values((
+                    course_url_redirects::url.eq(&self.url),
+                    course_url_redirects::course.eq(self.id),
+                ))
+                .execute(connection)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn update_department(
+        &self,
+        connection: &mut Connection,
+        department: Option<u32>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(courses::department.eq(department))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn update_curriculum(
+        &self,
+        connection: &mut Connection,
+        ects: u32,
+        semester: Option<u8>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set((courses::ects.eq(ects), courses::semester.eq(semester)))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
     pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
         diesel::update(self)
             .set(courses::deleted.eq(true))
+            .execute(connection)?;
+
+        let course = Course {
+            deleted: true,
+            ..self.clone()
+        };
+        webhook::trigger(connection, WebhookEvent::CourseDeleted, &course)
+    }
+
+    pub fn restore(&self, connection: &mut Connection) -> Result<(), Error> {
+        diesel::update(self)
+            .set(courses::deleted.eq(false))
             .execute(connection)
             .map(|_| ())
             .map_err(Error::from)
     }
 
-    pub fn authorized_to_edit(&self, user: &User) -> bool {
-        user.is_administrator() || self.professor == user.id()
+    /// Courses are reached at their name with the year, as every year has
+    /// its own course with the same name
+    pub fn url_for(name: &str, year: u32) -> String {
+        format!("{}_{year}", util::string_to_url(name))
+    }
+
+    /// The URL of the course after changing its name or year, which stays
+    /// the same when neither changes so editing never moves a course that
+    /// predates the current URL scheme
+    pub fn edited_url(&self, name: &str, year: u32) -> String {
+        if self.name == name && self.year == year {
+            self.url.clone()
+        } else {
+            Course::url_for(name, year)
+        }
+    }
+
+    pub fn enrolled_emails(&self, connection: &mut Connection) -> Result<Vec<String>, Error> {
+        enrolments::table
+            .inner_join(users::table)
+            .filter(enrolments::course.eq(self.id))
+            .filter(users::deleted.eq(false))
+            .select(users::email)
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    /// Creates the course again for another year with the same staff and
+    /// assignments, but without enrolments or progress
+    pub fn roll_over(&self, connection: &mut Connection, year: u32) -> Result<Course, Error> {
+        let url = Course::url_for(&self.name, year);
+        Course::create(
+            connection,
+            year,
+            &self.name,
+            &url,
+            self.professor,
+            self.department,
+        )?;
+        let course = Course::get_by_url(connection, &url)?;
+        diesel::update(&course)
+            .set(courses::original.eq(self.identity()))
+            .execute(connection)?;
+        course.update_curriculum(connection, self.ects, self.semester)?;
+        Curriculum::copy_course(connection, self.id, course.id)?;
+
+        for member in CourseStaffMember::get_all(connection, self.id)? {
+            if member.member.role != CourseRole::Owner {
+                CourseStaffMember::set(
+                    connection,
+                    course.id,
+                    member.member.user,
+                    member.member.role,
+                )?;
+            }
+        }
+
+        let years =
+            i32::try_from(year).unwrap_or(i32::MAX) - i32::try_from(self.year).unwrap_or(i32::MAX);
+        for assignment in Assignments::get(connection, self.id)?.0 {
+            if !assignment.base().deleted {
+                assignment.copy_to(connection, course.id, years)?;
+            }
+        }
+
+        Course::get_by_id(connection, course.id)
+    }
+
+    /// Makes the user the only owner of the course, the previous owner stays
+    /// on as a lecturer
+    pub fn set_owner(
+        connection: &mut Connection,
+        course: CourseId,
+        owner: UserId,
+    ) -> Result<(), Error> {
+        diesel::update(
+            course_staff::table
+                .filter(course_staff::course.eq(course))
+                .filter(course_staff::role.eq(CourseRole::Owner)),
+        )
+        .set(course_staff::role.eq(CourseRole::Lecturer))
+        .execute(connection)?;
+
+        CourseStaffMember::set(connection, course, owner, CourseRole::Owner)
+    }
+
+    /// Assignments, enrolments and publishing grades
+    pub fn authorized_to_edit(&self, user: &User, role: Option<CourseRole>) -> bool {
+        user.administers(self.department)
+            || matches!(role, Some(CourseRole::Owner | CourseRole::Lecturer))
+    }
+
+    pub fn authorized_to_grade(&self, user: &User, role: Option<CourseRole>) -> bool {
+        user.administers(self.department) || role.is_some()
+    }
+
+    /// The course itself and its staff
+    pub fn authorized_to_manage(&self, user: &User, role: Option<CourseRole>) -> bool {
+        user.administers(self.department) || role == Some(CourseRole::Owner)
+    }
+
+    /// The same for every year of the course, which all share the course
+    /// they were first rolled over from
+    pub fn identity(&self) -> CourseId {
+        self.original.unwrap_or(self.id)
+    }
+
+    /// Grades can't be changed or published once the year is closed
+    pub fn grades_read_only(&self, connection: &mut Connection) -> Result<bool, Error> {
+        AcademicYear::is_closed(connection, self.year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Course;
+
+    fn course(year: u32, name: &str, url: &str) -> Course {
+        Course {
+            id: 1,
+            year,
+            name: name.to_owned(),
+            url: url.to_owned(),
+            professor: 1,
+            deleted: false,
+            ects: 6,
+            semester: None,
+            department: None,
+            original: None,
+        }
+    }
+
+    #[test]
+    fn editing_a_rolled_over_course_keeps_its_url() {
+        let original = course(2022, "Linear Algebra", "linear_algebra");
+        let copy = course(2023, &original.name, &Course::url_for(&original.name, 2023));
+
+        assert_eq!(copy.url, "linear_algebra_2023");
+        assert_eq!(copy.edited_url(&copy.name, copy.year), copy.url);
+        assert_ne!(copy.edited_url(&copy.name, copy.year), original.url);
+    }
+
+    #[test]
+    fn renamed_courses_keep_the_year_in_their_url() {
+        let copy = course(2023, "Linear Algebra", "linear_algebra_2023");
+
+        assert_eq!(copy.edited_url("Algebra", 2023), "algebra_2023");
+        assert_eq!(
+            copy.edited_url("Linear Algebra", 2024),
+            "linear_algebra_2024"
+        );
+    }
+
+    #[test]
+    fn renamed_rolled_over_courses_stay_the_same_course() {
+        let original = course(2022, "Linear Algebra", "linear_algebra");
+        let copy = Course {
+            id: 2,
+            original: Some(original.id),
+            ..course(2023, "Algebra", "algebra_2023")
+        };
+
+        assert_eq!(copy.identity(), original.identity());
     }
 }
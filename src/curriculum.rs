@@ -0,0 +1,203 @@
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    course::{Course, CourseId},
+    database::Connection,
+    error::Error,
+    index::Program,
+    schema::{courses, program_courses},
+    transcript::Transcript,
+    user::UserId,
+};
+
+#[derive(Insertable)]
+#[diesel(table_name = program_courses)]
+struct NewProgramCourse {
+    program: u32,
+    course: CourseId,
+    mandatory: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CurriculumCourse {
+    #[serde(flatten)]
+    pub course: Course,
+    pub mandatory: bool,
+}
+
+/// Courses of a program, ordered by semester, the ones without a semester
+/// last
+#[derive(Serialize, Debug)]
+pub struct Curriculum {
+    pub program: Program,
+    pub courses: Vec<CurriculumCourse>,
+}
+
+impl Curriculum {
+    pub fn get(connection: &mut Connection, program: u32) -> Result<Self, Error> {
+        let program = Program::get_by_id(connection, program)?;
+
+        let mut courses: Vec<CurriculumCourse> = courses::table
+            .inner_join(program_courses::table)
+            .filter(courses::deleted.eq(false))
+            .filter(program_courses::program.eq(program.id))
+            .select((Course::as_select(), program_courses::mandatory))
+            .load::<(Course, bool)>(connection)
+            .map_err(Error::from)?
+            .into_iter()
+            .map(|(course, mandatory)| CurriculumCourse { course, mandatory })
+            .collect();
+        courses.sort_by(|a, b| {
+            let semester = |course: &CurriculumCourse| course.course.semester.unwrap_or(u8::MAX);
+            semester(a)
+                .cmp(&semester(b))
+                .then_with(|| a.course.name.cmp(&b.course.name))
+                .then_with(|| b.course.year.cmp(&a.course.year))
+        });
+
+        Ok(Self { program, courses })
+    }
+
+    /// Adds the course to the program, or changes whether it is mandatory
+    /// if it is already there
+    pub fn set(
+        connection: &mut Connection,
+        program: u32,
+        course: CourseId,
+        mandatory: bool,
+    ) -> Result<(), Error> {
+        diesel::replace_into(program_courses::table)
+            .values(NewProgramCourse {
+                program,
+                course,
+                mandatory,
+            })
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn remove(
+        connection: &mut Connection,
+        program: u32,
+        course: CourseId,
+    ) -> Result<(), Error> {
+        let removed = diesel::delete(
+            program_courses::table
+                .filter(program_courses::program.eq(program))
+                .filter(program_courses::course.eq(course)),
+        )
+        .execute(connection)
+        .map_err(Error::from)?;
+
+        if removed == 0 {
+            return Err(Error::DatabaseEntryNotFound);
+        }
+        Ok(())
+    }
+
+    /// Puts a course rolled over into a new year into the same programs as
+    /// the one it was copied from
+    pub fn copy_course(
+        connection: &mut Connection,
+        from: CourseId,
+        to: CourseId,
+    ) -> Result<(), Error> {
+        let programs = program_courses::table
+            .filter(program_courses::course.eq(from))
+            .select((program_courses::program, program_courses::mandatory))
+            .load::<(u32, bool)>(connection)
+            .map_err(Error::from)?;
+
+        for (program, mandatory) in programs {
+            Self::set(connection, program, to, mandatory)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CurriculumProgressCourse {
+    #[serde(flatten)]
+    pub curriculum_course: CurriculumCourse,
+    pub passed: bool,
+}
+
+/// How far a student got through the curriculum of their program. A course
+/// taught in several years counts once and is passed if it was passed in
+/// any of them, with the ECTS credits of the year it was passed in.
+#[derive(Serialize, Debug)]
+pub struct CurriculumProgress {
+    pub program: Program,
+    pub courses: Vec<CurriculumProgressCourse>,
+    pub earned_ects: u32,
+    pub total_ects: u32,
+    pub remaining_mandatory: Vec<Course>,
+}
+
+impl CurriculumProgress {
+    /// `None` for students without an index, who are not in any program
+    pub fn get(connection: &mut Connection, student: UserId) -> Result<Option<Self>, Error> {
+        let transcript = Transcript::get(connection, student)?;
+        let Some(index) = &transcript.student.index else {
+            return Ok(None);
+        };
+
+        let passed: Vec<&Course> = transcript
+            .years
+            .iter()
+            .flat_map(|year| &year.courses)
+            .filter(|course| course.passed)
+            .map(|course| &course.course)
+            .collect();
+
+        let curriculum = Curriculum::get(connection, index.program.id)?;
+
+        // Each course is shown once, as it appears first in the curriculum,
+        // or as it was passed
+        let mut courses: Vec<CurriculumProgressCourse> = Vec::new();
+        for mut curriculum_course in curriculum.courses {
+            let identity = curriculum_course.course.identity();
+            if courses
+                .iter()
+                .any(|course| course.curriculum_course.course.identity() == identity)
+            {
+                continue;
+            }
+
+            let passed_course = passed.iter().find(|course| course.identity() == identity);
+            if let Some(passed_course) = passed_course {
+                curriculum_course.course = (*passed_course).clone();
+            }
+
+            courses.push(CurriculumProgressCourse {
+                passed: passed_course.is_some(),
+                curriculum_course,
+            });
+        }
+
+        let earned_ects = courses
+            .iter()
+            .filter(|course| course.passed)
+            .map(|course| course.curriculum_course.course.ects)
+            .sum();
+        let total_ects = courses
+            .iter()
+            .map(|course| course.curriculum_course.course.ects)
+            .sum();
+        let remaining_mandatory = courses
+            .iter()
+            .filter(|course| course.curriculum_course.mandatory && !course.passed)
+            .map(|course| course.curriculum_course.course.clone())
+            .collect();
+
+        Ok(Some(Self {
+            program: curriculum.program,
+            courses,
+            earned_ects,
+            total_ects,
+            remaining_mandatory,
+        }))
+    }
+}
@@ -0,0 +1,64 @@
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::{database::Connection, error::Error, schema::departments};
+
+/// Groups programs, courses and users, so that an administrator can manage
+/// only one part of the faculty
+#[derive(Clone, Debug, Queryable, Selectable, Serialize, Identifiable)]
+pub struct Department {
+    pub id: u32,
+    pub name: String,
+}
+
+impl Department {
+    pub fn create(connection: &mut Connection, name: &str) -> Result<(), Error> {
+        diesel::insert_into(departments::table)
+            .values(departments::name.eq(name))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn get_by_id(connection: &mut Connection, id: u32) -> Result<Self, Error> {
+        departments::table
+            .filter(departments::id.eq(id))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn update(&self, connection: &mut Connection, name: &str) -> Result<(), Error> {
+        diesel::update(self)
+            .set(departments::name.eq(name))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Departments(pub Vec<Department>);
+
+impl Departments {
+    pub fn get(connection: &mut Connection) -> Result<Self, Error> {
+        departments::table
+            .order(departments::name.asc())
+            .load(connection)
+            .map(Departments)
+            .map_err(Error::from)
+    }
+
+    /// Every department for administrators of every department, otherwise
+    /// only the administrator's own
+    pub fn get_administered(
+        connection: &mut Connection,
+        department: Option<u32>,
+    ) -> Result<Self, Error> {
+        match department {
+            Some(department) => Ok(Departments(vec![Department::get_by_id(
+                connection, department,
+            )?])),
+            None => Self::get(connection),
+        }
+    }
+}
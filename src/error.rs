@@ -11,10 +11,23 @@ pub enum Error {
     HandlebarsTemplate(handlebars::TemplateError),
     Hex(hex::FromHexError),
     LettreSmtp(lettre::transport::smtp::Error),
+    SerdeJson(rocket::serde::json::serde_json::Error),
     NotLoggedIn,
     InvalidLanguageCode,
     InvalidAccountTypeValue,
+    InvalidAssignmentTypeValue,
+    InvalidCourseRoleValue,
+    InvalidSelfEnrolmentModeValue,
+    InvalidAcademicYearStatusValue,
+    InvalidStudentStatusValue,
+    InvalidWebhookEventValue,
     NoEnrolDropdownsReceived,
+    /// A student can't be without an index
+    IndexRequired,
+    /// Courses can only be given to professors
+    InvalidProfessor,
+    /// Courses are only created in and moved into open academic years
+    AcademicYearNotOpen,
 }
 
 impl From<diesel::result::Error> for Error {
@@ -66,9 +79,21 @@ impl From<lettre::transport::smtp::Error> for Error {
     }
 }
 
+impl From<rocket::serde::json::serde_json::Error> for Error {
+    fn from(value: rocket::serde::json::serde_json::Error) -> Self {
+        Self::SerdeJson(value)
+    }
+}
+
 impl From<Error> for rocket::http::Status {
     fn from(val: Error) -> Self {
-        error!("Internal server error: {:?}", val);
-        rocket::http::Status::InternalServerError
+        match val {
+            // Lets the not found catcher handle missing and moved entries
+            Error::DatabaseEntryNotFound => rocket::http::Status::NotFound,
+            val => {
+                error!("Internal server error: {:?}", val);
+                rocket::http::Status::InternalServerError
+            }
+        }
     }
 }
@@ -0,0 +1,171 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    course::{Course, CourseId},
+    database::Connection,
+    error::Error,
+    schema::{courses, enrolments, exam_periods, exam_registrations},
+    user::UserId,
+};
+
+pub type ExamPeriodId = u32;
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize, Identifiable)]
+#[diesel(table_name = exam_periods)]
+pub struct ExamPeriod {
+    pub id: ExamPeriodId,
+    pub name: String,
+    pub registration_opens: NaiveDateTime,
+    pub registration_closes: NaiveDateTime,
+}
+
+impl ExamPeriod {
+    pub fn create(
+        connection: &mut Connection,
+        name: &str,
+        registration_opens: NaiveDateTime,
+        registration_closes: NaiveDateTime,
+    ) -> Result<(), Error> {
+        diesel::insert_into(exam_periods::table)
+            .values((
+                exam_periods::name.eq(name),
+                exam_periods::registration_opens.eq(registration_opens),
+                exam_periods::registration_closes.eq(registration_closes),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn get_by_id(connection: &mut Connection, id: ExamPeriodId) -> Result<Self, Error> {
+        exam_periods::table
+            .filter(exam_periods::id.eq(id))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn is_open(&self, now: NaiveDateTime) -> bool {
+        self.registration_opens <= now && now <= self.registration_closes
+    }
+
+    /// Assignments bound to the period lose the binding, registrations are
+    /// deleted with it
+    pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
+        diesel::delete(self)
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExamPeriods(pub Vec<ExamPeriod>);
+
+impl ExamPeriods {
+    pub fn get(connection: &mut Connection) -> Result<Self, Error> {
+        exam_periods::table
+            .order(exam_periods::registration_opens.desc())
+            .load(connection)
+            .map(ExamPeriods)
+            .map_err(Error::from)
+    }
+
+    /// Periods students can register for at the given time
+    pub fn get_open(connection: &mut Connection, now: NaiveDateTime) -> Result<Self, Error> {
+        exam_periods::table
+            .filter(exam_periods::registration_opens.le(now))
+            .filter(exam_periods::registration_closes.ge(now))
+            .order(exam_periods::registration_closes.asc())
+            .load(connection)
+            .map(ExamPeriods)
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Clone, Debug, Queryable, Selectable, Serialize)]
+#[diesel(table_name = exam_registrations)]
+pub struct ExamRegistration {
+    pub exam_period: ExamPeriodId,
+    pub course: CourseId,
+    pub student: UserId,
+    pub created: NaiveDateTime,
+}
+
+impl ExamRegistration {
+    /// Registers the student for the exam, only if they are enrolled in the
+    /// course. Returns `DatabaseEntryNotFound` otherwise.
+    pub fn create(
+        connection: &mut Connection,
+        exam_period: ExamPeriodId,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<(), Error> {
+        enrolments::table
+            .filter(enrolments::course.eq(course))
+            .filter(enrolments::student.eq(student))
+            .select(enrolments::student)
+            .first::<UserId>(connection)?;
+
+        diesel::insert_or_ignore_into(exam_registrations::table)
+            .values((
+                exam_registrations::exam_period.eq(exam_period),
+                exam_registrations::course.eq(course),
+                exam_registrations::student.eq(student),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    pub fn delete(
+        connection: &mut Connection,
+        exam_period: ExamPeriodId,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<(), Error> {
+        diesel::delete(
+            exam_registrations::table
+                .filter(exam_registrations::exam_period.eq(exam_period))
+                .filter(exam_registrations::course.eq(course))
+                .filter(exam_registrations::student.eq(student)),
+        )
+        .execute(connection)
+        .map(|_| ())
+        .map_err(Error::from)
+    }
+
+    pub fn exists(
+        connection: &mut Connection,
+        exam_period: ExamPeriodId,
+        course: CourseId,
+        student: UserId,
+    ) -> Result<bool, Error> {
+        exam_registrations::table
+            .filter(exam_registrations::exam_period.eq(exam_period))
+            .filter(exam_registrations::course.eq(course))
+            .filter(exam_registrations::student.eq(student))
+            .select(exam_registrations::student)
+            .first::<UserId>(connection)
+            .optional()
+            .map(|registration| registration.is_some())
+            .map_err(Error::from)
+    }
+
+    /// Courses the student registered an exam for in the given periods
+    pub fn get_for_student(
+        connection: &mut Connection,
+        student: UserId,
+        exam_periods: &[ExamPeriodId],
+    ) -> Result<Vec<(ExamPeriodId, Course)>, Error> {
+        exam_registrations::table
+            .inner_join(courses::table)
+            .filter(exam_registrations::student.eq(student))
+            .filter(exam_registrations::exam_period.eq_any(exam_periods))
+            .filter(courses::deleted.eq(false))
+            .select((exam_registrations::exam_period, Course::as_select()))
+            .load(connection)
+            .map_err(Error::from)
+    }
+}
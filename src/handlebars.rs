@@ -1,3 +1,4 @@
+use chrono::{NaiveDate, NaiveDateTime};
 use handlebars::{
     Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
     ScopedJson,
@@ -5,6 +6,7 @@ use handlebars::{
 
 use handlebars::to_json;
 use log::trace;
+use pulldown_cmark::{html, Event, Parser, Tag};
 
 pub struct ConcatHelper {}
 
@@ -134,3 +136,98 @@ impl RangeHelper {
         Box::new(RangeHelper {})
     }
 }
+
+pub struct MarkdownHelper {}
+
+impl HelperDef for MarkdownHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper,
+        _: &Handlebars,
+        _: &Context,
+        _: &mut RenderContext,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let markdown = h
+            .param(0)
+            .ok_or(RenderError::new("markdown text not provided"))?
+            .value()
+            .as_str()
+            .unwrap_or_default();
+
+        // raw HTML inside the markdown is shown as text and script links are
+        // dropped, so descriptions can't inject anything into the page
+        let parser = Parser::new(markdown).map(|event| match event {
+            Event::Html(html) => Event::Text(html),
+            Event::Start(Tag::Link(kind, url, title)) if is_script_url(&url) => {
+                Event::Start(Tag::Link(kind, "".into(), title))
+            }
+            Event::Start(Tag::Image(kind, url, title)) if is_script_url(&url) => {
+                Event::Start(Tag::Image(kind, "".into(), title))
+            }
+            event => event,
+        });
+
+        let mut result = String::new();
+        html::push_html(&mut result, parser);
+
+        out.write(&result)?;
+        Ok(())
+    }
+}
+
+fn is_script_url(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    url.starts_with("javascript:") || url.starts_with("vbscript:") || url.starts_with("data:")
+}
+
+impl MarkdownHelper {
+    pub fn name() -> &'static str {
+        "markdown"
+    }
+
+    pub fn helper() -> Box<dyn HelperDef + Send + Sync + 'static> {
+        Box::new(MarkdownHelper {})
+    }
+}
+
+pub struct DateTimeHelper {}
+
+impl HelperDef for DateTimeHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper,
+        _: &'reg Handlebars,
+        _: &'rc Context,
+        _: &mut RenderContext,
+    ) -> Result<ScopedJson<'reg, 'rc>, RenderError> {
+        let value = h
+            .param(0)
+            .ok_or(RenderError::new("date and time not provided"))?
+            .value()
+            .as_str()
+            .unwrap_or_default();
+
+        // chrono serializes NaiveDateTime and NaiveDate as ISO 8601,
+        // anything else is passed through unchanged
+        let result = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+            .map(|d| d.format("%d.%m.%Y. %H:%M").to_string())
+            .or_else(|_| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .map(|d| d.format("%d.%m.%Y.").to_string())
+            })
+            .unwrap_or_else(|_| value.to_owned());
+
+        Ok(ScopedJson::Derived(to_json(result)))
+    }
+}
+
+impl DateTimeHelper {
+    pub fn name() -> &'static str {
+        "datetime"
+    }
+
+    pub fn helper() -> Box<dyn HelperDef + Send + Sync + 'static> {
+        Box::new(DateTimeHelper {})
+    }
+}
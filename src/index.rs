@@ -1,8 +1,13 @@
+use chrono::NaiveDateTime;
 use diesel::query_dsl::RunQueryDsl;
-use diesel::{ExpressionMethods, Identifiable, Insertable, QueryDsl, Queryable, Selectable};
+use diesel::{
+    ExpressionMethods, Identifiable, Insertable, NullableExpressionMethods, OptionalExtension,
+    QueryDsl, Queryable, Selectable,
+};
 use serde::Serialize;
+use utoipa::ToSchema;
 
-use crate::schema::indicies;
+use crate::schema::{indicies, indicies_revisions};
 use crate::user::UserId;
 use crate::{
     database::Connection,
@@ -10,7 +15,7 @@ use crate::{
     schema::{generations, programs},
 };
 
-#[derive(Queryable, Selectable, Debug, Serialize, Identifiable, Clone)]
+#[derive(Queryable, Selectable, Debug, Serialize, Identifiable, Clone, ToSchema)]
 pub struct Generation {
     pub id: u32,
     pub year: u32,
@@ -47,6 +52,26 @@ impl Generation {
             .map_err(Error::from)
     }
 
+    pub fn update(&self, connection: &mut Connection, year: u32) -> Result<(), Error> {
+        diesel::update(self)
+            .set(generations::year.eq(year))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    /// Number of students with an index in the generation
+    pub fn count_students(&self, connection: &mut Connection) -> Result<u32, Error> {
+        indicies::table
+            .filter(indicies::generation.eq(self.id))
+            .count()
+            .get_result::<i64>(connection)
+            .map(|count| count as u32)
+            .map_err(Error::from)
+    }
+
+    /// Only a generation without students can be deleted, their indices
+    /// would go with it otherwise
     pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
         diesel::delete(self)
             .execute(connection)
@@ -67,11 +92,12 @@ impl Generations {
     }
 }
 
-#[derive(Queryable, Selectable, Debug, Serialize, Identifiable, Clone)]
+#[derive(Queryable, Selectable, Debug, Serialize, Identifiable, Clone, ToSchema)]
 pub struct Program {
     pub id: u32,
     pub short_name: String,
     pub full_name: String,
+    pub department: Option<u32>,
 }
 
 #[derive(Insertable)]
@@ -79,6 +105,7 @@ pub struct Program {
 struct NewProgram {
     short_name: String,
     full_name: String,
+    department: Option<u32>,
 }
 
 impl Program {
@@ -86,11 +113,13 @@ impl Program {
         connection: &mut Connection,
         short_name: String,
         full_name: String,
+        department: Option<u32>,
     ) -> Result<(), Error> {
         diesel::insert_into(programs::table)
             .values(NewProgram {
                 short_name,
                 full_name,
+                department,
             })
             .execute(connection)
             .map(|_| ())
@@ -116,6 +145,36 @@ impl Program {
             .map_err(Error::from)
     }
 
+    pub fn update(
+        &self,
+        connection: &mut Connection,
+        short_name: &str,
+        full_name: &str,
+        department: Option<u32>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set((
+                programs::short_name.eq(short_name),
+                programs::full_name.eq(full_name),
+                programs::department.eq(department),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    /// Number of students with an index in the program
+    pub fn count_students(&self, connection: &mut Connection) -> Result<u32, Error> {
+        indicies::table
+            .filter(indicies::program.eq(self.id))
+            .count()
+            .get_result::<i64>(connection)
+            .map(|count| count as u32)
+            .map_err(Error::from)
+    }
+
+    /// Only a program without students can be deleted, their indices would
+    /// go with it otherwise
     pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
         diesel::delete(self)
             .execute(connection)
@@ -134,17 +193,34 @@ impl Programs {
             .map_err(Error::from)
             .map(|g| Programs { 0: g })
     }
+
+    /// Programs of the department, or every program without one
+    pub fn get_administered(
+        connection: &mut Connection,
+        department: Option<u32>,
+    ) -> Result<Programs, Error> {
+        match department {
+            Some(department) => programs::table
+                .filter(programs::department.eq(department))
+                .get_results(connection)
+                .map_err(Error::from)
+                .map(Programs),
+            None => Self::get(connection),
+        }
+    }
 }
 
 pub type IndexNumber = u32;
 
-#[derive(Debug, Serialize, Selectable, Queryable, Identifiable, Clone)]
+#[derive(Debug, Serialize, Selectable, Queryable, Identifiable, Clone, ToSchema)]
 #[diesel(table_name = indicies)]
 pub struct Index {
     pub id: u32,
     pub program: u32,
     pub generation: u32,
+    #[schema(value_type = u32)]
     pub number: IndexNumber,
+    #[schema(value_type = u32)]
     pub student: UserId,
 }
 
@@ -176,4 +252,144 @@ impl Index {
             .map(|_| ())
             .map_err(Error::from)
     }
+
+    pub fn get_by_student(
+        connection: &mut Connection,
+        student: UserId,
+    ) -> Result<Option<Index>, Error> {
+        indicies::table
+            .filter(indicies::student.eq(student))
+            .first(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
+    /// Gives the student the index, correcting the one they already have.
+    /// The update trigger keeps the replaced index as a revision.
+    pub fn set_for_student(
+        connection: &mut Connection,
+        student: UserId,
+        program: u32,
+        generation: u32,
+        number: IndexNumber,
+    ) -> Result<(), Error> {
+        match Index::get_by_student(connection, student)? {
+            // Leaves no revision behind when nothing changed
+            Some(index)
+                if (index.program, index.generation, index.number)
+                    == (program, generation, number) =>
+            {
+                Ok(())
+            }
+            Some(index) => diesel::update(&index)
+                .set((
+                    indicies::program.eq(program),
+                    indicies::generation.eq(generation),
+                    indicies::number.eq(number),
+                ))
+                .execute(connection)
+                .map(|_| ())
+                .map_err(Error::from),
+            None => Index::create(connection, program, generation, number, student),
+        }
+    }
+
+    /// Gives the student back the last index they had whose program and
+    /// generation still exist
+    pub fn restore_latest(connection: &mut Connection, student: UserId) -> Result<(), Error> {
+        let (program, generation, number) = indicies_revisions::table
+            .inner_join(programs::table)
+            .inner_join(generations::table)
+            .filter(indicies_revisions::student.eq(student))
+            .order(indicies_revisions::revision.desc())
+            .select((
+                indicies_revisions::program,
+                indicies_revisions::generation,
+                indicies_revisions::number,
+            ))
+            .first::<(u32, u32, IndexNumber)>(connection)
+            .optional()?
+            .ok_or(Error::IndexRequired)?;
+
+        Index::create(connection, program, generation, number, student)
+    }
+
+    /// The delete trigger keeps the removed index as a revision
+    pub fn delete(&self, connection: &mut Connection) -> Result<(), Error> {
+        diesel::delete(self)
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    /// Finds the student with an index written the way it is shown, such as
+    /// `RA12/2022` or `RA 12/2022`
+    pub fn get_student_by_text(
+        connection: &mut Connection,
+        text: &str,
+    ) -> Result<Option<UserId>, Error> {
+        let Some((program, number, year)) = parse_index(text) else {
+            return Ok(None);
+        };
+
+        indicies::table
+            .inner_join(programs::table)
+            .inner_join(generations::table)
+            .filter(programs::short_name.eq(program))
+            .filter(indicies::number.eq(number))
+            .filter(generations::year.eq(year))
+            .select(indicies::student)
+            .first(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+}
+
+fn parse_index(text: &str) -> Option<(String, IndexNumber, u32)> {
+    let (program_and_number, year) = text.trim().split_once('/')?;
+    let number_start = program_and_number.find(|c: char| c.is_ascii_digit())?;
+    let (program, number) = program_and_number.split_at(number_start);
+
+    let program = program.trim();
+    if program.is_empty() {
+        return None;
+    }
+
+    Some((
+        program.to_owned(),
+        number.trim().parse().ok()?,
+        year.trim().parse().ok()?,
+    ))
+}
+
+/// An index the student had before, with the program and generation it
+/// was in if they still exist
+#[derive(Debug, Serialize, Queryable)]
+pub struct IndexRevision {
+    pub revision: u32,
+    /// When the index was replaced or removed
+    pub created: Option<NaiveDateTime>,
+    pub number: IndexNumber,
+    pub program: Option<String>,
+    pub generation: Option<u32>,
+}
+
+impl IndexRevision {
+    /// Newest first
+    pub fn get_all(connection: &mut Connection, student: UserId) -> Result<Vec<Self>, Error> {
+        indicies_revisions::table
+            .left_join(programs::table)
+            .left_join(generations::table)
+            .filter(indicies_revisions::student.eq(student))
+            .order(indicies_revisions::revision.desc())
+            .select((
+                indicies_revisions::revision,
+                indicies_revisions::created,
+                indicies_revisions::number,
+                programs::short_name.nullable(),
+                generations::year.nullable(),
+            ))
+            .load(connection)
+            .map_err(Error::from)
+    }
 }
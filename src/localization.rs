@@ -38,6 +38,34 @@ impl Script {
             _ => Err(Error::InvalidLanguageCode),
         }
     }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Script::Latin => SCRIPT_CODE_LATIN,
+            Script::Cyrillic => SCRIPT_CODE_CYRILLIC,
+        }
+    }
+
+    /// Writes a Cyrillic sentence out in this script, for text that doesn't
+    /// go through the i18n template helper
+    pub fn localize(&self, sentence: &str) -> String {
+        match self {
+            Script::Latin => cyrillic_to_latin()
+                .process(sentence)
+                .unwrap_or_else(|_| sentence.to_owned()),
+            Script::Cyrillic => sentence.to_owned(),
+        }
+    }
+}
+
+fn cyrillic_to_latin() -> Transliterator {
+    Transliterator::new(
+        translitrs::Charset::Cyrillic,
+        translitrs::Charset::Latin,
+        false,
+        false,
+        false,
+    )
 }
 
 #[rocket::async_trait]
@@ -99,13 +127,7 @@ impl HelperDef for ScriptHelper {
 impl ScriptHelper {
     pub fn helper() -> Box<dyn HelperDef + Send + Sync + 'static> {
         Box::new(ScriptHelper {
-            transliterator: Transliterator::new(
-                translitrs::Charset::Cyrillic,
-                translitrs::Charset::Latin,
-                false,
-                false,
-                false,
-            ),
+            transliterator: cyrillic_to_latin(),
         })
     }
 
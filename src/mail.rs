@@ -1,11 +1,13 @@
+use std::sync::Arc;
+
 use crate::error::Error;
 use handlebars::Handlebars;
 use lettre::{
     message::{header::ContentType, Mailbox},
     transport::smtp::authentication::Credentials,
-    Address, Message, SmtpTransport, Transport,
+    Address, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
-use rocket::async_trait;
+use rocket::{async_trait, fairing::AdHoc};
 use serde::Serialize;
 
 const SMTP_ADDRESS: &str = dotenvy_macro::dotenv!("SMTP_ADDRESS");
@@ -19,9 +21,11 @@ pub trait MailSender {
     async fn send(&self, message: Message) -> Result<(), Error>;
 }
 
+/// Cheap to clone, so mails can be sent from background tasks
+#[derive(Clone)]
 pub struct Mail {
-    transport: SmtpTransport,
-    handlebars: Handlebars<'static>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    handlebars: Arc<Handlebars<'static>>,
 }
 
 impl Mail {
@@ -31,21 +35,26 @@ impl Mail {
         handlebars.set_strict_mode(true);
 
         handlebars.register_template_file("invite", "templates/mail/invite.html.hbs")?;
+        handlebars.register_template_file(
+            "grades_published",
+            "templates/mail/grades_published.html.hbs",
+        )?;
 
         let credentials = Credentials::new(SMTP_USERNAME.to_owned(), SMTP_PASSWORD.to_owned());
-        let transport = SmtpTransport::relay(SMTP_ADDRESS)?
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(SMTP_ADDRESS)?
             .credentials(credentials)
             .build();
 
         Ok(Self {
             transport,
-            handlebars,
+            handlebars: Arc::new(handlebars),
         })
     }
 
-    async fn send(&self, message: &Message) -> Result<(), Error> {
+    async fn send(&self, message: Message) -> Result<(), Error> {
         self.transport
             .send(message)
+            .await
             .map(|_| ())
             .map_err(Error::from)
     }
@@ -70,6 +79,47 @@ impl Mail {
             )
             .unwrap();
 
-        self.send(&message).await
+        self.send(message).await
+    }
+
+    pub async fn send_grades_published(
+        &self,
+        to: Address,
+        course_name: &str,
+        assignment_name: &str,
+    ) -> Result<(), Error> {
+        let from_address: Address = SMTP_FROM_ADDRESS.parse().unwrap();
+
+        #[derive(Serialize)]
+        pub struct GradesPublishedMailContext<'a> {
+            course_name: &'a str,
+            assignment_name: &'a str,
+        }
+
+        let message = Message::builder()
+            .from(Mailbox::new(None, from_address))
+            .to(Mailbox::new(None, to))
+            .subject(format!(
+                "Grades published: {assignment_name} ({course_name})"
+            ))
+            .header(ContentType::TEXT_HTML)
+            .body(self.handlebars.render(
+                "grades_published",
+                &GradesPublishedMailContext {
+                    course_name,
+                    assignment_name,
+                },
+            )?)
+            .unwrap();
+
+        self.send(message).await
     }
 }
+
+/// Manages the `Mail` once the runtime is up, the transport's connection pool
+/// starts a task of its own when built
+pub fn fairing() -> AdHoc {
+    AdHoc::on_ignite("Mail", |rocket| async {
+        rocket.manage(Mail::new().unwrap())
+    })
+}
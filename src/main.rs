@@ -1,31 +1,41 @@
 #![warn(clippy::pedantic)]
 //#![deny(warnings)]
 
+mod academic_year;
+mod api_token;
 mod assignment;
 mod base_layout_context;
+mod calendar;
 mod catchers;
 mod components;
 mod course;
+mod curriculum;
 mod database;
+mod department;
 mod error;
+mod exam;
 mod handlebars;
 mod index;
 mod localization;
 mod mail;
 mod models;
+mod revision;
 mod routes;
 mod schema;
+mod student_status;
+mod transcript;
 mod user;
 mod util;
+mod webhook;
 
 use database::Database;
-use mail::Mail;
 use rocket::catchers;
 use rocket::fs::FileServer;
 use rocket::{build, launch, routes};
 use rocket_dyn_templates::Template;
 
 use routes::administrator;
+use routes::api;
 use routes::locale;
 use routes::login;
 use routes::logout;
@@ -54,6 +64,14 @@ fn rocket() -> _ {
         engines
             .handlebars
             .register_helper(handlebars::EqHelper::name(), handlebars::EqHelper::helper());
+        engines.handlebars.register_helper(
+            handlebars::MarkdownHelper::name(),
+            handlebars::MarkdownHelper::helper(),
+        );
+        engines.handlebars.register_helper(
+            handlebars::DateTimeHelper::name(),
+            handlebars::DateTimeHelper::helper(),
+        );
     });
 
     build()
@@ -67,22 +85,68 @@ fn rocket() -> _ {
                 login::post,
                 logout::get,
                 locale::get,
+                routes::calendar::get,
+                routes::calendar::post,
+                routes::calendar::get_feed,
+                routes::tokens::get,
+                routes::tokens::post,
+                routes::tokens::post_revoke,
                 student::courses::get,
                 student::course::get,
+                student::catalog::get,
+                student::catalog::post,
+                student::exams::get,
+                student::exams::post,
+                student::exams::post_cancel,
+                student::curriculum::get,
+                student::transcript::get,
+                student::transcript::get_pdf,
                 professor::courses::get,
                 professor::courses::create::get,
                 professor::courses::create::post,
                 professor::course::get,
                 professor::course::delete::get,
                 professor::course::delete::post,
+                professor::course::edit::get,
+                professor::course::edit::post,
+                professor::course::staff::get,
+                professor::course::staff::post,
+                professor::course::staff::post_remove,
+                professor::course::rollover::get,
+                professor::course::rollover::post,
                 professor::course::assignments::create::get,
                 professor::course::assignments::create::post_grade,
                 professor::course::assignments::create::post_point,
+                professor::course::assignments::create::post_pass_fail,
+                professor::course::assignments::create::post_counter,
+                professor::course::assignments::reorder::post,
                 professor::course::enrol::get,
                 professor::course::enrol::post,
+                professor::course::enrol::bulk::post_filtered,
+                professor::course::enrol::bulk::get_list,
+                professor::course::enrol::bulk::post_list,
+                professor::course::enrol::bulk::post_students,
+                professor::course::enrol::bulk::get_clear,
+                professor::course::enrol::bulk::post_clear,
+                professor::course::enrol::self_enrolment::get,
+                professor::course::enrol::self_enrolment::post,
+                professor::course::enrol::self_enrolment::post_approve,
+                professor::course::enrol::self_enrolment::post_reject,
                 professor::course::assignment::progress::get,
                 professor::course::assignment::progress::post,
+                professor::course::assignment::edit::get,
+                professor::course::assignment::edit::post,
+                professor::course::assignment::history::get,
+                professor::course::assignment::delete::get,
+                professor::course::assignment::delete::post,
+                professor::course::assignment::publish::get,
+                professor::course::assignment::publish::post,
+                professor::course::assignment::restore::post,
                 administrator::courses::get,
+                administrator::courses::revisions::get,
+                administrator::courses::revisions::post,
+                administrator::courses::restore::get,
+                administrator::courses::restore::post,
                 administrator::users::get,
                 administrator::users::post,
                 administrator::users::delete::get,
@@ -94,18 +158,55 @@ fn rocket() -> _ {
                 administrator::users::create::post_student,
                 administrator::users::edit::get,
                 administrator::users::edit::post,
+                administrator::users::revisions::get,
+                administrator::users::revisions::post,
+                administrator::users::status::get,
+                administrator::users::status::post,
+                administrator::users::transcript::get,
+                administrator::users::transcript::get_pdf,
+                administrator::users::restore::get,
+                administrator::users::restore::post,
+                administrator::departments::get,
+                administrator::departments::post,
+                administrator::departments::post_rename,
                 administrator::generations::get,
                 administrator::generations::post,
                 administrator::generations::delete::get,
                 administrator::generations::delete::post,
+                administrator::generations::edit::get,
+                administrator::generations::edit::post,
+                administrator::academic_years::get,
+                administrator::academic_years::post,
+                administrator::academic_years::post_status,
+                administrator::exam_periods::get,
+                administrator::exam_periods::post,
+                administrator::exam_periods::delete::get,
+                administrator::exam_periods::delete::post,
                 administrator::programs::get,
                 administrator::programs::post,
+                administrator::programs::curriculum::get,
+                administrator::programs::curriculum::post,
+                administrator::programs::curriculum::post_remove,
                 administrator::programs::delete::get,
                 administrator::programs::delete::post,
+                administrator::programs::edit::get,
+                administrator::programs::edit::post,
+                administrator::webhooks::get,
+                administrator::webhooks::post,
+                administrator::webhooks::post_delete,
+                administrator::webhooks::deliveries::get,
+                administrator::webhooks::deliveries::post_redeliver,
             ],
         )
+        .mount("/api/v1", api::routes())
+        .mount(
+            "/api/v1",
+            routes![api::openapi::get, api::openapi::get_docs],
+        )
         .register("/", catchers![catchers::not_found])
+        .register("/api", catchers![api::catcher])
         .attach(handlebars)
         .attach(Database::fairing())
-        .manage(Mail::new().unwrap())
+        .attach(webhook::fairing())
+        .attach(mail::fairing())
 }
@@ -0,0 +1,367 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    course::{Course, CourseId},
+    database::Connection,
+    error::Error,
+    index::Index,
+    schema::{courses, courses_revisions, departments, users, users_revisions},
+    student_status::StudentStatus,
+    user::{AccountType, UserId},
+};
+
+/// A single field that differs from the previous revision
+#[derive(Serialize, Debug)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// Values a revisioned row is shown with, as (field name, value) pairs
+pub trait Revisioned {
+    fn fields(&self) -> Vec<(&'static str, Option<String>)>;
+}
+
+#[derive(Serialize, Debug)]
+pub struct RevisionEntry<T> {
+    /// `None` for the current state of the row
+    pub revision: Option<u32>,
+    /// When the revision was replaced by the next one
+    pub created: Option<NaiveDateTime>,
+    pub state: T,
+    pub changes: Vec<FieldChange>,
+    /// The oldest state kept, which has nothing to be compared to
+    pub initial: bool,
+}
+
+/// Orders the revisions newest first, each with its changes compared to
+/// the one before it. Revisions that only differ in what isn't shown, such
+/// as the password, are left out.
+fn timeline<T: Revisioned>(
+    revisions: Vec<(u32, Option<NaiveDateTime>, T)>,
+    current: T,
+) -> Vec<RevisionEntry<T>> {
+    let entries = revisions
+        .into_iter()
+        .map(|(revision, created, state)| (Some(revision), created, state))
+        .chain(std::iter::once((None, None, current)));
+
+    let mut timeline: Vec<RevisionEntry<T>> = Vec::new();
+    for (revision, created, state) in entries {
+        let changes = match timeline.last() {
+            Some(previous) => previous
+                .state
+                .fields()
+                .into_iter()
+                .zip(state.fields())
+                .filter(|((_, old), (_, new))| old != new)
+                .map(|((field, old), (_, new))| FieldChange { field, old, new })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let initial = timeline.is_empty();
+        if !initial && revision.is_some() && changes.is_empty() {
+            continue;
+        }
+
+        timeline.push(RevisionEntry {
+            revision,
+            created,
+            state,
+            changes,
+            initial,
+        });
+    }
+
+    timeline.reverse();
+    timeline
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "Да" } else { "Не" }.to_owned()
+}
+
+/// Everything about a user except the password hash, which is never shown
+/// or restored
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct UserState {
+    pub email: String,
+    pub account_type: AccountType,
+    pub password_reset_required: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub last_login_time: Option<NaiveDateTime>,
+    pub deleted: bool,
+    pub student_status: StudentStatus,
+    /// Name of the department
+    pub department: Option<String>,
+}
+
+impl Revisioned for UserState {
+    fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+        let account_type = match self.account_type {
+            AccountType::Student => "Студент",
+            AccountType::Professor => "Професор",
+            AccountType::Administrator => "Администратор",
+        };
+
+        vec![
+            ("Имејл адреса", Some(self.email.clone())),
+            ("Тип налога", Some(account_type.to_owned())),
+            (
+                "Потребна промена лозинке",
+                Some(yes_no(self.password_reset_required)),
+            ),
+            ("Име", self.first_name.clone()),
+            ("Презиме", self.last_name.clone()),
+            (
+                "Последња пријава",
+                self.last_login_time
+                    .map(|time| time.format("%d.%m.%Y. %H:%M").to_string()),
+            ),
+            ("Обрисан", Some(yes_no(self.deleted))),
+            ("Статус", Some(self.student_status.name().to_owned())),
+            ("Департман", self.department.clone()),
+        ]
+    }
+}
+
+impl UserState {
+    pub fn get_revisions(
+        connection: &mut Connection,
+        user: UserId,
+    ) -> Result<Vec<RevisionEntry<Self>>, Error> {
+        let revisions = users_revisions::table
+            .left_join(
+                departments::table.on(departments::id.nullable().eq(users_revisions::department)),
+            )
+            .filter(users_revisions::id.eq(user))
+            .order(users_revisions::revision.asc())
+            .select((
+                users_revisions::revision,
+                users_revisions::created,
+                (
+                    users_revisions::email,
+                    users_revisions::account_type,
+                    users_revisions::password_reset_required,
+                    users_revisions::first_name,
+                    users_revisions::last_name,
+                    users_revisions::last_login_time,
+                    users_revisions::deleted,
+                    users_revisions::student_status,
+                    departments::name.nullable(),
+                ),
+            ))
+            .load::<(u32, Option<NaiveDateTime>, UserState)>(connection)
+            .map_err(Error::from)?;
+
+        let current = users::table
+            .left_join(departments::table)
+            .filter(users::id.eq(user))
+            .select((
+                users::email,
+                users::account_type,
+                users::password_reset_required,
+                users::first_name,
+                users::last_name,
+                users::last_login_time,
+                users::deleted,
+                users::student_status,
+                departments::name.nullable(),
+            ))
+            .first::<UserState>(connection)
+            .map_err(Error::from)?;
+
+        Ok(timeline(revisions, current))
+    }
+
+    pub fn get_revision(
+        connection: &mut Connection,
+        user: UserId,
+        revision: u32,
+    ) -> Result<Self, Error> {
+        users_revisions::table
+            .left_join(
+                departments::table.on(departments::id.nullable().eq(users_revisions::department)),
+            )
+            .filter(users_revisions::id.eq(user))
+            .filter(users_revisions::revision.eq(revision))
+            .select((
+                users_revisions::email,
+                users_revisions::account_type,
+                users_revisions::password_reset_required,
+                users_revisions::first_name,
+                users_revisions::last_name,
+                users_revisions::last_login_time,
+                users_revisions::deleted,
+                users_revisions::student_status,
+                departments::name.nullable(),
+            ))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Writes the state back to the user, the update trigger keeps the
+    /// replaced values as a new revision. The password and the last login
+    /// time stay as they are, the student status and the department only
+    /// change through their own pages. Only students have an index, a
+    /// student gets back the last one they had.
+    pub fn restore(&self, connection: &mut Connection, user: UserId) -> Result<(), Error> {
+        match (self.account_type, Index::get_by_student(connection, user)?) {
+            (AccountType::Student, None) => Index::restore_latest(connection, user)?,
+            (AccountType::Professor | AccountType::Administrator, Some(index)) => {
+                index.delete(connection)?;
+            }
+            _ => (),
+        }
+
+        diesel::update(users::table.filter(users::id.eq(user)))
+            .set((
+                users::email.eq(&self.email),
+                users::account_type.eq(self.account_type),
+                users::password_reset_required.eq(self.password_reset_required),
+                users::first_name.eq(&self.first_name),
+                users::last_name.eq(&self.last_name),
+                users::deleted.eq(self.deleted),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Queryable)]
+pub struct CourseState {
+    pub year: u32,
+    pub name: String,
+    pub url: String,
+    pub professor: UserId,
+    pub professor_email: String,
+    pub deleted: bool,
+    pub ects: u32,
+    pub semester: Option<u8>,
+    /// Name of the department
+    pub department: Option<String>,
+}
+
+impl Revisioned for CourseState {
+    fn fields(&self) -> Vec<(&'static str, Option<String>)> {
+        vec![
+            ("Година", Some(self.year.to_string())),
+            ("Име", Some(self.name.clone())),
+            ("Адреса", Some(self.url.clone())),
+            ("Професор", Some(self.professor_email.clone())),
+            ("Обрисан", Some(yes_no(self.deleted))),
+            ("ЕСПБ", Some(self.ects.to_string())),
+            (
+                "Семестар",
+                self.semester.map(|semester| semester.to_string()),
+            ),
+            ("Департман", self.department.clone()),
+        ]
+    }
+}
+
+impl CourseState {
+    pub fn get_revisions(
+        connection: &mut Connection,
+        course: CourseId,
+    ) -> Result<Vec<RevisionEntry<Self>>, Error> {
+        let revisions = courses_revisions::table
+            .inner_join(users::table.on(users::id.eq(courses_revisions::professor)))
+            .left_join(
+                departments::table.on(departments::id.nullable().eq(courses_revisions::department)),
+            )
+            .filter(courses_revisions::id.eq(course))
+            .order(courses_revisions::revision.asc())
+            .select((
+                courses_revisions::revision,
+                courses_revisions::created,
+                (
+                    courses_revisions::year,
+                    courses_revisions::name,
+                    courses_revisions::url,
+                    courses_revisions::professor,
+                    users::email,
+                    courses_revisions::deleted,
+                    courses_revisions::ects,
+                    courses_revisions::semester,
+                    departments::name.nullable(),
+                ),
+            ))
+            .load::<(u32, Option<NaiveDateTime>, CourseState)>(connection)
+            .map_err(Error::from)?;
+
+        let current = courses::table
+            .inner_join(users::table)
+            .left_join(departments::table)
+            .filter(courses::id.eq(course))
+            .select((
+                courses::year,
+                courses::name,
+                courses::url,
+                courses::professor,
+                users::email,
+                courses::deleted,
+                courses::ects,
+                courses::semester,
+                departments::name.nullable(),
+            ))
+            .first::<CourseState>(connection)
+            .map_err(Error::from)?;
+
+        Ok(timeline(revisions, current))
+    }
+
+    pub fn get_revision(
+        connection: &mut Connection,
+        course: CourseId,
+        revision: u32,
+    ) -> Result<Self, Error> {
+        courses_revisions::table
+            .inner_join(users::table.on(users::id.eq(courses_revisions::professor)))
+            .left_join(
+                departments::table.on(departments::id.nullable().eq(courses_revisions::department)),
+            )
+            .filter(courses_revisions::id.eq(course))
+            .filter(courses_revisions::revision.eq(revision))
+            .select((
+                courses_revisions::year,
+                courses_revisions::name,
+                courses_revisions::url,
+                courses_revisions::professor,
+                users::email,
+                courses_revisions::deleted,
+                courses_revisions::ects,
+                courses_revisions::semester,
+                departments::name.nullable(),
+            ))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Writes the state back to the course the same way editing does, so the
+    /// URL it has now keeps redirecting and the professor it had becomes the
+    /// owner again. The update trigger keeps the replaced values as a new
+    /// revision. The department only changes through editing the course.
+    pub fn restore(&self, connection: &mut Connection, course: CourseId) -> Result<(), Error> {
+        // Deleted courses have revisions too
+        let course = courses::table
+            .filter(courses::id.eq(course))
+            .first::<Course>(connection)?;
+        course.update(connection, self.year, &self.name, &self.url, self.professor)?;
+        if (course.ects, course.semester) != (self.ects, self.semester) {
+            course.update_curriculum(connection, self.ects, self.semester)?;
+        }
+
+        match (course.deleted, self.deleted) {
+            (false, true) => course.delete(connection),
+            (true, false) => course.restore(connection),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, response::Redirect, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    academic_year::{AcademicYear, AcademicYearStatus, AcademicYears},
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    academic_years: Vec<AcademicYear>,
+    show_success_message: bool,
+    show_error_duplicate_year: bool,
+    show_error_invalid_dates: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, database: Database) -> Result<Self, Error> {
+        let academic_years = database.run(AcademicYears::get).await?.0;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            academic_years,
+            show_success_message: false,
+            show_error_duplicate_year: false,
+            show_error_invalid_dates: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_duplicate_year(mut self) -> Self {
+        self.show_error_duplicate_year = true;
+        self
+    }
+
+    pub fn show_error_invalid_dates(mut self) -> Self {
+        self.show_error_invalid_dates = true;
+        self
+    }
+}
+
+#[get("/academic_years")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/academic_years",
+        LayoutContext::new(language, user, database).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    year: u32,
+    starts: String,
+    ends: String,
+}
+
+#[post("/academic_years", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let starts = crate::util::parse_date(&form.starts);
+    let ends = crate::util::parse_date(&form.ends);
+    let (Some(starts), Some(ends)) = (starts, ends) else {
+        return Ok(Template::render(
+            "routes/administrator/academic_years",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_invalid_dates(),
+        ));
+    };
+
+    if ends <= starts {
+        return Ok(Template::render(
+            "routes/administrator/academic_years",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_invalid_dates(),
+        ));
+    }
+
+    let year = form.year;
+    match database
+        .run(move |c| AcademicYear::create(c, year, starts, ends))
+        .await
+    {
+        Ok(_) => Ok(Template::render(
+            "routes/administrator/academic_years",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_success_message(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            "routes/administrator/academic_years",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_duplicate_year(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[derive(FromForm, Debug)]
+pub struct StatusFormData {
+    status: AcademicYearStatus,
+}
+
+#[post("/academic_years/<id>/status", data = "<form>")]
+pub async fn post_status(
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+    form: Form<StatusFormData>,
+) -> Result<Redirect, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let status = form.status;
+    let changed = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let academic_year = AcademicYear::get_by_id(c, id)?;
+                if !academic_year.status.can_change_to(status) {
+                    return Ok(false);
+                }
+                academic_year.set_status(c, status).map(|()| true)
+            })
+        })
+        .await?;
+
+    if !changed {
+        return Err(Status::BadRequest);
+    }
+
+    Ok(Redirect::to("/academic_years"))
+}
@@ -1,3 +1,6 @@
+pub mod restore;
+pub mod revisions;
+
 use crate::routes::courses;
 use rocket::{
     get,
@@ -5,20 +8,56 @@ use rocket::{
 };
 use rocket_dyn_templates::Template;
 
-use crate::{course::Courses, database::Database, localization::Script, user::Administrator};
+use crate::{
+    course::{Course, CourseId, Courses},
+    database::Database,
+    localization::Script,
+    user::Administrator,
+};
 
-#[get("/courses", rank = 0)]
+#[get("/courses?<deleted>", rank = 0)]
 pub async fn get(
     language: Script,
     administrator: Administrator<'_>,
     database: Database,
     _jar: &CookieJar<'_>,
+    deleted: Option<bool>,
 ) -> Result<Template, Status> {
     let user = administrator.0;
+    let department = administrator.department();
+
+    if deleted.unwrap_or(false) {
+        let courses = database
+            .run(move |c| Courses::get_administered(c, department, true))
+            .await?
+            .0;
+
+        let context = courses::LayoutContext::new(language, user, courses)
+            .await?
+            .deleted();
 
-    let courses = database.run(move |c| Courses::get_all(c)).await?.0;
+        return Ok(Template::render("routes/administrator/courses", context));
+    }
+
+    let courses = database
+        .run(move |c| Courses::get_administered(c, department, false))
+        .await?
+        .0;
 
     let context = courses::LayoutContext::new(language, user, courses).await?;
 
     Ok(Template::render("routes/administrator/courses", context))
 }
+
+/// Administrators of a department only manage its courses
+pub async fn authorize(
+    administrator: &Administrator<'_>,
+    database: &Database,
+    id: CourseId,
+) -> Result<(), Status> {
+    let department = database.run(move |c| Course::get_department(c, id)).await?;
+    if !administrator.administers(department) {
+        return Err(Status::Forbidden);
+    }
+    Ok(())
+}
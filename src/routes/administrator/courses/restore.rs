@@ -0,0 +1,73 @@
+use rocket::{get, http::Status, post, response::Redirect};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseId},
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Clone, Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    restoring_course: Course,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        restoring_course: Course,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            restoring_course,
+        })
+    }
+}
+
+#[get("/courses/restore/<id>", rank = 0)]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: CourseId,
+) -> Result<Template, Status> {
+    let restoring_course = database
+        .run(move |c| Course::get_deleted_by_id(c, id))
+        .await?;
+    if !administrator.administers(restoring_course.department) {
+        return Err(Status::Forbidden);
+    }
+
+    let user = administrator.0;
+    let context = LayoutContext::new(language, user, restoring_course).await?;
+    Ok(Template::render(
+        "routes/administrator/courses/restore",
+        context,
+    ))
+}
+
+#[post("/courses/restore/<id>", rank = 0)]
+pub async fn post(
+    administrator: Administrator<'_>,
+    database: Database,
+    id: CourseId,
+) -> Result<Redirect, Status> {
+    let restoring_course = database
+        .run(move |c| Course::get_deleted_by_id(c, id))
+        .await?;
+    if !administrator.administers(restoring_course.department) {
+        return Err(Status::Forbidden);
+    }
+
+    let url = restoring_course.url.clone();
+    database.run(move |c| restoring_course.restore(c)).await?;
+
+    Ok(Redirect::to(format!("/course/{url}")))
+}
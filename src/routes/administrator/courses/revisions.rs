@@ -0,0 +1,104 @@
+use diesel::Connection;
+use rocket::{get, http::Status, post};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::CourseId,
+    database::Database,
+    error::Error,
+    localization::Script,
+    revision::{CourseState, RevisionEntry},
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    id: CourseId,
+    revisions: Vec<RevisionEntry<CourseState>>,
+    show_success: bool,
+    error_duplicate: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        id: CourseId,
+        revisions: Vec<RevisionEntry<CourseState>>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            id,
+            revisions,
+            show_success: false,
+            error_duplicate: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn duplicate(mut self) -> Self {
+        self.error_duplicate = true;
+        self
+    }
+}
+
+#[get("/courses/revisions/<id>", rank = 0)]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: CourseId,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    super::authorize(&administrator, &database, id).await?;
+
+    let revisions = database
+        .run(move |c| CourseState::get_revisions(c, id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/courses/revisions",
+        LayoutContext::new(language, user, id, revisions).await?,
+    ))
+}
+
+#[post("/courses/revisions/<id>/<revision>", rank = 0)]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: CourseId,
+    revision: u32,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    super::authorize(&administrator, &database, id).await?;
+
+    let result = database
+        .run(move |c| c.transaction(|c| CourseState::get_revision(c, id, revision)?.restore(c, id)))
+        .await;
+
+    let revisions = database
+        .run(move |c| CourseState::get_revisions(c, id))
+        .await?;
+
+    let context = LayoutContext::new(language, user, id, revisions).await?;
+
+    let context = match result {
+        Ok(()) => context.success(),
+        Err(Error::DatabaseDuplicateEntry) => context.duplicate(),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Template::render(
+        "routes/administrator/courses/revisions",
+        context,
+    ))
+}
@@ -0,0 +1,136 @@
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    department::{Department, Departments},
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    departments: Vec<Department>,
+    show_success_message: bool,
+    show_error_duplicate_name: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, database: &Database) -> Result<Self, Error> {
+        let departments = database.run(Departments::get).await?.0;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            departments,
+            show_success_message: false,
+            show_error_duplicate_name: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_duplicate_name(mut self) -> Self {
+        self.show_error_duplicate_name = true;
+        self
+    }
+}
+
+/// Only administrators of every department manage the departments
+#[get("/departments")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/departments",
+        LayoutContext::new(language, user, &database).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    name: String,
+}
+
+#[post("/departments", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let name = form.name.trim().to_owned();
+    render_result(
+        language,
+        user,
+        &database,
+        database.run(move |c| Department::create(c, &name)).await,
+    )
+    .await
+}
+
+#[post("/departments/<id>", data = "<form>")]
+pub async fn post_rename(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let name = form.name.trim().to_owned();
+    render_result(
+        language,
+        user,
+        &database,
+        database
+            .run(move |c| Department::get_by_id(c, id)?.update(c, &name))
+            .await,
+    )
+    .await
+}
+
+async fn render_result(
+    language: Script,
+    user: &User,
+    database: &Database,
+    result: Result<(), Error>,
+) -> Result<Template, Status> {
+    match result {
+        Ok(_) => Ok(Template::render(
+            "routes/administrator/departments",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_success_message(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            "routes/administrator/departments",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_duplicate_name(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,131 @@
+pub mod delete;
+
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    exam::{ExamPeriod, ExamPeriods},
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    exam_periods: Vec<ExamPeriod>,
+    show_success_message: bool,
+    show_error_duplicate_name: bool,
+    show_error_invalid_dates: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, database: Database) -> Result<Self, Error> {
+        let exam_periods = database.run(ExamPeriods::get).await?.0;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            exam_periods,
+            show_success_message: false,
+            show_error_duplicate_name: false,
+            show_error_invalid_dates: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_duplicate_name(mut self) -> Self {
+        self.show_error_duplicate_name = true;
+        self
+    }
+
+    pub fn show_error_invalid_dates(mut self) -> Self {
+        self.show_error_invalid_dates = true;
+        self
+    }
+}
+
+#[get("/exam_periods")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/exam_periods",
+        LayoutContext::new(language, user, database).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    name: String,
+    registration_opens: String,
+    registration_closes: String,
+}
+
+#[post("/exam_periods", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let registration_opens = crate::util::parse_datetime_local(&form.registration_opens);
+    let registration_closes = crate::util::parse_datetime_local(&form.registration_closes);
+    let (Some(registration_opens), Some(registration_closes)) =
+        (registration_opens, registration_closes)
+    else {
+        return Ok(Template::render(
+            "routes/administrator/exam_periods",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_invalid_dates(),
+        ));
+    };
+
+    if registration_closes < registration_opens {
+        return Ok(Template::render(
+            "routes/administrator/exam_periods",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_invalid_dates(),
+        ));
+    }
+
+    let name = form.into_inner().name;
+    match database
+        .run(move |c| ExamPeriod::create(c, name.trim(), registration_opens, registration_closes))
+        .await
+    {
+        Ok(_) => Ok(Template::render(
+            "routes/administrator/exam_periods",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_success_message(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            "routes/administrator/exam_periods",
+            LayoutContext::new(language, user, database)
+                .await?
+                .show_error_duplicate_name(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,70 @@
+use rocket::{get, http::Status, post, response::Redirect};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    exam::ExamPeriod,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    deleting_exam_period: ExamPeriod,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        deleting_exam_period: ExamPeriod,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            deleting_exam_period,
+        })
+    }
+}
+
+#[get("/exam_periods/delete/<id>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let deleting_exam_period = database.run(move |c| ExamPeriod::get_by_id(c, id)).await?;
+
+    let user = administrator.0;
+    let context = LayoutContext::new(language, user, deleting_exam_period).await?;
+    Ok(Template::render(
+        "routes/administrator/exam_periods/delete",
+        context,
+    ))
+}
+
+#[post("/exam_periods/delete/<id>", rank = 0)]
+pub async fn post(
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Redirect, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let deleting_exam_period = database.run(move |c| ExamPeriod::get_by_id(c, id)).await?;
+
+    database
+        .run(move |c| deleting_exam_period.delete(c))
+        .await?;
+
+    Ok(Redirect::to("/exam_periods"))
+}
@@ -1,4 +1,5 @@
 pub mod delete;
+pub mod edit;
 
 use rocket::{form::Form, get, http::Status, post, FromForm};
 use rocket_dyn_templates::Template;
@@ -50,6 +51,9 @@ pub async fn get(
     administrator: Administrator<'_>,
     database: Database,
 ) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
     let user = administrator.0;
 
     Ok(Template::render(
@@ -70,6 +74,9 @@ pub async fn post(
     database: Database,
     form: Form<FormData>,
 ) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
     let user = administrator.0;
 
     match database
@@ -1,9 +1,11 @@
+use diesel::Connection;
 use rocket::{get, http::Status, post, response::Redirect};
 use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
     base_layout_context::BaseLayoutContext,
+    catchers::TemplateOrRedirect,
     database::Database,
     error::Error,
     index::Generation,
@@ -16,19 +18,37 @@ struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     deleting_generation: Generation,
+    students: u32,
+    show_error_has_students: bool,
 }
 
 impl LayoutContext {
     pub async fn new(
         language: Script,
         user: &User,
-        deleting_generation: Generation,
+        database: &Database,
+        id: u32,
     ) -> Result<Self, Error> {
+        let (deleting_generation, students) = database
+            .run(move |c| -> Result<_, Error> {
+                let generation = Generation::get_by_id(c, id)?;
+                let students = generation.count_students(c)?;
+                Ok((generation, students))
+            })
+            .await?;
+
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             deleting_generation,
+            students,
+            show_error_has_students: false,
         })
     }
+
+    pub fn show_error_has_students(mut self) -> Self {
+        self.show_error_has_students = true;
+        self
+    }
 }
 
 #[get("/generations/delete/<id>")]
@@ -38,10 +58,11 @@ pub async fn get(
     database: Database,
     id: u32,
 ) -> Result<Template, Status> {
-    let deleting_generation = database.run(move |c| Generation::get_by_id(c, id)).await?;
-
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
     let user = administrator.0;
-    let context = LayoutContext::new(language, user, deleting_generation).await?;
+    let context = LayoutContext::new(language, user, &database, id).await?;
     Ok(Template::render(
         "routes/administrator/generations/delete",
         context,
@@ -50,13 +71,37 @@ pub async fn get(
 
 #[post("/generations/delete/<id>", rank = 0)]
 pub async fn post(
-    _administrator: Administrator<'_>,
+    language: Script,
+    administrator: Administrator<'_>,
     database: Database,
     id: u32,
-) -> Result<Redirect, Status> {
-    let deleting_generation = database.run(move |c| Generation::get_by_id(c, id)).await?;
+) -> Result<TemplateOrRedirect, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
 
-    database.run(move |c| deleting_generation.delete(c)).await?;
+    let deleted = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let generation = Generation::get_by_id(c, id)?;
+                if generation.count_students(c)? > 0 {
+                    return Ok(false);
+                }
+                generation.delete(c)?;
+                Ok::<_, Error>(true)
+            })
+        })
+        .await?;
+
+    if !deleted {
+        return Ok(TemplateOrRedirect::Template(Template::render(
+            "routes/administrator/generations/delete",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_error_has_students(),
+        )));
+    }
 
-    Ok(Redirect::to("/generations"))
+    Ok(TemplateOrRedirect::Redirect(Redirect::to("/generations")))
 }
@@ -0,0 +1,114 @@
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    index::Generation,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    editing_generation: Generation,
+    students: u32,
+    show_success_message: bool,
+    show_error_duplicate_year: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        id: u32,
+    ) -> Result<Self, Error> {
+        let (editing_generation, students) = database
+            .run(move |c| -> Result<_, Error> {
+                let generation = Generation::get_by_id(c, id)?;
+                let students = generation.count_students(c)?;
+                Ok((generation, students))
+            })
+            .await?;
+
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            editing_generation,
+            students,
+            show_success_message: false,
+            show_error_duplicate_year: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_duplicate_year(mut self) -> Self {
+        self.show_error_duplicate_year = true;
+        self
+    }
+}
+
+#[get("/generations/edit/<id>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/generations/edit",
+        LayoutContext::new(language, user, &database, id).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    year: u32,
+}
+
+#[post("/generations/edit/<id>", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let year = form.year;
+    match database
+        .run(move |c| Generation::get_by_id(c, id)?.update(c, year))
+        .await
+    {
+        Ok(_) => Ok(Template::render(
+            "routes/administrator/generations/edit",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_success_message(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            "routes/administrator/generations/edit",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_error_duplicate_year(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,4 +1,8 @@
+pub mod academic_years;
 pub mod courses;
+pub mod departments;
+pub mod exam_periods;
 pub mod generations;
 pub mod programs;
 pub mod users;
+pub mod webhooks;
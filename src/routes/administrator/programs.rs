@@ -1,4 +1,6 @@
+pub mod curriculum;
 pub mod delete;
+pub mod edit;
 
 use rocket::{form::Form, get, http::Status, post, FromForm};
 use rocket_dyn_templates::Template;
@@ -7,6 +9,7 @@ use serde::Serialize;
 use crate::{
     base_layout_context::BaseLayoutContext,
     database::Database,
+    department::{Department, Departments},
     error::Error,
     index::{Program, Programs},
     localization::Script,
@@ -18,6 +21,7 @@ struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     programs: Vec<Program>,
+    departments: Vec<Department>,
     show_success_message: bool,
     show_error_duplicate_name: bool,
     show_error_short_name_too_long: bool,
@@ -25,10 +29,19 @@ struct LayoutContext {
 
 impl LayoutContext {
     pub async fn new(language: Script, user: &User, database: Database) -> Result<Self, Error> {
-        let programs = database.run(|c| Programs::get(c)).await?.0;
+        let department = user.department;
+        let (programs, departments) = database
+            .run(move |c| -> Result<_, Error> {
+                Ok((
+                    Programs::get_administered(c, department)?.0,
+                    Departments::get_administered(c, department)?.0,
+                ))
+            })
+            .await?;
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             programs,
+            departments,
             show_success_message: false,
             show_error_duplicate_name: false,
             show_error_short_name_too_long: false,
@@ -69,6 +82,7 @@ pub async fn get(
 pub struct FormData {
     short_name: String,
     full_name: String,
+    department: Option<u32>,
 }
 
 #[post("/programs", data = "<form>")]
@@ -93,8 +107,16 @@ pub async fn post(
         ));
     }
 
+    let department = administrator.assign_department(form.department);
     match database
-        .run(move |c| Program::create(c, form.short_name.clone(), form.full_name.clone()))
+        .run(move |c| {
+            Program::create(
+                c,
+                form.short_name.clone(),
+                form.full_name.clone(),
+                department,
+            )
+        })
         .await
     {
         Ok(_) => Ok(Template::render(
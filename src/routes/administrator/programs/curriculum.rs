@@ -0,0 +1,123 @@
+use rocket::{form::Form, get, http::Status, post, response::Redirect, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseId, Courses},
+    curriculum::Curriculum,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    curriculum: Curriculum,
+    courses: Vec<Course>,
+    show_success_message: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        program: u32,
+    ) -> Result<Self, Error> {
+        let department = user.department;
+        let (curriculum, courses) = database
+            .run(move |c| -> Result<_, Error> {
+                let mut courses = Courses::get_administered(c, department, false)?.0;
+                courses.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| b.year.cmp(&a.year)));
+                Ok((Curriculum::get(c, program)?, courses))
+            })
+            .await?;
+
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            curriculum,
+            courses,
+            show_success_message: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+}
+
+#[get("/programs/curriculum/<program>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    program: u32,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    super::edit::authorize(&administrator, &database, program).await?;
+
+    Ok(Template::render(
+        "routes/administrator/programs/curriculum",
+        LayoutContext::new(language, user, &database, program).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    course: CourseId,
+    mandatory: bool,
+}
+
+#[post("/programs/curriculum/<program>", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    program: u32,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    super::edit::authorize(&administrator, &database, program).await?;
+
+    // Deleted courses aren't found, only the administered ones can be added
+    let (course, mandatory) = (form.course, form.mandatory);
+    let department = database
+        .run(move |c| Course::get_by_id(c, course))
+        .await?
+        .department;
+    if !administrator.administers(department) {
+        return Err(Status::Forbidden);
+    }
+
+    database
+        .run(move |c| Curriculum::set(c, program, course, mandatory))
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/programs/curriculum",
+        LayoutContext::new(language, user, &database, program)
+            .await?
+            .show_success_message(),
+    ))
+}
+
+#[post("/programs/curriculum/<program>/remove/<course>")]
+pub async fn post_remove(
+    administrator: Administrator<'_>,
+    database: Database,
+    program: u32,
+    course: CourseId,
+) -> Result<Redirect, Status> {
+    super::edit::authorize(&administrator, &database, program).await?;
+
+    database
+        .run(move |c| Curriculum::remove(c, program, course))
+        .await?;
+
+    Ok(Redirect::to(format!("/programs/curriculum/{program}")))
+}
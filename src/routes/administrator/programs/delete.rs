@@ -1,9 +1,11 @@
+use diesel::Connection;
 use rocket::{get, http::Status, post, response::Redirect};
 use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
     base_layout_context::BaseLayoutContext,
+    catchers::TemplateOrRedirect,
     database::Database,
     error::Error,
     index::Program,
@@ -16,19 +18,37 @@ struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     deleting_program: Program,
+    students: u32,
+    show_error_has_students: bool,
 }
 
 impl LayoutContext {
     pub async fn new(
         language: Script,
         user: &User,
-        deleting_program: Program,
+        database: &Database,
+        id: u32,
     ) -> Result<Self, Error> {
+        let (deleting_program, students) = database
+            .run(move |c| -> Result<_, Error> {
+                let program = Program::get_by_id(c, id)?;
+                let students = program.count_students(c)?;
+                Ok((program, students))
+            })
+            .await?;
+
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             deleting_program,
+            students,
+            show_error_has_students: false,
         })
     }
+
+    pub fn show_error_has_students(mut self) -> Self {
+        self.show_error_has_students = true;
+        self
+    }
 }
 
 #[get("/programs/delete/<id>")]
@@ -38,10 +58,9 @@ pub async fn get(
     database: Database,
     id: u32,
 ) -> Result<Template, Status> {
-    let deleting_program = database.run(move |c| Program::get_by_id(c, id)).await?;
-
     let user = administrator.0;
-    let context = LayoutContext::new(language, user, deleting_program).await?;
+    super::edit::authorize(&administrator, &database, id).await?;
+    let context = LayoutContext::new(language, user, &database, id).await?;
     Ok(Template::render(
         "routes/administrator/programs/delete",
         context,
@@ -50,13 +69,35 @@ pub async fn get(
 
 #[post("/programs/delete/<id>", rank = 0)]
 pub async fn post(
-    _administrator: Administrator<'_>,
+    language: Script,
+    administrator: Administrator<'_>,
     database: Database,
     id: u32,
-) -> Result<Redirect, Status> {
-    let deleting_program = database.run(move |c| Program::get_by_id(c, id)).await?;
+) -> Result<TemplateOrRedirect, Status> {
+    let user = administrator.0;
+    super::edit::authorize(&administrator, &database, id).await?;
 
-    database.run(move |c| deleting_program.delete(c)).await?;
+    let deleted = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let program = Program::get_by_id(c, id)?;
+                if program.count_students(c)? > 0 {
+                    return Ok(false);
+                }
+                program.delete(c)?;
+                Ok::<_, Error>(true)
+            })
+        })
+        .await?;
+
+    if !deleted {
+        return Ok(TemplateOrRedirect::Template(Template::render(
+            "routes/administrator/programs/delete",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_error_has_students(),
+        )));
+    }
 
-    Ok(Redirect::to("/programs"))
+    Ok(TemplateOrRedirect::Redirect(Redirect::to("/programs")))
 }
@@ -0,0 +1,149 @@
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    department::{Department, Departments},
+    error::Error,
+    index::Program,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    editing_program: Program,
+    departments: Vec<Department>,
+    students: u32,
+    show_success_message: bool,
+    show_error_duplicate_name: bool,
+    show_error_short_name_too_long: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        id: u32,
+    ) -> Result<Self, Error> {
+        let department = user.department;
+        let (editing_program, departments, students) = database
+            .run(move |c| -> Result<_, Error> {
+                let program = Program::get_by_id(c, id)?;
+                let departments = Departments::get_administered(c, department)?.0;
+                let students = program.count_students(c)?;
+                Ok((program, departments, students))
+            })
+            .await?;
+
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            editing_program,
+            departments,
+            students,
+            show_success_message: false,
+            show_error_duplicate_name: false,
+            show_error_short_name_too_long: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_duplicate_name(mut self) -> Self {
+        self.show_error_duplicate_name = true;
+        self
+    }
+
+    pub fn show_error_short_name_too_long(mut self) -> Self {
+        self.show_error_short_name_too_long = true;
+        self
+    }
+}
+
+#[get("/programs/edit/<id>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    authorize(&administrator, &database, id).await?;
+
+    Ok(Template::render(
+        "routes/administrator/programs/edit",
+        LayoutContext::new(language, user, &database, id).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    short_name: String,
+    full_name: String,
+    department: Option<u32>,
+}
+
+#[post("/programs/edit/<id>", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = administrator.0;
+    authorize(&administrator, &database, id).await?;
+
+    // Same limit as when creating a program
+    if form.short_name.len() > 2 {
+        return Ok(Template::render(
+            "routes/administrator/programs/edit",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_error_short_name_too_long(),
+        ));
+    }
+
+    let department = administrator.assign_department(form.department);
+    match database
+        .run(move |c| {
+            Program::get_by_id(c, id)?.update(c, &form.short_name, &form.full_name, department)
+        })
+        .await
+    {
+        Ok(_) => Ok(Template::render(
+            "routes/administrator/programs/edit",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_success_message(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            "routes/administrator/programs/edit",
+            LayoutContext::new(language, user, &database, id)
+                .await?
+                .show_error_duplicate_name(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Administrators of a department only manage its programs
+pub async fn authorize(
+    administrator: &Administrator<'_>,
+    database: &Database,
+    id: u32,
+) -> Result<(), Status> {
+    let program = database.run(move |c| Program::get_by_id(c, id)).await?;
+    if !administrator.administers(program.department) {
+        return Err(Status::Forbidden);
+    }
+    Ok(())
+}
@@ -1,6 +1,10 @@
 pub mod create;
 pub mod delete;
 pub mod edit;
+pub mod restore;
+pub mod revisions;
+pub mod status;
+pub mod transcript;
 
 use rocket::{form::Form, get, http::Status, post, FromForm};
 use rocket_dyn_templates::Template;
@@ -8,11 +12,11 @@ use serde::Serialize;
 
 use crate::{
     base_layout_context::BaseLayoutContext,
-    components::users::{self, ControlTypeOptions},
+    components::users::{self, ControlTypeOptions, EditOptions},
     database::Database,
     error::Error,
     localization::Script,
-    user::{Administrator, User},
+    user::{Administrator, User, UserId, UserWithIndex},
 };
 
 #[derive(Serialize, Debug)]
@@ -43,7 +47,10 @@ pub async fn get(
 ) -> Result<Template, Status> {
     let user = administrator.0;
 
-    let users_context = users::LayoutContext::new(database, None, ControlTypeOptions::Edit).await?;
+    let control_type = ControlTypeOptions::Edit(EditOptions {
+        department: administrator.department(),
+    });
+    let users_context = users::LayoutContext::new(database, None, control_type).await?;
 
     let context = LayoutContext::new(language, user, users_context).await?;
 
@@ -67,10 +74,27 @@ pub async fn post(
     let users_context = users::LayoutContext::new(
         database,
         Some(form.into_inner().users_form),
-        ControlTypeOptions::Edit,
+        ControlTypeOptions::Edit(EditOptions {
+            department: administrator.department(),
+        }),
     )
     .await?;
     let context = LayoutContext::new(language, user, users_context).await?;
 
     Ok(Template::render("routes/administrator/users", context))
 }
+
+/// Administrators of a department only manage its users
+pub async fn authorize(
+    administrator: &Administrator<'_>,
+    database: &Database,
+    id: UserId,
+) -> Result<(), Status> {
+    let user = database
+        .run(move |c| UserWithIndex::get_including_deleted(c, id))
+        .await?;
+    if !administrator.manages_user(&user) {
+        return Err(Status::Forbidden);
+    }
+    Ok(())
+}
@@ -13,7 +13,7 @@ use crate::{
     index::{Generation, Generations, Index, Program, Programs},
     localization::Script,
     mail::Mail,
-    user::{AccountType, Administrator, User},
+    user::{AccountType, Administrator, User, UserId},
 };
 
 #[derive(Serialize, Debug)]
@@ -43,10 +43,14 @@ enum AccountTypeWithData {
 impl AccountTypeWithData {
     pub async fn new_student(
         database: &Database,
+        department: Option<u32>,
         previous: Option<FormDataStudent>,
     ) -> Result<AccountTypeWithData, Error> {
         let generations = database.run(|c| Generations::get(c)).await?.0;
-        let programs = database.run(|c| Programs::get(c)).await?.0;
+        let programs = database
+            .run(move |c| Programs::get_administered(c, department))
+            .await?
+            .0;
         Ok(AccountTypeWithData::Student(StudentData {
             programs,
             generations,
@@ -77,6 +81,7 @@ struct LayoutContext {
     show_success_message: bool,
     show_invalid_email: bool,
     show_duplicate_data: bool,
+    deleted_duplicate: Option<UserId>,
 }
 
 impl LayoutContext {
@@ -87,6 +92,7 @@ impl LayoutContext {
             show_success_message: false,
             show_duplicate_data: false,
             show_invalid_email: false,
+            deleted_duplicate: None,
         })
     }
 
@@ -100,6 +106,11 @@ impl LayoutContext {
         self
     }
 
+    pub fn deleted_duplicate(mut self, deleted_user: UserId) -> Self {
+        self.deleted_duplicate = Some(deleted_user);
+        self
+    }
+
     pub fn invalid_email(mut self) -> Self {
         self.show_invalid_email = true;
         self
@@ -135,7 +146,9 @@ pub async fn get_with_account_type(
     let template_path = "routes/administrator/users/create";
 
     let account_type = match account_type {
-        AccountType::Student => AccountTypeWithData::new_student(&database, None).await?,
+        AccountType::Student => {
+            AccountTypeWithData::new_student(&database, administrator.department(), None).await?
+        }
         AccountType::Professor => AccountTypeWithData::new_professor(None).await?,
         AccountType::Administrator => AccountTypeWithData::new_administrator(None).await?,
     };
@@ -149,6 +162,28 @@ pub async fn get_with_account_type(
     Ok(Template::render(template_path, context))
 }
 
+/// Soft-deleted users keep their email address, so when one of them is the
+/// cause of the collision the administrator is offered to restore it instead
+async fn render_duplicate(
+    database: &Database,
+    context: LayoutContext,
+    email: String,
+) -> Result<Template, Status> {
+    let deleted_user = database
+        .run(move |c| User::get_deleted_by_email(c, &email))
+        .await?;
+
+    let context = match deleted_user {
+        Some(deleted_user) => context.deleted_duplicate(deleted_user.id),
+        None => context.duplicate_data(),
+    };
+
+    Ok(Template::render(
+        "routes/administrator/users/create",
+        context,
+    ))
+}
+
 fn generate_random_password() -> String {
     rand::thread_rng()
         .sample_iter(&Alphanumeric)
@@ -195,12 +230,14 @@ pub async fn post_administrator(
     let builder = User::builder(form.email.clone(), password)
         .with_first_name(first_name)
         .with_last_name(last_name)
+        .with_department(administrator.department())
         .with_account_type(AccountType::Administrator);
 
+    let email = form.email.clone();
     match database.run(move |c| builder.build().create(c)).await {
         Ok(_) => (),
         Err(Error::DatabaseDuplicateEntry) => {
-            return Ok(Template::render(template_path, context.duplicate_data()))
+            return render_duplicate(&database, context, email).await
         }
         Err(e) => return Err(e.into()),
     }
@@ -248,12 +285,14 @@ pub async fn post_professor(
     let builder = User::builder(form.email.clone(), password)
         .with_first_name(first_name)
         .with_last_name(last_name)
+        .with_department(administrator.department())
         .with_account_type(AccountType::Professor);
 
+    let email = form.email.clone();
     match database.run(move |c| builder.build().create(c)).await {
         Ok(_) => (),
         Err(Error::DatabaseDuplicateEntry) => {
-            return Ok(Template::render(template_path, context.duplicate_data()))
+            return render_duplicate(&database, context, email).await
         }
         Err(e) => return Err(e.into()),
     }
@@ -283,7 +322,9 @@ pub async fn post_student(
 ) -> Result<Template, Status> {
     let user = administrator.0;
 
-    let account_type = AccountTypeWithData::new_student(&database, Some(form.clone())).await?;
+    let account_type =
+        AccountTypeWithData::new_student(&database, administrator.department(), Some(form.clone()))
+            .await?;
 
     let context = LayoutContext::new(language, user)
         .await?
@@ -304,16 +345,26 @@ pub async fn post_student(
     let builder = User::builder(form.email.clone(), password)
         .with_first_name(first_name)
         .with_last_name(last_name)
+        .with_department(administrator.department())
         .with_account_type(AccountType::Student);
 
     let index_number = form.index_number;
+    let email = form.email.clone();
+
+    // Administrators of a department only add students to its programs
+    let program_name = form.program.clone();
+    let program = database
+        .run(move |c| Program::get_by_short_name(c, &program_name))
+        .await?;
+    if !administrator.administers(program.department) {
+        return Err(Status::Forbidden);
+    }
 
     match database
         .run(move |c| {
             c.transaction(|c| {
                 builder.build().create(c)?;
                 let new_user = User::get_by_email(c, &form.email.clone())?;
-                let program = Program::get_by_short_name(c, &form.program)?;
                 let generation = Generation::get_by_year(c, form.generation)?;
                 Index::create(c, program.id, generation.id, index_number, new_user.id)
             })
@@ -322,7 +373,7 @@ pub async fn post_student(
     {
         Ok(_) => (),
         Err(Error::DatabaseDuplicateEntry) => {
-            return Ok(Template::render(template_path, context.duplicate_data()))
+            return render_duplicate(&database, context, email).await
         }
         Err(e) => return Err(e.into()),
     }
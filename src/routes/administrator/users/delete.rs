@@ -33,6 +33,7 @@ pub async fn get(
     database: Database,
     id: u32,
 ) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
     let deleting_user = database.run(move |c| User::get_by_id(c, id)).await?;
 
     let user = administrator.0;
@@ -45,10 +46,11 @@ pub async fn get(
 
 #[post("/users/delete/<id>", rank = 0)]
 pub async fn post(
-    _administrator: Administrator<'_>,
+    administrator: Administrator<'_>,
     database: Database,
     id: u32,
 ) -> Result<Redirect, Status> {
+    super::authorize(&administrator, &database, id).await?;
     let deleting_user = database.run(move |c| User::get_by_id(c, id)).await?;
 
     database
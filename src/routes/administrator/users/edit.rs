@@ -1,29 +1,76 @@
+use diesel::Connection;
 use rocket::{form::Form, get, http::Status, post, response::Redirect, FromForm};
 use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
     base_layout_context::BaseLayoutContext,
+    catchers::TemplateOrRedirect,
     database::Database,
+    department::{Department, Departments},
     error::Error,
+    index::{Generation, Generations, Index, IndexRevision, Program, Programs},
     localization::Script,
     user::{AccountType, Administrator, User},
 };
 
-#[derive(Clone, Serialize, Debug)]
+#[derive(Serialize, Debug)]
 struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     editing_user: User,
+    index: Option<Index>,
+    index_revisions: Vec<IndexRevision>,
+    programs: Vec<Program>,
+    generations: Vec<Generation>,
+    departments: Vec<Department>,
+    show_error_duplicate: bool,
+    show_error_index_required: bool,
 }
 
 impl LayoutContext {
-    pub async fn new(language: Script, user: &User, editing_user: User) -> Result<Self, Error> {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        id: u32,
+    ) -> Result<Self, Error> {
+        let department = user.department;
+        let (editing_user, index, index_revisions, programs, generations, departments) = database
+            .run(move |c| {
+                Ok::<_, Error>((
+                    User::get_by_id(c, id)?,
+                    Index::get_by_student(c, id)?,
+                    IndexRevision::get_all(c, id)?,
+                    Programs::get_administered(c, department)?.0,
+                    Generations::get(c)?.0,
+                    Departments::get_administered(c, department)?.0,
+                ))
+            })
+            .await?;
+
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             editing_user,
+            index,
+            index_revisions,
+            programs,
+            generations,
+            departments,
+            show_error_duplicate: false,
+            show_error_index_required: false,
         })
     }
+
+    pub fn duplicate(mut self) -> Self {
+        self.show_error_duplicate = true;
+        self
+    }
+
+    pub fn index_required(mut self) -> Self {
+        self.show_error_index_required = true;
+        self
+    }
 }
 
 #[get("/users/edit/<id>", rank = 0)]
@@ -33,10 +80,9 @@ pub async fn get(
     database: Database,
     id: u32,
 ) -> Result<Template, Status> {
-    let editing_user = database.run(move |c| User::get_by_id(c, id)).await?;
-
+    super::authorize(&administrator, &database, id).await?;
     let user = administrator.0;
-    let context = LayoutContext::new(language, user, editing_user).await?;
+    let context = LayoutContext::new(language, user, &database, id).await?;
     Ok(Template::render("routes/administrator/users/edit", context))
 }
 
@@ -46,50 +92,114 @@ pub struct FormData {
     pub last_name: String,
     pub email: String,
     pub account_type: u8,
+    pub program: Option<u32>,
+    pub generation: Option<u32>,
+    pub index_number: Option<u32>,
+    pub department: Option<u32>,
 }
 
 #[post("/users/edit/<id>", data = "<form>", rank = 0)]
 pub async fn post(
-    _administrator: Administrator<'_>,
+    language: Script,
+    administrator: Administrator<'_>,
     database: Database,
     form: Form<FormData>,
     id: u32,
-) -> Result<Redirect, Status> {
-    let editing_user = database.run(move |c| User::get_by_id(c, id)).await?;
-    let email = form.email.clone();
-
-    database
-        .run(move |c| editing_user.update_email(c, &email))
-        .await?;
-
-    let editing_user = database.run(move |c| User::get_by_id(c, id)).await?;
+) -> Result<TemplateOrRedirect, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+    let form = form.into_inner();
     let account_type = AccountType::try_from(form.account_type)?;
 
-    database
-        .run(move |c| editing_user.update_account_type(c, account_type))
-        .await?;
-
-    let editing_user = database.run(move |c| User::get_by_id(c, id)).await?;
-    let first_name = if form.first_name.is_empty() {
-        None
-    } else {
-        Some(form.first_name.clone())
+    // Only students have an index, it is removed from anyone who stops
+    // being one
+    let index = match (
+        account_type,
+        form.program,
+        form.generation,
+        form.index_number,
+    ) {
+        (AccountType::Student, Some(program), Some(generation), Some(number)) => {
+            Some((program, generation, number))
+        }
+        (AccountType::Student, ..) => {
+            let context = LayoutContext::new(language, user, &database, id)
+                .await?
+                .index_required();
+            return Ok(TemplateOrRedirect::Template(Template::render(
+                "routes/administrator/users/edit",
+                context,
+            )));
+        }
+        _ => None,
     };
 
-    database
-        .run(move |c| editing_user.update_first_name(c, first_name.as_deref()))
-        .await?;
+    // Administrators of a department only give out indices in its programs
+    if let Some((program, generation, _)) = index {
+        let program = database
+            .run(move |c| Program::get_by_id(c, program))
+            .await?;
+        if !administrator.administers(program.department) {
+            return Err(Status::Forbidden);
+        }
 
-    let editing_user = database.run(move |c| User::get_by_id(c, id)).await?;
-    let last_name = if form.last_name.is_empty() {
-        None
-    } else {
-        Some(form.last_name.clone())
-    };
+        match database
+            .run(move |c| Generation::get_by_id(c, generation))
+            .await
+        {
+            Ok(_) => (),
+            Err(Error::DatabaseEntryNotFound) => {
+                let context = LayoutContext::new(language, user, &database, id)
+                    .await?
+                    .index_required();
+                return Ok(TemplateOrRedirect::Template(Template::render(
+                    "routes/administrator/users/edit",
+                    context,
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
 
-    database
-        .run(move |c| editing_user.update_last_name(c, last_name.as_deref()))
-        .await?;
+    let department = administrator.assign_department(form.department);
+    let first_name = Some(form.first_name).filter(|s| !s.is_empty());
+    let last_name = Some(form.last_name).filter(|s| !s.is_empty());
+    let email = form.email;
 
-    Ok(Redirect::to("/users"))
+    let result = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let editing_user = User::get_by_id(c, id)?;
+                editing_user.update_email(c, &email)?;
+                editing_user.update_account_type(c, account_type)?;
+                editing_user.update_first_name(c, first_name.as_deref())?;
+                editing_user.update_last_name(c, last_name.as_deref())?;
+                editing_user.update_department(c, department)?;
+
+                match index {
+                    Some((program, generation, number)) => {
+                        Index::set_for_student(c, id, program, generation, number)
+                    }
+                    None => match Index::get_by_student(c, id)? {
+                        Some(index) => index.delete(c),
+                        None => Ok(()),
+                    },
+                }
+            })
+        })
+        .await;
+
+    match result {
+        Ok(()) => Ok(TemplateOrRedirect::Redirect(Redirect::to("/users"))),
+        Err(Error::DatabaseDuplicateEntry) => {
+            let context = LayoutContext::new(language, user, &database, id)
+                .await?
+                .duplicate();
+            Ok(TemplateOrRedirect::Template(Template::render(
+                "routes/administrator/users/edit",
+                context,
+            )))
+        }
+        Err(e) => Err(e.into()),
+    }
 }
@@ -0,0 +1,65 @@
+use rocket::{get, http::Status, post, response::Redirect};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+};
+
+#[derive(Clone, Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    restoring_user: User,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, restoring_user: User) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            restoring_user,
+        })
+    }
+}
+
+#[get("/users/restore/<id>", rank = 0)]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let restoring_user = database
+        .run(move |c| User::get_deleted_by_id(c, id))
+        .await?;
+
+    let user = administrator.0;
+    let context = LayoutContext::new(language, user, restoring_user).await?;
+    Ok(Template::render(
+        "routes/administrator/users/restore",
+        context,
+    ))
+}
+
+#[post("/users/restore/<id>", rank = 0)]
+pub async fn post(
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Redirect, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let restoring_user = database
+        .run(move |c| User::get_deleted_by_id(c, id))
+        .await?;
+
+    database
+        .run(move |c| restoring_user.update_deleted(c, false))
+        .await?;
+
+    Ok(Redirect::to(format!("/users/edit/{id}")))
+}
@@ -0,0 +1,111 @@
+use diesel::Connection;
+use rocket::{get, http::Status, post};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    revision::{RevisionEntry, UserState},
+    user::{Administrator, User, UserId},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    id: UserId,
+    revisions: Vec<RevisionEntry<UserState>>,
+    show_success: bool,
+    error_duplicate: bool,
+    error_index_required: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        id: UserId,
+        revisions: Vec<RevisionEntry<UserState>>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            id,
+            revisions,
+            show_success: false,
+            error_duplicate: false,
+            error_index_required: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn duplicate(mut self) -> Self {
+        self.error_duplicate = true;
+        self
+    }
+
+    pub fn index_required(mut self) -> Self {
+        self.error_index_required = true;
+        self
+    }
+}
+
+#[get("/users/revisions/<id>", rank = 0)]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+
+    let revisions = database
+        .run(move |c| UserState::get_revisions(c, id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/users/revisions",
+        LayoutContext::new(language, user, id, revisions).await?,
+    ))
+}
+
+#[post("/users/revisions/<id>/<revision>", rank = 0)]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+    revision: u32,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+
+    let result = database
+        .run(move |c| c.transaction(|c| UserState::get_revision(c, id, revision)?.restore(c, id)))
+        .await;
+
+    let revisions = database
+        .run(move |c| UserState::get_revisions(c, id))
+        .await?;
+
+    let context = LayoutContext::new(language, user, id, revisions).await?;
+
+    let context = match result {
+        Ok(()) => context.success(),
+        Err(Error::DatabaseDuplicateEntry) => context.duplicate(),
+        Err(Error::IndexRequired) => context.index_required(),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(Template::render(
+        "routes/administrator/users/revisions",
+        context,
+    ))
+}
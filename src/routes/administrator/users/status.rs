@@ -0,0 +1,161 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    student_status::{Graduation, StudentStatus, StudentStatusChange},
+    user::{AccountType, Administrator, User, UserId, UserWithIndex},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    student: UserWithIndex,
+    changes: Vec<StudentStatusChange>,
+    show_success_message: bool,
+    show_error_invalid_transition: bool,
+    show_error_reason_required: bool,
+    show_error_graduation_required: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        id: UserId,
+    ) -> Result<Self, Error> {
+        let (student, changes) = database
+            .run(move |c| -> Result<_, Error> {
+                let student = UserWithIndex::get(c, id)?;
+                if student.user.account_type != AccountType::Student {
+                    return Err(Error::DatabaseEntryNotFound);
+                }
+                Ok((student, StudentStatusChange::get_all(c, id)?))
+            })
+            .await?;
+
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            student,
+            changes,
+            show_success_message: false,
+            show_error_invalid_transition: false,
+            show_error_reason_required: false,
+            show_error_graduation_required: false,
+        })
+    }
+
+    pub fn show_success_message(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn show_error_invalid_transition(mut self) -> Self {
+        self.show_error_invalid_transition = true;
+        self
+    }
+
+    pub fn show_error_reason_required(mut self) -> Self {
+        self.show_error_reason_required = true;
+        self
+    }
+
+    pub fn show_error_graduation_required(mut self) -> Self {
+        self.show_error_graduation_required = true;
+        self
+    }
+}
+
+#[get("/users/status/<id>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/users/status",
+        LayoutContext::new(language, user, &database, id).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    status: StudentStatus,
+    reason: String,
+    graduated_on: String,
+    thesis_grade: Option<u8>,
+}
+
+#[post("/users/status/<id>", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+    let context = LayoutContext::new(language.clone(), user, &database, id).await?;
+
+    let reason = form.reason.trim().to_owned();
+    if reason.is_empty() {
+        return Ok(Template::render(
+            "routes/administrator/users/status",
+            context.show_error_reason_required(),
+        ));
+    }
+
+    let status = form.status;
+    if !context.student.user.student_status.can_change_to(status) {
+        return Ok(Template::render(
+            "routes/administrator/users/status",
+            context.show_error_invalid_transition(),
+        ));
+    }
+
+    // A graduation needs the date and a passing thesis grade
+    let graduation = if status == StudentStatus::Graduated {
+        let graduated_on = crate::util::parse_date(&form.graduated_on);
+        let thesis_grade = form.thesis_grade.filter(|grade| (6..=10).contains(grade));
+        let (Some(graduated_on), Some(thesis_grade)) = (graduated_on, thesis_grade) else {
+            return Ok(Template::render(
+                "routes/administrator/users/status",
+                context.show_error_graduation_required(),
+            ));
+        };
+        Some(Graduation {
+            graduated_on,
+            thesis_grade,
+        })
+    } else {
+        None
+    };
+
+    let changed_by = user.id;
+    database
+        .run(move |c| {
+            c.transaction(|c| {
+                StudentStatusChange::create(c, id, status, &reason, graduation, changed_by)
+            })
+        })
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/users/status",
+        LayoutContext::new(language, user, &database, id)
+            .await?
+            .show_success_message(),
+    ))
+}
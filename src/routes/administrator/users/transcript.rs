@@ -0,0 +1,46 @@
+use rocket::{get, http::ContentType, http::Status};
+use rocket_dyn_templates::Template;
+
+use crate::{
+    database::Database,
+    localization::Script,
+    routes::transcript,
+    transcript::Transcript,
+    user::{Administrator, UserId},
+};
+
+#[get("/users/transcript/<id>")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+) -> Result<Template, Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let user = administrator.0;
+
+    let transcript = database.run(move |c| Transcript::get(c, id)).await?;
+
+    let context = transcript::LayoutContext::new(
+        language,
+        user,
+        transcript,
+        format!("/users/transcript/{id}/pdf"),
+        "/users".to_owned(),
+    )
+    .await?;
+
+    Ok(Template::render("routes/transcript", context))
+}
+
+#[get("/users/transcript/<id>/pdf")]
+pub async fn get_pdf(
+    administrator: Administrator<'_>,
+    database: Database,
+    id: UserId,
+) -> Result<(ContentType, Vec<u8>), Status> {
+    super::authorize(&administrator, &database, id).await?;
+    let transcript = database.run(move |c| Transcript::get(c, id)).await?;
+
+    Ok((ContentType::PDF, transcript.pdf()))
+}
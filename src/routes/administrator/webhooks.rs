@@ -0,0 +1,145 @@
+pub mod deliveries;
+
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+    webhook::{Webhook, WebhookEvents},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    webhooks: Vec<Webhook>,
+    created_secret: Option<String>,
+    show_deleted_message: bool,
+    show_error_invalid_url: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, database: &Database) -> Result<Self, Error> {
+        let webhooks = database.run(Webhook::get_all).await?;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            webhooks,
+            created_secret: None,
+            show_deleted_message: false,
+            show_error_invalid_url: false,
+        })
+    }
+
+    pub fn created_secret(mut self, secret: String) -> Self {
+        self.created_secret = Some(secret);
+        self
+    }
+
+    pub fn show_deleted_message(mut self) -> Self {
+        self.show_deleted_message = true;
+        self
+    }
+
+    pub fn show_error_invalid_url(mut self) -> Self {
+        self.show_error_invalid_url = true;
+        self
+    }
+}
+
+/// Webhooks are sent events of every department, so only administrators of
+/// every department manage them
+#[get("/webhooks")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/webhooks",
+        LayoutContext::new(language, user, &database).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    url: String,
+    grade_changed: bool,
+    enrolment_created: bool,
+    user_created: bool,
+    course_deleted: bool,
+}
+
+#[post("/webhooks", data = "<form>")]
+pub async fn post(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    let url = form.url.trim().to_owned();
+    if !matches!(
+        reqwest::Url::parse(&url).as_ref().map(reqwest::Url::scheme),
+        Ok("http" | "https")
+    ) {
+        return Ok(Template::render(
+            "routes/administrator/webhooks",
+            LayoutContext::new(language, user, &database)
+                .await?
+                .show_error_invalid_url(),
+        ));
+    }
+
+    let events = WebhookEvents {
+        grade_changed: form.grade_changed,
+        enrolment_created: form.enrolment_created,
+        user_created: form.user_created,
+        course_deleted: form.course_deleted,
+    };
+    let secret = database
+        .run(move |c| Webhook::create(c, &url, events))
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/webhooks",
+        LayoutContext::new(language, user, &database)
+            .await?
+            .created_secret(secret),
+    ))
+}
+
+#[post("/webhooks/<id>/delete")]
+pub async fn post_delete(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    database.run(move |c| Webhook::delete(c, id)).await?;
+
+    Ok(Template::render(
+        "routes/administrator/webhooks",
+        LayoutContext::new(language, user, &database)
+            .await?
+            .show_deleted_message(),
+    ))
+}
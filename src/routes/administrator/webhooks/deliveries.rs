@@ -0,0 +1,98 @@
+use rocket::{get, http::Status, post};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Administrator, User},
+    webhook::{Webhook, WebhookDelivery, WebhookDeliveryWithAttempts},
+};
+
+/// How many of the latest deliveries the log shows
+const LOGGED_DELIVERIES: i64 = 100;
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    webhook: Webhook,
+    deliveries: Vec<WebhookDeliveryWithAttempts>,
+    show_redelivered_message: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        id: u32,
+    ) -> Result<Self, Error> {
+        let (webhook, deliveries) = database
+            .run(move |c| -> Result<_, Error> {
+                Ok((
+                    Webhook::get_by_id(c, id)?,
+                    WebhookDelivery::get_latest(c, id, LOGGED_DELIVERIES)?,
+                ))
+            })
+            .await?;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            webhook,
+            deliveries,
+            show_redelivered_message: false,
+        })
+    }
+
+    pub fn show_redelivered_message(mut self) -> Self {
+        self.show_redelivered_message = true;
+        self
+    }
+}
+
+#[get("/webhooks/<id>/deliveries")]
+pub async fn get(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    Ok(Template::render(
+        "routes/administrator/webhooks/deliveries",
+        LayoutContext::new(language, user, &database, id).await?,
+    ))
+}
+
+/// Sends the payload of a logged delivery again, the receiver can tell it's
+/// the same event by its contents but gets a new delivery ID
+#[post("/webhooks/<id>/deliveries/<delivery>/redeliver")]
+pub async fn post_redeliver(
+    language: Script,
+    administrator: Administrator<'_>,
+    database: Database,
+    id: u32,
+    delivery: u32,
+) -> Result<Template, Status> {
+    if administrator.department().is_some() {
+        return Err(Status::Forbidden);
+    }
+    let user = administrator.0;
+
+    database
+        .run(move |c| WebhookDelivery::redeliver(c, id, delivery))
+        .await?;
+
+    Ok(Template::render(
+        "routes/administrator/webhooks/deliveries",
+        LayoutContext::new(language, user, &database, id)
+            .await?
+            .show_redelivered_message(),
+    ))
+}
@@ -0,0 +1,290 @@
+use rocket::{get, http::Status, put, serde::json::Json};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{courses, ApiError, ApiResult, ApiUser, Page, Pagination};
+use crate::{
+    api_token::Scope,
+    assignment::{
+        Assignment, BaseAssignment, CounterAssignment, GradeAssignment, GradeAssignmentGrade,
+        PassFailAssignment, PointAssignment, GRADE_MAJOR_MAX, GRADE_MAJOR_MIN, GRADE_MINOR_MAX,
+    },
+    course::Enrolment,
+    database::{Connection, Database},
+    error::Error,
+    exam::ExamRegistration,
+    user::{
+        RetrievalFilters, UserId, UserWithIndexAndCounterProgress, UserWithIndexAndGradeProgress,
+        UserWithIndexAndPassFailProgress, UserWithIndexAndPointProgress, UsersRetrievalOptions,
+        UsersWithIndex, UsersWithIndexAndCounterProgress, UsersWithIndexAndGradeProgress,
+        UsersWithIndexAndPassFailProgress, UsersWithIndexAndPointProgress,
+    },
+};
+
+/// A student with their progress on the assignment, which has the fields of
+/// the assignment's type
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(untagged)]
+pub enum StudentProgress {
+    Grade(UserWithIndexAndGradeProgress),
+    Point(UserWithIndexAndPointProgress),
+    PassFail(UserWithIndexAndPassFailProgress),
+    Counter(UserWithIndexAndCounterProgress),
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct AssignmentProgress {
+    pub assignment: Assignment,
+    #[schema(value_type = StudentProgressPage)]
+    pub students: Page<StudentProgress>,
+}
+
+/// Progress of every enrolled student, for the course staff
+#[utoipa::path(
+    tag = "assignments",
+    responses(
+        (status = 200, body = AssignmentProgress),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/assignments/<id>/progress?<page>&<per_page>")]
+pub async fn get_progress(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> ApiResult<AssignmentProgress> {
+    user.require(Scope::ReadGrades)?;
+    let base = database
+        .run(move |c| BaseAssignment::get_by_id(c, id))
+        .await?;
+    if base.deleted {
+        return Err(Status::NotFound.into());
+    }
+
+    let access = courses::authorize(&database, user.0, base.course).await?;
+    if !access.course.authorized_to_grade(user.0, access.role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    let pagination = Pagination::new(page, per_page);
+    let mut filters = RetrievalFilters::new();
+    filters.filter_enrolled_in = Some(base.course);
+
+    let count_filters = filters.clone();
+    let exam = Some((base.course, base.exam_period));
+    let pages = database
+        .run(move |c| {
+            UsersWithIndex::get_number_of_pages(c, count_filters, exam, pagination.per_page)
+        })
+        .await?;
+
+    let (assignment, students) = database
+        .run(move |c| -> Result<_, Error> {
+            let assignment = Assignment::get(c, base.course, &base.url)?;
+
+            let mut options = UsersRetrievalOptions::new(pagination.page, pagination.per_page);
+            options.filters = filters;
+            let students: Vec<StudentProgress> = match &assignment {
+                Assignment::Grade(assignment) => {
+                    UsersWithIndexAndGradeProgress::get(c, options, assignment.data.id)?
+                        .0
+                        .into_iter()
+                        .map(StudentProgress::Grade)
+                        .collect()
+                }
+                Assignment::Point(assignment) => {
+                    UsersWithIndexAndPointProgress::get(c, options, assignment.data.id)?
+                        .0
+                        .into_iter()
+                        .map(StudentProgress::Point)
+                        .collect()
+                }
+                Assignment::PassFail(assignment) => {
+                    UsersWithIndexAndPassFailProgress::get(c, options, assignment.data.id)?
+                        .0
+                        .into_iter()
+                        .map(StudentProgress::PassFail)
+                        .collect()
+                }
+                Assignment::Counter(assignment) => {
+                    UsersWithIndexAndCounterProgress::get(c, options, assignment.data.id)?
+                        .0
+                        .into_iter()
+                        .map(StudentProgress::Counter)
+                        .collect()
+                }
+            };
+
+            Ok((assignment, students))
+        })
+        .await?;
+
+    Ok(Json(AssignmentProgress {
+        assignment,
+        students: Page::new(students, pagination, pages),
+    }))
+}
+
+/// The new progress of a student, only the field of the assignment's type
+/// is used
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct NewProgress {
+    pub grade: Option<GradeAssignmentGrade>,
+    pub points: Option<u32>,
+    pub passed: Option<bool>,
+    pub count: Option<u32>,
+}
+
+/// Grades an enrolled student, the same as the progress page does
+#[utoipa::path(
+    tag = "assignments",
+    request_body = NewProgress,
+    responses(
+        (status = 204),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+        (status = 409, body = ApiErrorBody),
+        (status = 422, body = ApiErrorBody),
+    )
+)]
+#[put(
+    "/assignments/<id>/progress/<student>",
+    format = "json",
+    data = "<progress>"
+)]
+pub async fn put_progress(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+    student: u32,
+    progress: Json<NewProgress>,
+) -> Result<Status, ApiError> {
+    user.require(Scope::WriteGrades)?;
+    let base = database
+        .run(move |c| BaseAssignment::get_by_id(c, id))
+        .await?;
+    if base.deleted {
+        return Err(Status::NotFound.into());
+    }
+
+    let access = courses::authorize(&database, user.0, base.course).await?;
+    if !access.course.authorized_to_grade(user.0, access.role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    let (course, grader, progress) = (access.course, user.0.id, progress.into_inner());
+    database
+        .run(move |c| -> Result<(), ApiError> {
+            if course.grades_read_only(c)? {
+                return Err(ApiError::new(
+                    Status::Conflict,
+                    "Grades of a closed year can't be changed",
+                ));
+            }
+
+            match Enrolment::get(c, course.id, student) {
+                Ok(_) => {}
+                Err(Error::DatabaseEntryNotFound) => {
+                    return Err(ApiError::new(
+                        Status::UnprocessableEntity,
+                        "The student isn't enrolled in the course",
+                    ))
+                }
+                Err(e) => return Err(e.into()),
+            }
+
+            // Exam-bound assignments are only graded for the registered students
+            if let Some(exam_period) = base.exam_period {
+                if !ExamRegistration::exists(c, exam_period, course.id, student)? {
+                    return Err(ApiError::new(
+                        Status::UnprocessableEntity,
+                        "The student isn't registered for the exam",
+                    ));
+                }
+            }
+
+            let assignment = Assignment::get(c, course.id, &base.url)?;
+            grade(c, &assignment, student, progress, grader)
+        })
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+/// Checks the progress is within what the assignment allows and stores it
+fn grade(
+    connection: &mut Connection,
+    assignment: &Assignment,
+    student: UserId,
+    progress: NewProgress,
+    grader: UserId,
+) -> Result<(), ApiError> {
+    match (assignment, progress) {
+        (
+            Assignment::Grade(assignment),
+            NewProgress {
+                grade: Some(mut grade),
+                ..
+            },
+        ) => {
+            if !(GRADE_MAJOR_MIN..=GRADE_MAJOR_MAX).contains(&grade.major)
+                || grade.minor > GRADE_MINOR_MAX
+            {
+                return Err(ApiError::new(
+                    Status::UnprocessableEntity,
+                    "The grade is out of range",
+                ));
+            }
+            if grade.major == GRADE_MAJOR_MAX {
+                grade.minor = 0;
+            }
+            GradeAssignment::grade(connection, assignment.data.id, student, grade, grader)
+        }
+        (
+            Assignment::Point(assignment),
+            NewProgress {
+                points: Some(points),
+                ..
+            },
+        ) => {
+            if points > assignment.data.max_points {
+                return Err(ApiError::new(
+                    Status::UnprocessableEntity,
+                    "The points are over the assignment's maximum",
+                ));
+            }
+            PointAssignment::grade(connection, assignment.data.id, student, points, grader)
+        }
+        (
+            Assignment::PassFail(assignment),
+            NewProgress {
+                passed: Some(passed),
+                ..
+            },
+        ) => PassFailAssignment::grade(connection, assignment.data.id, student, passed, grader),
+        (
+            Assignment::Counter(assignment),
+            NewProgress {
+                count: Some(count), ..
+            },
+        ) => CounterAssignment::grade(
+            connection,
+            assignment.data.id,
+            student,
+            count.min(assignment.data.max_count),
+            grader,
+        ),
+        _ => {
+            return Err(ApiError::new(
+                Status::UnprocessableEntity,
+                "The progress doesn't match the assignment type",
+            ))
+        }
+    }
+    .map_err(ApiError::from)
+}
@@ -0,0 +1,289 @@
+use rocket::{delete, get, http::Status, post, serde::json::Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use super::{ApiError, ApiResult, ApiUser, Page, Pagination};
+use crate::{
+    academic_year::AcademicYear,
+    api_token::Scope,
+    assignment::{Assignment, Assignments, GradedAssignment, GradedAssignments},
+    course::{Course, CourseRole, CourseStaffMember, Courses, Enrolment},
+    database::Database,
+    error::Error,
+    user::{RetrievalFilters, User, UserWithIndex, UsersRetrievalOptions, UsersWithIndex},
+};
+
+/// A course along with what the user is to it
+pub struct CourseAccess {
+    pub course: Course,
+    pub role: Option<CourseRole>,
+    pub enrolled: bool,
+}
+
+/// Finds the course if the user can see it, which the staff, the enrolled
+/// students and the administrators of its department can
+pub async fn authorize(
+    database: &Database,
+    user: &User,
+    id: u32,
+) -> Result<CourseAccess, ApiError> {
+    let user_id = user.id;
+    let (course, role, enrolled) = database
+        .run(move |c| -> Result<_, Error> {
+            let course = Course::get_by_id(c, id)?;
+            let role = CourseStaffMember::get_role(c, id, user_id)?;
+            let enrolled = match Enrolment::get(c, id, user_id) {
+                Ok(_) => true,
+                Err(Error::DatabaseEntryNotFound) => false,
+                Err(e) => return Err(e),
+            };
+            Ok((course, role, enrolled))
+        })
+        .await?;
+
+    if !enrolled && !course.authorized_to_grade(user, role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    Ok(CourseAccess {
+        course,
+        role,
+        enrolled,
+    })
+}
+
+/// Courses the user administers, teaches or is enrolled in, of the given
+/// year or of the active one
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 200, body = CoursePage),
+        (status = 401, body = ApiErrorBody),
+    )
+)]
+#[get("/courses?<year>&<page>&<per_page>")]
+pub async fn get(
+    user: ApiUser<'_>,
+    database: Database,
+    year: Option<u32>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> ApiResult<Page<Course>> {
+    let user = user.0.clone();
+    let courses = database
+        .run(move |c| -> Result<_, Error> {
+            if user.is_administrator() {
+                let mut courses = Courses::get_administered(c, user.department, false)?.0;
+                if let Some(year) = AcademicYear::year_or_active(c, year)? {
+                    courses.retain(|course| course.year == year);
+                }
+                Ok(courses)
+            } else if user.is_professor() {
+                Courses::get_teaching(c, user.id, year).map(|courses| courses.0)
+            } else {
+                Courses::get_enrolled(c, user.id, year).map(|courses| courses.0)
+            }
+        })
+        .await?;
+
+    Ok(Json(Pagination::new(page, per_page).slice(courses)))
+}
+
+/// The course, for its staff, the enrolled students and the administrators
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 200, body = Course),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/courses/<id>")]
+pub async fn get_by_id(user: ApiUser<'_>, database: Database, id: u32) -> ApiResult<Course> {
+    let access = authorize(&database, user.0, id).await?;
+
+    Ok(Json(access.course))
+}
+
+/// Students enrolled in the course, for its staff
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 200, body = UserPage),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/courses/<id>/enrolments?<page>&<per_page>")]
+pub async fn get_enrolments(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> ApiResult<Page<UserWithIndex>> {
+    user.require(Scope::ReadGrades)?;
+    let access = authorize(&database, user.0, id).await?;
+    if !access.course.authorized_to_grade(user.0, access.role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    let pagination = Pagination::new(page, per_page);
+    let mut filters = RetrievalFilters::new();
+    filters.filter_enrolled_in = Some(id);
+
+    let count_filters = filters.clone();
+    let pages = database
+        .run(move |c| {
+            UsersWithIndex::get_number_of_pages(c, count_filters, None, pagination.per_page)
+        })
+        .await?;
+
+    let mut options = UsersRetrievalOptions::new(pagination.page, pagination.per_page);
+    options.filters = filters;
+    let students = database
+        .run(move |c| UsersWithIndex::get(c, options))
+        .await?
+        .0;
+
+    Ok(Json(Page::new(students, pagination, pages)))
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct NewEnrolment {
+    pub student: u32,
+}
+
+/// Enrols an active student in the course
+#[utoipa::path(
+    tag = "courses",
+    request_body = NewEnrolment,
+    responses(
+        (status = 201, body = Enrolment),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+        (status = 409, body = ApiErrorBody),
+        (status = 422, body = ApiErrorBody),
+    )
+)]
+#[post("/courses/<id>/enrolments", format = "json", data = "<enrolment>")]
+pub async fn post_enrolment(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+    enrolment: Json<NewEnrolment>,
+) -> Result<(Status, Json<Enrolment>), ApiError> {
+    user.require(Scope::ManageEnrolments)?;
+    let access = authorize(&database, user.0, id).await?;
+    if !access.course.authorized_to_edit(user.0, access.role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    let student_id = enrolment.student;
+    let student = database
+        .run(move |c| User::get_by_id(c, student_id))
+        .await?;
+    if !student.is_active_student() {
+        return Err(ApiError::new(
+            Status::UnprocessableEntity,
+            "Only active students can be enrolled",
+        ));
+    }
+
+    let enrolment = database
+        .run(move |c| -> Result<_, Error> {
+            Enrolment::create(c, id, student_id)?;
+            Enrolment::get(c, id, student_id)
+        })
+        .await?;
+
+    Ok((Status::Created, Json(enrolment)))
+}
+
+/// Un-enrols the student from the course
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 204),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[delete("/courses/<id>/enrolments/<student>")]
+pub async fn delete_enrolment(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+    student: u32,
+) -> Result<Status, ApiError> {
+    user.require(Scope::ManageEnrolments)?;
+    let access = authorize(&database, user.0, id).await?;
+    if !access.course.authorized_to_edit(user.0, access.role) {
+        return Err(Status::Forbidden.into());
+    }
+
+    database
+        .run(move |c| Enrolment::get(c, id, student)?.delete(c))
+        .await?;
+
+    Ok(Status::NoContent)
+}
+
+/// Assignments of the course that haven't been deleted, in their order
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 200, body = [Assignment]),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/courses/<id>/assignments")]
+pub async fn get_assignments(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+) -> ApiResult<Vec<Assignment>> {
+    authorize(&database, user.0, id).await?;
+
+    let mut assignments = database.run(move |c| Assignments::get(c, id)).await?.0;
+    assignments.retain(|assignment| !assignment.base().deleted);
+
+    Ok(Json(assignments))
+}
+
+/// The enrolled student's own progress, grades show up once published
+#[utoipa::path(
+    tag = "courses",
+    responses(
+        (status = 200, body = [GradedAssignment]),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/courses/<id>/progress")]
+pub async fn get_progress(
+    user: ApiUser<'_>,
+    database: Database,
+    id: u32,
+) -> ApiResult<Vec<GradedAssignment>> {
+    user.require(Scope::ReadGrades)?;
+    let access = authorize(&database, user.0, id).await?;
+    if !access.enrolled {
+        return Err(Status::Forbidden.into());
+    }
+
+    let user_id = user.0.id;
+    let assignments = database
+        .run(move |c| GradedAssignments::get(c, id, user_id))
+        .await?
+        .0;
+
+    Ok(Json(assignments))
+}
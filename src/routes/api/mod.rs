@@ -0,0 +1,228 @@
+use log::error;
+use rocket::{
+    catch,
+    http::Status,
+    outcome::Outcome,
+    request::{self, FromRequest},
+    response::{self, Responder},
+    routes,
+    serde::json::Json,
+    Request, Route,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    api_token::{ApiTokenScopes, Scope},
+    course::Course,
+    error::Error,
+    user::{User, UserWithIndex},
+};
+use assignments::StudentProgress;
+
+pub mod assignments;
+pub mod courses;
+pub mod openapi;
+pub mod users;
+
+/// Every route of the API, mounted at `/api/v1` and described by the
+/// `OpenAPI` document, which is served next to them
+pub fn routes() -> Vec<Route> {
+    routes![
+        users::get,
+        users::get_me,
+        users::get_by_id,
+        courses::get,
+        courses::get_by_id,
+        courses::get_enrolments,
+        courses::post_enrolment,
+        courses::delete_enrolment,
+        courses::get_assignments,
+        courses::get_progress,
+        assignments::get_progress,
+        assignments::put_progress,
+    ]
+}
+
+pub const DEFAULT_PER_PAGE: u32 = 50;
+pub const MAX_PER_PAGE: u32 = 200;
+
+pub type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// An error answered with a JSON body instead of an error page
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: Status,
+    pub message: &'static str,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ApiErrorBody {
+    status: u16,
+    error: &'static str,
+}
+
+impl ApiError {
+    pub fn new(status: Status, message: &'static str) -> Self {
+        Self { status, message }
+    }
+}
+
+impl From<Status> for ApiError {
+    fn from(status: Status) -> Self {
+        Self {
+            status,
+            message: status.reason().unwrap_or("Unknown Error"),
+        }
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(value: Error) -> Self {
+        match value {
+            Error::DatabaseEntryNotFound => Status::NotFound.into(),
+            Error::DatabaseDuplicateEntry => Self::new(Status::Conflict, "Already exists"),
+            Error::InvalidLanguageCode
+            | Error::InvalidAccountTypeValue
+            | Error::InvalidAssignmentTypeValue
+            | Error::InvalidCourseRoleValue
+            | Error::InvalidSelfEnrolmentModeValue
+            | Error::InvalidAcademicYearStatusValue
+            | Error::InvalidStudentStatusValue => Status::UnprocessableEntity.into(),
+            Error::NotLoggedIn => Status::Unauthorized.into(),
+            value => {
+                error!("Internal server error: {:?}", value);
+                Status::InternalServerError.into()
+            }
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let body = ApiErrorBody {
+            status: self.status.code,
+            error: self.message,
+        };
+
+        response::Response::build_from(Json(body).respond_to(request)?)
+            .status(self.status)
+            .ok()
+    }
+}
+
+/// Answers every error under `/api` with a JSON body, including the ones
+/// raised before a route runs, such as a malformed request body
+#[catch(default)]
+pub fn catcher(status: Status, _request: &Request) -> ApiError {
+    status.into()
+}
+
+/// The logged in user of an API request. Unlike the `&User` guard it fails
+/// with 401 instead of forwarding to the login page.
+pub struct ApiUser<'r>(pub &'r User, Option<ApiTokenScopes>);
+
+impl ApiUser<'_> {
+    /// Requests signed in with a token need the scope, sessions have them
+    /// all
+    pub fn require(&self, scope: Scope) -> Result<(), ApiError> {
+        match self.1 {
+            Some(scopes) if !scopes.has(scope) => Err(ApiError::new(
+                Status::Forbidden,
+                "The token doesn't have the required scope",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiUser<'r> {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match request.guard::<&User>().await {
+            Outcome::Success(user) => {
+                let scopes = *request.local_cache(|| None::<ApiTokenScopes>);
+                Outcome::Success(ApiUser(user, scopes))
+            }
+            Outcome::Forward(()) => Outcome::Failure((Status::Unauthorized, ())),
+            Outcome::Failure((status, _)) => Outcome::Failure((status, ())),
+        }
+    }
+}
+
+/// The requested page of a list, pages are counted from 0 the same as in
+/// the user lists
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl Pagination {
+    pub fn new(page: Option<u32>, per_page: Option<u32>) -> Self {
+        Self {
+            page: page.unwrap_or(0),
+            per_page: per_page.unwrap_or(DEFAULT_PER_PAGE).clamp(1, MAX_PER_PAGE),
+        }
+    }
+
+    /// Pages a list that is loaded whole
+    pub fn slice<T>(self, items: Vec<T>) -> Page<T> {
+        let pages = u32::try_from(items.len())
+            .unwrap_or(u32::MAX)
+            .div_ceil(self.per_page)
+            .max(1);
+        let items = items
+            .into_iter()
+            .skip((self.page as usize).saturating_mul(self.per_page as usize))
+            .take(self.per_page as usize)
+            .collect();
+
+        Page::new(items, self, pages)
+    }
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[aliases(
+    UserPage = Page<UserWithIndex>,
+    CoursePage = Page<Course>,
+    StudentProgressPage = Page<StudentProgress>
+)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    pub pages: u32,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, pagination: Pagination, pages: u32) -> Self {
+        Self {
+            items,
+            page: pagination.page,
+            per_page: pagination.per_page,
+            pages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pagination;
+
+    #[test]
+    fn counts_only_pages_that_have_items() {
+        let pagination = Pagination::new(None, Some(10));
+        assert_eq!(pagination.slice(vec![0; 20]).pages, 2);
+        assert_eq!(pagination.slice(vec![0; 21]).pages, 3);
+        assert_eq!(pagination.slice(Vec::<u8>::new()).pages, 1);
+    }
+
+    #[test]
+    fn pages_far_past_the_end_are_empty() {
+        let page = Pagination::new(Some(u32::MAX), Some(u32::MAX)).slice(vec![0; 5]);
+        assert!(page.items.is_empty());
+    }
+}
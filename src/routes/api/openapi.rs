@@ -0,0 +1,230 @@
+use rocket::{get, serde::json::Json};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+use utoipa::{
+    openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme},
+    Modify, OpenApi,
+};
+
+use super::{assignments, courses, users, ApiErrorBody, CoursePage, StudentProgressPage, UserPage};
+use crate::{
+    assignment::{
+        Assignment, AssignmentType, BaseAssignment, CounterAssignment, CounterAssignmentData,
+        GradeAssignment, GradeAssignmentData, GradeAssignmentGrade, GradedAssignment,
+        GradedCounterAssignment, GradedGradeAssignment, GradedPassFailAssignment,
+        GradedPointAssignment, PassFailAssignment, PassFailAssignmentData, PointAssignment,
+        PointAssignmentData,
+    },
+    course::{Course, Enrolment},
+    index::{Generation, Index, Program},
+    localization::Script,
+    student_status::StudentStatus,
+    user::{
+        AccountType, IndexGenerationProgram, User, UserWithIndex, UserWithIndexAndCounterProgress,
+        UserWithIndexAndGradeProgress, UserWithIndexAndPassFailProgress,
+        UserWithIndexAndPointProgress, SESSION_KEY_COOKIE_NAME,
+    },
+};
+
+/// The contract of the API, built from the routes and the models they
+/// answer with
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "e-Student API"),
+    servers((url = "/api/v1")),
+    paths(
+        users::get,
+        users::get_me,
+        users::get_by_id,
+        courses::get,
+        courses::get_by_id,
+        courses::get_enrolments,
+        courses::post_enrolment,
+        courses::delete_enrolment,
+        courses::get_assignments,
+        courses::get_progress,
+        assignments::get_progress,
+        assignments::put_progress,
+    ),
+    components(schemas(
+        ApiErrorBody,
+        UserPage,
+        CoursePage,
+        StudentProgressPage,
+        AccountType,
+        StudentStatus,
+        User,
+        UserWithIndex,
+        IndexGenerationProgram,
+        Index,
+        Generation,
+        Program,
+        Course,
+        Enrolment,
+        courses::NewEnrolment,
+        AssignmentType,
+        BaseAssignment,
+        Assignment,
+        GradeAssignment,
+        GradeAssignmentData,
+        PointAssignment,
+        PointAssignmentData,
+        PassFailAssignment,
+        PassFailAssignmentData,
+        CounterAssignment,
+        CounterAssignmentData,
+        GradeAssignmentGrade,
+        GradedAssignment,
+        GradedGradeAssignment,
+        GradedPointAssignment,
+        GradedPassFailAssignment,
+        GradedCounterAssignment,
+        assignments::AssignmentProgress,
+        assignments::StudentProgress,
+        assignments::NewProgress,
+        UserWithIndexAndGradeProgress,
+        UserWithIndexAndPointProgress,
+        UserWithIndexAndPassFailProgress,
+        UserWithIndexAndCounterProgress,
+    )),
+    modifiers(&Authentication),
+    security(("token" = []), ("session" = [])),
+    tags(
+        (name = "users"),
+        (name = "courses"),
+        (name = "assignments"),
+    )
+)]
+pub struct ApiDoc;
+
+/// A personal API token in the `Authorization: Bearer` header, or the
+/// session cookie of the pages
+struct Authentication;
+
+impl Modify for Authentication {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "token",
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+            components.add_security_scheme(
+                "session",
+                SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_KEY_COOKIE_NAME))),
+            );
+        }
+    }
+}
+
+#[get("/openapi.json")]
+pub fn get() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[derive(Serialize)]
+struct LayoutContext {
+    script: Script,
+    spec: utoipa::openapi::OpenApi,
+}
+
+/// The document as a page, so the API can be browsed without other tools
+#[get("/docs")]
+pub fn get_docs(language: Script) -> Template {
+    Template::render(
+        "routes/api/docs",
+        LayoutContext {
+            script: language,
+            spec: ApiDoc::openapi(),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rocket::serde::json::{serde_json, Value};
+    use utoipa::OpenApi;
+
+    use super::ApiDoc;
+
+    const METHODS: [&str; 8] = [
+        "get", "put", "post", "delete", "options", "head", "patch", "trace",
+    ];
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    /// Writes `/courses/<id>` the way OpenAPI does, as `/courses/{id}`
+    fn openapi_path(path: &str) -> String {
+        path.replace('<', "{").replace('>', "}")
+    }
+
+    #[test]
+    fn documents_every_route() {
+        let routes: BTreeSet<(String, String)> = super::super::routes()
+            .iter()
+            .map(|route| {
+                (
+                    route.method.as_str().to_lowercase(),
+                    openapi_path(route.uri.path()),
+                )
+            })
+            .collect();
+
+        let spec = spec();
+        let documented: BTreeSet<(String, String)> = spec["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .filter(|key| METHODS.contains(&key.as_str()))
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect();
+
+        assert_eq!(
+            routes, documented,
+            "the OpenAPI document doesn't match the routes of the API"
+        );
+    }
+
+    #[test]
+    fn references_only_known_schemas() {
+        fn collect_references(value: &Value, references: &mut BTreeSet<String>) {
+            match value {
+                Value::Object(object) => {
+                    if let Some(Value::String(reference)) = object.get("$ref") {
+                        references.insert(reference.clone());
+                    }
+                    object
+                        .values()
+                        .for_each(|value| collect_references(value, references));
+                }
+                Value::Array(array) => array
+                    .iter()
+                    .for_each(|value| collect_references(value, references)),
+                _ => {}
+            }
+        }
+
+        let spec = spec();
+        let mut references = BTreeSet::new();
+        collect_references(&spec, &mut references);
+
+        let schemas = spec["components"]["schemas"].as_object().unwrap();
+        for reference in references {
+            let name = reference
+                .strip_prefix("#/components/schemas/")
+                .unwrap_or(&reference);
+            assert!(
+                schemas.contains_key(name),
+                "{reference} isn't in the OpenAPI document"
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use rocket::{get, http::Status, serde::json::Json};
+
+use super::{ApiResult, ApiUser, Page, Pagination};
+use crate::{
+    api_token::Scope,
+    database::Database,
+    user::{
+        AccountType, Administrator, RetrievalFilters, UserWithIndex, UsersRetrievalOptions,
+        UsersWithIndex,
+    },
+};
+
+/// Users the administrator manages
+#[utoipa::path(
+    tag = "users",
+    responses(
+        (status = 200, body = UserPage),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+    )
+)]
+#[get("/users?<page>&<per_page>&<email>&<account_type>")]
+pub async fn get(
+    user: ApiUser<'_>,
+    database: Database,
+    page: Option<u32>,
+    per_page: Option<u32>,
+    email: Option<String>,
+    account_type: Option<AccountType>,
+) -> ApiResult<Page<UserWithIndex>> {
+    if !user.0.is_administrator() {
+        return Err(Status::Forbidden.into());
+    }
+    user.require(Scope::ManageUsers)?;
+
+    let pagination = Pagination::new(page, per_page);
+    let mut filters = RetrievalFilters::new();
+    filters.filter_email = email.filter(|s| !s.is_empty());
+    filters.filter_account_type = account_type;
+    filters.filter_department = Administrator(user.0).department();
+
+    let count_filters = filters.clone();
+    let pages = database
+        .run(move |c| {
+            UsersWithIndex::get_number_of_pages(c, count_filters, None, pagination.per_page)
+        })
+        .await?;
+
+    let mut options = UsersRetrievalOptions::new(pagination.page, pagination.per_page);
+    options.filters = filters;
+    let users = database
+        .run(move |c| UsersWithIndex::get(c, options))
+        .await?
+        .0;
+
+    Ok(Json(Page::new(users, pagination, pages)))
+}
+
+/// The signed in user
+#[utoipa::path(
+    tag = "users",
+    responses(
+        (status = 200, body = UserWithIndex),
+        (status = 401, body = ApiErrorBody),
+    )
+)]
+#[get("/users/me")]
+pub async fn get_me(user: ApiUser<'_>, database: Database) -> ApiResult<UserWithIndex> {
+    let user_id = user.0.id;
+    let user = database
+        .run(move |c| UserWithIndex::get(c, user_id))
+        .await?;
+
+    Ok(Json(user))
+}
+
+/// Everyone can see themselves, administrators also the users they manage
+#[utoipa::path(
+    tag = "users",
+    responses(
+        (status = 200, body = UserWithIndex),
+        (status = 401, body = ApiErrorBody),
+        (status = 403, body = ApiErrorBody),
+        (status = 404, body = ApiErrorBody),
+    )
+)]
+#[get("/users/<id>")]
+pub async fn get_by_id(user: ApiUser<'_>, database: Database, id: u32) -> ApiResult<UserWithIndex> {
+    if user.0.id != id {
+        if !user.0.is_administrator() {
+            return Err(Status::Forbidden.into());
+        }
+        user.require(Scope::ManageUsers)?;
+    }
+
+    let found = database.run(move |c| UserWithIndex::get(c, id)).await?;
+    if user.0.id != id && !Administrator(user.0).manages_user(&found) {
+        return Err(Status::Forbidden.into());
+    }
+
+    Ok(Json(found))
+}
@@ -0,0 +1,132 @@
+use rocket::{
+    get,
+    http::{uri::Host, ContentType, Status},
+    post,
+};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    assignment::Deadlines,
+    base_layout_context::BaseLayoutContext,
+    calendar::{self, CalendarToken},
+    course::Courses,
+    database::Database,
+    error::Error,
+    exam::{ExamPeriods, ExamRegistration},
+    localization::Script,
+    user::User,
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    host: Option<String>,
+    file_name: Option<String>,
+    show_success_message: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        host: Option<&Host<'_>>,
+        token: Option<CalendarToken>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            host: host.map(ToString::to_string),
+            file_name: token.as_ref().map(CalendarToken::file_name),
+            show_success_message: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+}
+
+#[get("/calendar")]
+pub async fn get(
+    language: Script,
+    user: &User,
+    database: Database,
+    host: Option<&Host<'_>>,
+) -> Result<Template, Status> {
+    let user_id = user.id;
+
+    let token = database
+        .run(move |c| CalendarToken::get(c, user_id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/calendar",
+        LayoutContext::new(language, user, host, token).await?,
+    ))
+}
+
+#[post("/calendar")]
+pub async fn post(
+    language: Script,
+    user: &User,
+    database: Database,
+    host: Option<&Host<'_>>,
+) -> Result<Template, Status> {
+    let user_id = user.id;
+    let script = language.clone();
+
+    let token = database
+        .run(move |c| CalendarToken::regenerate(c, user_id, &script))
+        .await?;
+
+    Ok(Template::render(
+        "routes/calendar",
+        LayoutContext::new(language, user, host, Some(token))
+            .await?
+            .success(),
+    ))
+}
+
+#[get("/calendar/<file_name>")]
+pub async fn get_feed(
+    database: Database,
+    file_name: String,
+) -> Result<(ContentType, String), Status> {
+    let token = file_name
+        .strip_suffix(".ics")
+        .and_then(|token| hex::decode(token).ok())
+        .ok_or(Status::NotFound)?;
+
+    let token = match database
+        .run(move |c| CalendarToken::get_by_token(c, &token))
+        .await
+    {
+        Ok(token) => token,
+        Err(Error::DatabaseEntryNotFound) => return Err(Status::NotFound),
+        Err(e) => return Err(e.into()),
+    };
+
+    let user = token.user;
+    let (deadlines, exam_periods, registrations) = database
+        .run(move |c| -> Result<_, Error> {
+            let mut courses = Courses::get_enrolled(c, user, None)?.0;
+            courses.append(&mut Courses::get_teaching(c, user, None)?.0);
+
+            let course_ids: Vec<_> = courses.iter().map(|course| course.id).collect();
+            let exam_periods = ExamPeriods::get(c)?.0;
+            let exam_period_ids: Vec<_> = exam_periods.iter().map(|period| period.id).collect();
+            Ok((
+                Deadlines::get_dated(c, &course_ids)?.0,
+                exam_periods,
+                ExamRegistration::get_for_student(c, user, &exam_period_ids)?,
+            ))
+        })
+        .await?;
+
+    Ok((
+        ContentType::Calendar,
+        calendar::feed(&deadlines, &exam_periods, &registrations, &token.script()),
+    ))
+}
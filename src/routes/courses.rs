@@ -1,8 +1,8 @@
 use serde::Serialize;
 
 use crate::{
-    base_layout_context::BaseLayoutContext, course::Course, error::Error, localization::Script,
-    user::User,
+    academic_year::AcademicYear, assignment::Deadline, base_layout_context::BaseLayoutContext,
+    course::Course, error::Error, localization::Script, user::User,
 };
 
 #[derive(Serialize, Debug)]
@@ -10,6 +10,10 @@ pub struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     courses: Vec<Course>,
+    deadlines: Vec<Deadline>,
+    academic_years: Vec<AcademicYear>,
+    selected_year: Option<u32>,
+    show_deleted: bool,
 }
 
 impl LayoutContext {
@@ -17,6 +21,32 @@ impl LayoutContext {
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             courses,
+            deadlines: Vec::new(),
+            academic_years: Vec::new(),
+            selected_year: None,
+            show_deleted: false,
         })
     }
+
+    pub fn deadlines(mut self, deadlines: Vec<Deadline>) -> Self {
+        self.deadlines = deadlines;
+        self
+    }
+
+    /// Years the courses can be listed for, with the one they are listed
+    /// for
+    pub fn academic_years(
+        mut self,
+        academic_years: Vec<AcademicYear>,
+        selected_year: Option<u32>,
+    ) -> Self {
+        self.academic_years = academic_years;
+        self.selected_year = selected_year;
+        self
+    }
+
+    pub fn deleted(mut self) -> Self {
+        self.show_deleted = true;
+        self
+    }
 }
@@ -1,10 +1,14 @@
+pub mod calendar;
 pub mod locale;
 pub mod login;
 pub mod logout;
 pub mod root;
+pub mod tokens;
 
 pub mod administrator;
+pub mod api;
 pub mod professor;
 pub mod student;
 
 mod courses;
+mod transcript;
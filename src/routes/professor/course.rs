@@ -1,7 +1,10 @@
 pub mod assignment;
 pub mod assignments;
 pub mod delete;
+pub mod edit;
 pub mod enrol;
+pub mod rollover;
+pub mod staff;
 
 use rocket::{get, http::Status};
 use rocket_dyn_templates::Template;
@@ -10,7 +13,7 @@ use serde::Serialize;
 use crate::{
     assignment::{Assignment, Assignments},
     base_layout_context::BaseLayoutContext,
-    course::Course,
+    course::{Course, CourseRole, CourseStaffMember},
     database::Database,
     error::Error,
     localization::Script,
@@ -23,6 +26,7 @@ struct CourseWithAssignments {
     #[serde(flatten)]
     course: Course,
     assignments: Vec<Assignment>,
+    deleted_assignments: Vec<Assignment>,
 }
 
 #[derive(Serialize, Debug)]
@@ -30,6 +34,8 @@ struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     course: CourseWithAssignments,
+    can_edit: bool,
+    can_manage: bool,
 }
 
 impl LayoutContext {
@@ -37,9 +43,12 @@ impl LayoutContext {
         language: Script,
         user: &User,
         course: CourseWithAssignments,
+        role: Option<CourseRole>,
     ) -> Result<Self, Error> {
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
+            can_edit: course.course.authorized_to_edit(user, role),
+            can_manage: course.course.authorized_to_manage(user, role),
             course,
         })
     }
@@ -58,17 +67,29 @@ pub async fn get(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    let assignments = database
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_grade(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let (deleted_assignments, assignments) = database
         .run(move |c| Assignments::get(c, course.id))
         .await?
-        .0;
+        .0
+        .into_iter()
+        .partition(|a| a.base().deleted);
 
     let course = CourseWithAssignments {
         course,
         assignments,
+        deleted_assignments,
     };
 
-    let context = LayoutContext::new(language, user, course).await?;
+    let context = LayoutContext::new(language, user, course, role).await?;
 
     Ok(Template::render("routes/professor/course", context))
 }
@@ -1 +1,6 @@
+pub mod delete;
+pub mod edit;
+pub mod history;
 pub mod progress;
+pub mod publish;
+pub mod restore;
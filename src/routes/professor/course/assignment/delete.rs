@@ -0,0 +1,117 @@
+use rocket::{get, http::Status, post};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    assignment::Assignment,
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Professor, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    deleting_assignment: Assignment,
+    show_success: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        course: Course,
+        deleting_assignment: Assignment,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            deleting_assignment,
+            show_success: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+}
+
+#[get("/course/<course>/assignment/<assignment>/delete")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let deleting_assignment = database
+        .run(move |c| Assignment::get(c, course_id, &assignment))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/delete",
+        LayoutContext::new(language, user, course, deleting_assignment).await?,
+    ))
+}
+
+#[post("/course/<course>/assignment/<assignment>/delete")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let deleting_assignment = database
+        .run(move |c| {
+            let assignment = Assignment::get(c, course_id, &assignment)?;
+            assignment.base().update_deleted(c, true)?;
+            Ok::<_, Error>(assignment)
+        })
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/delete",
+        LayoutContext::new(language, user, course, deleting_assignment)
+            .await?
+            .success(),
+    ))
+}
@@ -0,0 +1,227 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    assignment::Assignment,
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    error::Error,
+    exam::{ExamPeriod, ExamPeriods},
+    localization::Script,
+    user::{Professor, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    assignment: Assignment,
+    exam_periods: Vec<ExamPeriod>,
+    show_success_message: bool,
+    show_error_all_fields_required: bool,
+    show_error_duplicate_name: bool,
+    show_error_invalid_dates: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        course: Course,
+        assignment: Assignment,
+    ) -> Result<Self, Error> {
+        let exam_periods = database.run(ExamPeriods::get).await?.0;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            assignment,
+            exam_periods,
+            show_success_message: false,
+            show_error_all_fields_required: false,
+            show_error_duplicate_name: false,
+            show_error_invalid_dates: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success_message = true;
+        self
+    }
+
+    pub fn error_all_fields_required(mut self) -> Self {
+        self.show_error_all_fields_required = true;
+        self
+    }
+
+    pub fn error_duplicate_name(mut self) -> Self {
+        self.show_error_duplicate_name = true;
+        self
+    }
+
+    pub fn error_invalid_dates(mut self) -> Self {
+        self.show_error_invalid_dates = true;
+        self
+    }
+}
+
+#[get("/course/<course>/assignment/<assignment>/edit")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let assignment = database
+        .run(move |c| Assignment::get(c, course_id, &assignment))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/edit",
+        LayoutContext::new(language, user, &database, course, assignment).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    name: String,
+    max_points: Option<u32>,
+    max_count: Option<u32>,
+    description: String,
+    opens_at: String,
+    due_at: String,
+    exam_period: Option<u32>,
+}
+
+#[post("/course/<course>/assignment/<assignment>/edit", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let editing = database
+        .run(move |c| Assignment::get(c, course_id, &assignment))
+        .await?;
+
+    let missing_maximum = match &editing {
+        Assignment::Point(_) => form.max_points.unwrap_or_default() == 0,
+        Assignment::Counter(_) => form.max_count.unwrap_or_default() == 0,
+        Assignment::Grade(_) | Assignment::PassFail(_) => false,
+    };
+
+    if form.name.is_empty() || missing_maximum {
+        return Ok(Template::render(
+            "routes/professor/course/assignment/edit",
+            LayoutContext::new(language, user, &database, course, editing)
+                .await?
+                .error_all_fields_required(),
+        ));
+    }
+
+    // Empty date fields clear the date, anything else has to parse
+    let opens_at = crate::util::parse_datetime_local(&form.opens_at);
+    let due_at = crate::util::parse_datetime_local(&form.due_at);
+    let invalid_dates = (!form.opens_at.is_empty() && opens_at.is_none())
+        || (!form.due_at.is_empty() && due_at.is_none())
+        || matches!((opens_at, due_at), (Some(opens_at), Some(due_at)) if due_at < opens_at);
+
+    if invalid_dates {
+        return Ok(Template::render(
+            "routes/professor/course/assignment/edit",
+            LayoutContext::new(language, user, &database, course, editing)
+                .await?
+                .error_invalid_dates(),
+        ));
+    }
+
+    let url = crate::util::string_to_url(&form.name);
+    let previous_url = editing.base().url.clone();
+    let form = form.into_inner();
+
+    let result = database
+        .run(move |c| {
+            c.transaction(|c| {
+                editing.base().update_name(c, &form.name, &url)?;
+                let description = form.description.trim();
+                editing.base().update_schedule(
+                    c,
+                    (!description.is_empty()).then_some(description),
+                    opens_at,
+                    due_at,
+                )?;
+                editing.base().update_exam_period(c, form.exam_period)?;
+                match &editing {
+                    Assignment::Point(point) => point
+                        .data
+                        .update_max_points(c, form.max_points.unwrap_or_default()),
+                    Assignment::Counter(counter) => counter
+                        .data
+                        .update_max_count(c, form.max_count.unwrap_or_default()),
+                    Assignment::Grade(_) | Assignment::PassFail(_) => Ok(()),
+                }?;
+                Assignment::get(c, course_id, &url)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(edited) => Ok(Template::render(
+            "routes/professor/course/assignment/edit",
+            LayoutContext::new(language, user, &database, course, edited)
+                .await?
+                .success(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => {
+            let unchanged = database
+                .run(move |c| Assignment::get(c, course_id, &previous_url))
+                .await?;
+            Ok(Template::render(
+                "routes/professor/course/assignment/edit",
+                LayoutContext::new(language, user, &database, course, unchanged)
+                    .await?
+                    .error_duplicate_name(),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,82 @@
+use rocket::{get, http::Status};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    assignment::{Assignment, GradeHistory},
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{Professor, User, UserId},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    assignment: Assignment,
+    student: User,
+    history: GradeHistory,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        course: Course,
+        assignment: Assignment,
+        student: User,
+        history: GradeHistory,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            assignment,
+            student,
+            history,
+        })
+    }
+}
+
+#[get("/course/<course>/assignment/<assignment>/history/<student>")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+    student: UserId,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_grade(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let (assignment, student, history) = database
+        .run(move |c| {
+            let assignment = Assignment::get(c, course_id, &assignment)?;
+            let student = User::get_by_id(c, student)?;
+            let history = GradeHistory::get(c, &assignment, student.id)?;
+            Ok::<_, Error>((assignment, student, history))
+        })
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/history",
+        LayoutContext::new(language, user, course, assignment, student, history).await?,
+    ))
+}
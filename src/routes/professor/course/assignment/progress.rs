@@ -3,14 +3,21 @@ use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
-    assignment::{Assignment, GradeAssignment, PointAssignment, GRADE_MAJOR_MAX},
+    assignment::{
+        Assignment, CounterAssignment, GradeAssignment, PassFailAssignment, PointAssignment,
+        GRADE_MAJOR_MAX,
+    },
     base_layout_context::BaseLayoutContext,
-    components::users::{self, ControlTypeOptions, GradeProgressOptions, PointProgressOptions},
-    course::Course,
-    database::Database,
+    components::users::{
+        self, ControlTypeOptions, CounterProgressOptions, GradeProgressOptions,
+        PassFailProgressOptions, PointProgressOptions,
+    },
+    course::{Course, CourseStaffMember},
+    database::{Connection, Database},
     error::Error,
+    exam::ExamRegistration,
     localization::Script,
-    user::{Professor, User},
+    user::{Professor, User, UserId},
 };
 
 #[derive(Serialize, Debug)]
@@ -20,6 +27,7 @@ struct LayoutContext {
     users: users::LayoutContext,
     course: Course,
     assignment: Assignment,
+    read_only: bool,
 }
 
 impl LayoutContext {
@@ -35,8 +43,115 @@ impl LayoutContext {
             users,
             course,
             assignment,
+            read_only: false,
         })
     }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+}
+
+fn control_type_options(assignment: &Assignment) -> ControlTypeOptions {
+    match assignment {
+        Assignment::Point(assignment) => ControlTypeOptions::PointProgress(PointProgressOptions {
+            assignment: assignment.data.id,
+        }),
+        Assignment::Grade(assignment) => ControlTypeOptions::GradeProgress(GradeProgressOptions {
+            assignment: assignment.data.id,
+        }),
+        Assignment::PassFail(assignment) => {
+            ControlTypeOptions::PassFailProgress(PassFailProgressOptions {
+                assignment: assignment.data.id,
+            })
+        }
+        Assignment::Counter(assignment) => {
+            ControlTypeOptions::CounterProgress(CounterProgressOptions {
+                assignment: assignment.data.id,
+            })
+        }
+    }
+}
+
+/// Stores the changed grades. Students who aren't registered for the exam
+/// of an exam-bound assignment aren't listed, their changes are ignored.
+fn grade(
+    connection: &mut Connection,
+    assignment: &Assignment,
+    users_form: &users::FormData,
+    grader: UserId,
+) -> Result<(), Error> {
+    let base = assignment.base();
+    let registered = |connection: &mut Connection, student: UserId| match base.exam_period {
+        Some(exam_period) => {
+            ExamRegistration::exists(connection, exam_period, base.course, student)
+        }
+        None => Ok(true),
+    };
+
+    match assignment {
+        Assignment::Point(assignment) => {
+            for point_progress in users_form.point_progresses() {
+                let user = point_progress.user();
+                if !point_progress.value_changed() || !registered(connection, user)? {
+                    continue;
+                }
+
+                let points = point_progress.new_value().unwrap_or_default();
+                PointAssignment::grade(connection, assignment.data.id, user, points, grader)?;
+            }
+        }
+        Assignment::Grade(assignment) => {
+            for grade_progress in users_form.grade_progresses() {
+                let user = grade_progress.user();
+                if !grade_progress.value_changed() || !registered(connection, user)? {
+                    continue;
+                }
+
+                let mut grade = grade_progress.new_value().clone().unwrap_or_default();
+                if grade.major == GRADE_MAJOR_MAX {
+                    grade.minor = 0;
+                }
+
+                GradeAssignment::grade(connection, assignment.data.id, user, grade, grader)?;
+            }
+        }
+        Assignment::PassFail(assignment) => {
+            for pass_fail_progress in users_form.pass_fail_progresses() {
+                let user = pass_fail_progress.user();
+                if !pass_fail_progress.value_changed() || !registered(connection, user)? {
+                    continue;
+                }
+
+                // Going back to "not graded" isn't supported, a cleared
+                // value leaves the stored one as it was
+                let Some(passed) = *pass_fail_progress.new_value() else {
+                    continue;
+                };
+
+                PassFailAssignment::grade(connection, assignment.data.id, user, passed, grader)?;
+            }
+        }
+        Assignment::Counter(assignment) => {
+            for counter_progress in users_form.counter_progresses() {
+                let user = counter_progress.user();
+                if !counter_progress.value_changed() || !registered(connection, user)? {
+                    continue;
+                }
+
+                // A cleared count is left as it was, same as with pass/fail
+                let Some(count) = *counter_progress.new_value() else {
+                    continue;
+                };
+
+                let count = count.min(assignment.data.max_count);
+                CounterAssignment::grade(connection, assignment.data.id, user, count, grader)?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
 #[get("/course/<course>/assignment/<assignment>/progress")]
@@ -53,21 +168,29 @@ pub async fn get(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_grade(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
     let assignment = database
         .run(move |c| Assignment::get(c, course.id, &assignment))
         .await?;
 
-    let options = match &assignment {
-        Assignment::Point(assignment) => ControlTypeOptions::PointProgress(PointProgressOptions {
-            assignment: assignment.data.id,
-        }),
-        Assignment::Grade(assignment) => ControlTypeOptions::GradeProgress(GradeProgressOptions {
-            assignment: assignment.data.id,
-        }),
-    };
+    let read_only_course = course.clone();
+    let read_only = database
+        .run(move |c| read_only_course.grades_read_only(c))
+        .await?;
 
+    let options = control_type_options(&assignment);
     let users_context = users::LayoutContext::new(database, None, options).await?;
-    let context = LayoutContext::new(language, user, users_context, course, assignment).await?;
+    let context = LayoutContext::new(language, user, users_context, course, assignment)
+        .await?
+        .read_only(read_only);
 
     Ok(Template::render(
         "routes/professor/course/assignment/progress",
@@ -95,59 +218,41 @@ pub async fn post(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    let assignment = database
-        .run(move |c| Assignment::get(c, course.id, &assignment))
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
         .await?;
 
-    let options = match &assignment {
-        Assignment::Point(assignment) => {
-            for point_progress in form.users_form.point_progresses() {
-                if !point_progress.value_changed() {
-                    continue;
-                }
-
-                let user = point_progress.user();
-                let points = point_progress.new_value().unwrap_or_default();
-                let assignment_id = assignment.data.id;
-
-                database
-                    .run(move |c| PointAssignment::grade(c, assignment_id, user, points))
-                    .await?;
-            }
-
-            ControlTypeOptions::PointProgress(PointProgressOptions {
-                assignment: assignment.data.id,
-            })
-        }
-        Assignment::Grade(assignment) => {
-            for grade_progress in form.users_form.grade_progresses() {
-                if !grade_progress.value_changed() {
-                    continue;
-                }
-
-                let user = grade_progress.user();
-                let mut grade = grade_progress.new_value().clone().unwrap_or_default();
-
-                if grade.major == GRADE_MAJOR_MAX {
-                    grade.minor = 0;
-                }
+    if !course.authorized_to_grade(user, role) {
+        return Err(Status::Unauthorized);
+    }
 
-                let assignment_id = assignment.data.id;
+    let assignment = database
+        .run(move |c| Assignment::get(c, course.id, &assignment))
+        .await?;
 
-                database
-                    .run(move |c| GradeAssignment::grade(c, assignment_id, user, grade))
-                    .await?;
-            }
+    let read_only_course = course.clone();
+    let read_only = database
+        .run(move |c| read_only_course.grades_read_only(c))
+        .await?;
 
-            ControlTypeOptions::GradeProgress(GradeProgressOptions {
-                assignment: assignment.data.id,
-            })
-        }
+    // Filtering and paging still work when the year is closed, only the
+    // changed grades are ignored
+    let users_form = form.into_inner().users_form;
+    let assignment = if read_only {
+        assignment
+    } else {
+        let (changes, grader) = (users_form.clone(), user.id);
+        database
+            .run(move |c| grade(c, &assignment, &changes, grader).map(|()| assignment))
+            .await?
     };
 
-    let users_context =
-        users::LayoutContext::new(database, Some(form.into_inner().users_form), options).await?;
-    let context = LayoutContext::new(language, user, users_context, course, assignment).await?;
+    let options = control_type_options(&assignment);
+    let users_context = users::LayoutContext::new(database, Some(users_form), options).await?;
+    let context = LayoutContext::new(language, user, users_context, course, assignment)
+        .await?
+        .read_only(read_only);
 
     Ok(Template::render(
         "routes/professor/course/assignment/progress",
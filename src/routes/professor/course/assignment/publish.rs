@@ -0,0 +1,191 @@
+use diesel::Connection;
+use lettre::Address;
+use log::warn;
+use rocket::{form::Form, get, http::Status, post, tokio, FromForm, State};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    assignment::Assignment,
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    error::Error,
+    localization::Script,
+    mail::Mail,
+    user::{Professor, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    assignment: Assignment,
+    show_success: bool,
+    read_only: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        course: Course,
+        assignment: Assignment,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            assignment,
+            show_success: false,
+            read_only: false,
+        })
+    }
+
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+}
+
+#[get("/course/<course>/assignment/<assignment>/publish")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let read_only_course = course.clone();
+    let (assignment, read_only) = database
+        .run(move |c| {
+            Ok::<_, Error>((
+                Assignment::get(c, read_only_course.id, &assignment)?,
+                read_only_course.grades_read_only(c)?,
+            ))
+        })
+        .await?;
+
+    let context = LayoutContext::new(language, user, course, assignment).await?;
+    let context = if read_only {
+        context.read_only()
+    } else {
+        context
+    };
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/publish",
+        context,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    published: bool,
+    notify: bool,
+}
+
+#[post("/course/<course>/assignment/<assignment>/publish", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    mail: &State<Mail>,
+    course: String,
+    assignment: String,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let read_only_course = course.clone();
+    if database
+        .run(move |c| read_only_course.grades_read_only(c))
+        .await?
+    {
+        return Err(Status::Forbidden);
+    }
+
+    let course_id = course.id;
+    let published = form.published;
+    let assignment = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let assignment = Assignment::get(c, course_id, &assignment)?;
+                assignment.base().update_published(c, published)?;
+                // Webhooks weren't sent the grades given while unpublished
+                if published && !assignment.base().published {
+                    assignment.trigger_grades_changed(c)?;
+                }
+                Assignment::get(c, course_id, &assignment.base().url)
+            })
+        })
+        .await?;
+
+    if form.published && form.notify {
+        let notified_course = course.clone();
+        let emails = database
+            .run(move |c| notified_course.enrolled_emails(c))
+            .await?;
+
+        // Sent in the background, a large course shouldn't hold up the
+        // response
+        let mail = mail.inner().clone();
+        let (course_name, assignment_name) = (course.name.clone(), assignment.base().name.clone());
+        tokio::spawn(async move {
+            // A single bad address or failed delivery shouldn't stop the others
+            for email in emails {
+                let Ok(address) = email.parse::<Address>() else {
+                    warn!("Not notifying {email} about published grades, invalid address");
+                    continue;
+                };
+
+                if let Err(e) = mail
+                    .send_grades_published(address, &course_name, &assignment_name)
+                    .await
+                {
+                    warn!("Failed to notify {email} about published grades: {e:?}");
+                }
+            }
+        });
+    }
+
+    Ok(Template::render(
+        "routes/professor/course/assignment/publish",
+        LayoutContext::new(language, user, course, assignment)
+            .await?
+            .success(),
+    ))
+}
@@ -0,0 +1,40 @@
+use rocket::{http::Status, post, response::Redirect};
+
+use crate::{
+    assignment::BaseAssignment,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    user::Professor,
+};
+
+#[post("/course/<course>/assignment/<assignment>/restore")]
+pub async fn post(
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    assignment: String,
+) -> Result<Redirect, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    database
+        .run(move |c| {
+            BaseAssignment::get_by_url(c, course_id, &assignment)?.update_deleted(c, false)
+        })
+        .await?;
+
+    Ok(Redirect::to(format!("/course/{}", course.url)))
+}
@@ -3,9 +3,11 @@ use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
-    assignment::{AssignmentType, GradeAssignment, PointAssignment},
+    assignment::{
+        AssignmentType, CounterAssignment, GradeAssignment, PassFailAssignment, PointAssignment,
+    },
     base_layout_context::BaseLayoutContext,
-    course::Course,
+    course::{Course, CourseStaffMember},
     database::Database,
     error::Error,
     localization::Script,
@@ -56,7 +58,12 @@ pub async fn get(
 
     let user = professor.0;
 
-    if !course.authorized_to_edit(user) {
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
         return Err(Status::Unauthorized);
     }
 
@@ -74,6 +81,14 @@ pub async fn get(
             "routes/professor/course/assignments/create/point",
             LayoutContext::new(language, user, course).await?,
         )),
+        Some(AssignmentType::PassFail) => Ok(Template::render(
+            "routes/professor/course/assignments/create/pass_fail",
+            LayoutContext::new(language, user, course).await?,
+        )),
+        Some(AssignmentType::Counter) => Ok(Template::render(
+            "routes/professor/course/assignments/create/counter",
+            LayoutContext::new(language, user, course).await?,
+        )),
     }
 }
 
@@ -102,7 +117,12 @@ pub async fn post_grade(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    if !course.authorized_to_edit(user) {
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
         return Err(Status::Unauthorized);
     }
 
@@ -150,7 +170,12 @@ pub async fn post_point(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    if !course.authorized_to_edit(user) {
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
         return Err(Status::Unauthorized);
     }
 
@@ -174,3 +199,108 @@ pub async fn post_point(
         LayoutContext::new(language, user, course).await?.success(),
     ))
 }
+
+#[derive(FromForm, Debug)]
+pub struct FormDataPassFail {
+    name: String,
+}
+
+#[post(
+    "/course/<course>/assignments/create?assignment_type=PassFail",
+    data = "<form>",
+    rank = 2
+)]
+pub async fn post_pass_fail(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormDataPassFail>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    if form.name.is_empty() {
+        return Ok(Template::render(
+            "routes/professor/course/assignments/create/pass_fail",
+            LayoutContext::new(language, user, course)
+                .await?
+                .error_all_fields_required(),
+        ));
+    }
+
+    let url = crate::util::string_to_url(&form.name);
+
+    database
+        .run(move |c| PassFailAssignment::create(c, course.id, &form.name, &url))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignments/create/pass_fail",
+        LayoutContext::new(language, user, course).await?.success(),
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormDataCounter {
+    name: String,
+    max_count: u32,
+}
+
+#[post(
+    "/course/<course>/assignments/create?assignment_type=Counter",
+    data = "<form>",
+    rank = 3
+)]
+pub async fn post_counter(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormDataCounter>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    if form.name.is_empty() || form.max_count == 0 {
+        return Ok(Template::render(
+            "routes/professor/course/assignments/create/counter",
+            LayoutContext::new(language, user, course)
+                .await?
+                .error_all_fields_required(),
+        ));
+    }
+
+    let url = crate::util::string_to_url(&form.name);
+
+    database
+        .run(move |c| CounterAssignment::create(c, course.id, &form.name, &url, form.max_count))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/assignments/create/counter",
+        LayoutContext::new(language, user, course).await?.success(),
+    ))
+}
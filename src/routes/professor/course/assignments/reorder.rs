@@ -0,0 +1,71 @@
+use diesel::Connection;
+use rocket::{form::Form, http::Status, post, response::Redirect, FromForm};
+
+use crate::{
+    assignment::BaseAssignment,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    error::Error,
+    user::Professor,
+};
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    // Assignment ids, in the order they should be listed
+    positions: Vec<u32>,
+}
+
+#[post("/course/<course>/assignments/reorder", data = "<form>")]
+pub async fn post(
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<Redirect, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let course_id = course.id;
+    let positions = form.into_inner().positions;
+
+    let reordered = database
+        .run(move |c| {
+            c.transaction(|c| {
+                let mut remaining = BaseAssignment::get_by_course(c, course_id)?;
+                let mut assignments = Vec::with_capacity(remaining.len());
+                for id in positions {
+                    let Some(index) = remaining.iter().position(|a| a.id == id) else {
+                        return Ok(false);
+                    };
+                    assignments.push(remaining.remove(index));
+                }
+                // Assignments left out of the form keep their order, after
+                // the listed ones, so no two share a position
+                assignments.append(&mut remaining);
+
+                for (position, assignment) in (0..).zip(assignments) {
+                    assignment.update_position(c, position)?;
+                }
+                Ok::<_, Error>(true)
+            })
+        })
+        .await?;
+
+    if !reordered {
+        return Err(Status::BadRequest);
+    }
+
+    Ok(Redirect::to(format!("/course/{}", course.url)))
+}
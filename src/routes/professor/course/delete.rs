@@ -4,7 +4,7 @@ use serde::Serialize;
 
 use crate::{
     base_layout_context::BaseLayoutContext,
-    course::Course,
+    course::{Course, CourseStaffMember},
     database::Database,
     error::Error,
     localization::Script,
@@ -50,7 +50,12 @@ pub async fn get(
     let user = professor.0;
     let context = LayoutContext::new(language, user, deleting_course.clone()).await?;
 
-    match deleting_course.authorized_to_edit(&user) {
+    let (course_id, user_id) = (deleting_course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    match deleting_course.authorized_to_manage(user, role) {
         true => Ok(Template::render("routes/professor/course/delete", context)),
         false => Err(Status::Unauthorized),
     }
@@ -68,7 +73,12 @@ pub async fn post(
     let user = professor.0;
     let context = LayoutContext::new(language, user, deleting_course.clone()).await?;
 
-    match deleting_course.authorized_to_edit(&user) {
+    let (course_id, user_id) = (deleting_course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    match deleting_course.authorized_to_manage(user, role) {
         true => {
             database.run(move |c| deleting_course.delete(c)).await?;
 
@@ -0,0 +1,278 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    academic_year::{AcademicYear, AcademicYears},
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseStaffMember},
+    database::Database,
+    department::{Department, Departments},
+    error::Error,
+    localization::Script,
+    user::{Professor, User, Users},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    professors: Vec<User>,
+    departments: Vec<Department>,
+    academic_years: Vec<u32>,
+    show_success: bool,
+    error_name_is_required: bool,
+    error_duplicate: bool,
+    error_invalid_professor: bool,
+    error_year_not_open: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        course: Course,
+        professors: Vec<User>,
+    ) -> Result<Self, Error> {
+        // A course of a closed year stays in it, the others can move into
+        // any open year
+        let course_year = course.year;
+        let academic_years = database
+            .run(move |c| -> Result<_, Error> {
+                if AcademicYear::is_closed(c, course_year)? {
+                    return Ok(vec![course_year]);
+                }
+
+                let mut years: Vec<u32> = AcademicYears::get_open(c)?
+                    .0
+                    .into_iter()
+                    .map(|academic_year| academic_year.year)
+                    .collect();
+                if !years.contains(&course_year) {
+                    years.push(course_year);
+                    years.sort_unstable_by(|a, b| b.cmp(a));
+                }
+                Ok(years)
+            })
+            .await?;
+        // Only administrators move courses between departments
+        let departments = if user.administers(course.department) {
+            let department = user.department;
+            database
+                .run(move |c| Departments::get_administered(c, department))
+                .await?
+                .0
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            professors,
+            departments,
+            academic_years,
+            show_success: false,
+            error_name_is_required: false,
+            error_duplicate: false,
+            error_invalid_professor: false,
+            error_year_not_open: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn name_is_required(mut self) -> Self {
+        self.error_name_is_required = true;
+        self
+    }
+
+    pub fn duplicate(mut self) -> Self {
+        self.error_duplicate = true;
+        self
+    }
+
+    pub fn invalid_professor(mut self) -> Self {
+        self.error_invalid_professor = true;
+        self
+    }
+
+    pub fn year_not_open(mut self) -> Self {
+        self.error_year_not_open = true;
+        self
+    }
+}
+
+/// Only administrators get to hand a course over to another professor, one
+/// of their department unless they administer every department
+async fn professors(database: &Database, user: &User, course: &Course) -> Result<Vec<User>, Error> {
+    if !user.administers(course.department) {
+        return Ok(Vec::new());
+    }
+
+    let department = user.department;
+    Ok(database
+        .run(Users::get_professors)
+        .await?
+        .0
+        .into_iter()
+        .filter(|professor| department.is_none() || professor.department == department)
+        .collect())
+}
+
+#[get("/course/<course>/edit")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_manage(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let professors = professors(&database, user, &course).await?;
+
+    Ok(Template::render(
+        "routes/professor/course/edit",
+        LayoutContext::new(language, user, &database, course, professors).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    name: String,
+    year: u32,
+    ects: u32,
+    semester: Option<u8>,
+    professor: Option<u32>,
+    department: Option<u32>,
+}
+
+#[post("/course/<course>/edit", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_manage(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    let professors = professors(&database, user, &course).await?;
+    let template_path = "routes/professor/course/edit";
+
+    let name = form.name.trim().to_owned();
+    if name.is_empty() {
+        return Ok(Template::render(
+            template_path,
+            LayoutContext::new(language, user, &database, course, professors)
+                .await?
+                .name_is_required(),
+        ));
+    }
+
+    let year = form.year;
+    let url = course.edited_url(&name, year);
+    let (ects, semester) = (form.ects, form.semester);
+    let administers = user.administers(course.department);
+    let new_professor = form
+        .professor
+        .filter(|_| administers)
+        .unwrap_or(course.professor);
+    let new_department = if administers {
+        user.department.or(form.department)
+    } else {
+        course.department
+    };
+
+    let updating_course = course.clone();
+    let administrator = user.clone();
+    let result = database
+        .run(move |c| {
+            c.transaction(|c| {
+                // The new owner has to be a professor the administrator manages
+                if new_professor != updating_course.professor {
+                    match User::get_by_id(c, new_professor) {
+                        Ok(owner)
+                            if owner.is_professor()
+                                && administrator.administers(owner.department) => {}
+                        Ok(_) | Err(Error::DatabaseEntryNotFound) => {
+                            return Err(Error::InvalidProfessor)
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                if year != updating_course.year
+                    && (AcademicYear::is_closed(c, updating_course.year)?
+                        || !AcademicYear::is_open(c, year)?)
+                {
+                    return Err(Error::AcademicYearNotOpen);
+                }
+
+                updating_course.update(c, year, &name, &url, new_professor)?;
+                updating_course.update_curriculum(c, ects, semester)?;
+                updating_course.update_department(c, new_department)?;
+                Course::get_by_id(c, updating_course.id)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(course) => Ok(Template::render(
+            template_path,
+            LayoutContext::new(language, user, &database, course, professors)
+                .await?
+                .success(),
+        )),
+        Err(Error::DatabaseDuplicateEntry) => Ok(Template::render(
+            template_path,
+            LayoutContext::new(language, user, &database, course, professors)
+                .await?
+                .duplicate(),
+        )),
+        Err(Error::InvalidProfessor) => Ok(Template::render(
+            template_path,
+            LayoutContext::new(language, user, &database, course, professors)
+                .await?
+                .invalid_professor(),
+        )),
+        Err(Error::AcademicYearNotOpen) => Ok(Template::render(
+            template_path,
+            LayoutContext::new(language, user, &database, course, professors)
+                .await?
+                .year_not_open(),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
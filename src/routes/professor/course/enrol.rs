@@ -1,3 +1,6 @@
+pub mod bulk;
+pub mod self_enrolment;
+
 use diesel::Connection;
 use rocket::{form::Form, get, http::Status, post, FromForm};
 use rocket_dyn_templates::Template;
@@ -6,7 +9,7 @@ use serde::Serialize;
 use crate::{
     base_layout_context::BaseLayoutContext,
     components::users::{self, ControlTypeOptions, EnrolOptions},
-    course::{Course, Enrolment},
+    course::{Course, CourseStaffMember, Enrolment},
     database::Database,
     error::Error,
     localization::Script,
@@ -46,7 +49,12 @@ pub async fn get(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    if !course.authorized_to_edit(user) {
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
         return Err(Status::Unauthorized);
     }
 
@@ -77,7 +85,12 @@ pub async fn post(
         .run(move |c| Course::get_by_url(c, &course))
         .await?;
 
-    if !course.authorized_to_edit(user) {
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
         return Err(Status::Unauthorized);
     }
 
@@ -109,3 +122,26 @@ pub async fn post(
 
     Ok(Template::render("routes/professor/course/enrol", context))
 }
+
+/// Loads the course and makes sure the user is allowed to change its
+/// enrolments
+pub async fn editable_course(
+    database: &Database,
+    user: &User,
+    course: String,
+) -> Result<Course, Status> {
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_edit(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    Ok(course)
+}
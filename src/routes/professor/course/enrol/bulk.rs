@@ -0,0 +1,277 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    components::users,
+    course::{Course, Enrolment},
+    database::Database,
+    error::Error,
+    index::Index,
+    localization::Script,
+    routes::professor::course::enrol::editable_course,
+    user::{AccountType, Professor, User, UserId, Users},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    /// Students the confirmation will enrol
+    students: Vec<UserId>,
+    found: usize,
+    already_enrolled: i64,
+    not_found: Vec<String>,
+    enrolled_count: Option<usize>,
+    /// Students the confirmation left out for not being active
+    skipped_inactive: usize,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, course: Course) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            students: Vec::new(),
+            found: 0,
+            already_enrolled: 0,
+            not_found: Vec::new(),
+            enrolled_count: None,
+            skipped_inactive: 0,
+        })
+    }
+
+    pub fn preview(
+        mut self,
+        students: Vec<UserId>,
+        already_enrolled: i64,
+        not_found: Vec<String>,
+    ) -> Self {
+        self.found = students.len();
+        self.students = students;
+        self.already_enrolled = already_enrolled;
+        self.not_found = not_found;
+        self
+    }
+
+    pub fn enrolled(mut self, count: usize) -> Self {
+        self.enrolled_count = Some(count);
+        self
+    }
+
+    pub fn skipped_inactive(mut self, count: usize) -> Self {
+        self.skipped_inactive = count;
+        self
+    }
+}
+
+async fn render_preview(
+    language: Script,
+    user: &User,
+    database: &Database,
+    course: Course,
+    students: Vec<UserId>,
+    not_found: Vec<String>,
+) -> Result<Template, Status> {
+    let course_id = course.id;
+    let (students, already_enrolled) = database
+        .run(move |c| {
+            let already_enrolled = Enrolment::count_enrolled(c, course_id, &students)?;
+            Ok::<_, Error>((students, already_enrolled))
+        })
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/confirm",
+        LayoutContext::new(language, user, course).await?.preview(
+            students,
+            already_enrolled,
+            not_found,
+        ),
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormDataFiltered {
+    users_form: users::FormData,
+}
+
+/// Previews enrolling every student matching the filters of the enrolment
+/// page, not only the ones on the current page
+#[post("/course/<course>/enrol/filtered", data = "<form>")]
+pub async fn post_filtered(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormDataFiltered>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let filters = form
+        .users_form
+        .retrieval_filters()
+        .active_students_by_default();
+    let students = database
+        .run(move |c| Users::get_student_ids(c, filters))
+        .await?;
+
+    render_preview(language, user, &database, course, students, Vec::new()).await
+}
+
+#[get("/course/<course>/enrol/list")]
+pub async fn get_list(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/list",
+        LayoutContext::new(language, user, course).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormDataList {
+    entries: String,
+}
+
+/// Previews enrolling students from a pasted list of index numbers and
+/// emails, separated by commas, semicolons or new lines
+#[post("/course/<course>/enrol/list", data = "<form>")]
+pub async fn post_list(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormDataList>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let entries: Vec<String> = form
+        .entries
+        .split([',', ';', '\n', '\r'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+
+    let (students, not_found) = database
+        .run(move |c| {
+            let mut students = Vec::new();
+            let mut not_found = Vec::new();
+
+            for entry in entries {
+                let student = if entry.contains('@') {
+                    match User::get_by_email(c, &entry) {
+                        // Only students are enrolled, anyone else is reported as not found
+                        Ok(user) if user.account_type == AccountType::Student => Some(user.id),
+                        Ok(_) | Err(Error::DatabaseEntryNotFound) => None,
+                        Err(e) => return Err(e),
+                    }
+                } else {
+                    Index::get_student_by_text(c, &entry)?
+                };
+
+                match student {
+                    Some(student) if !students.contains(&student) => students.push(student),
+                    Some(_) => (),
+                    None => not_found.push(entry),
+                }
+            }
+
+            Ok((students, not_found))
+        })
+        .await?;
+
+    render_preview(language, user, &database, course, students, not_found).await
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormDataStudents {
+    students: Vec<UserId>,
+}
+
+/// Enrols the students confirmed on one of the previews
+#[post("/course/<course>/enrol/students", data = "<form>")]
+pub async fn post_students(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormDataStudents>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    let students = form.into_inner().students;
+    let (enrolled, inactive) = database
+        .run(move |c| c.transaction(|c| Enrolment::create_many(c, course_id, &students)))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/confirm",
+        LayoutContext::new(language, user, course)
+            .await?
+            .enrolled(enrolled)
+            .skipped_inactive(inactive),
+    ))
+}
+
+#[get("/course/<course>/enrol/clear")]
+pub async fn get_clear(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    let already_enrolled = database
+        .run(move |c| Enrolment::count(c, course_id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/clear",
+        LayoutContext::new(language, user, course).await?.preview(
+            Vec::new(),
+            already_enrolled,
+            Vec::new(),
+        ),
+    ))
+}
+
+#[post("/course/<course>/enrol/clear")]
+pub async fn post_clear(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    let unenrolled = database
+        .run(move |c| c.transaction(|c| Enrolment::delete_all(c, course_id)))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/clear",
+        LayoutContext::new(language, user, course)
+            .await?
+            .enrolled(unenrolled),
+    ))
+}
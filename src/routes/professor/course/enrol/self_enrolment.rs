@@ -0,0 +1,194 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::{
+        Course, EnrolmentRequest, EnrolmentRequestWithUser, SelfEnrolment, SelfEnrolmentMode,
+    },
+    database::Database,
+    error::Error,
+    localization::Script,
+    routes::professor::course::enrol::editable_course,
+    user::{Professor, User, UserId},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    self_enrolment: SelfEnrolment,
+    requests: Vec<EnrolmentRequestWithUser>,
+    show_success: bool,
+    error_key_is_required: bool,
+    error_cannot_approve: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        course: Course,
+        self_enrolment: SelfEnrolment,
+        requests: Vec<EnrolmentRequestWithUser>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            self_enrolment,
+            requests,
+            show_success: false,
+            error_key_is_required: false,
+            error_cannot_approve: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn key_is_required(mut self) -> Self {
+        self.error_key_is_required = true;
+        self
+    }
+
+    pub fn cannot_approve(mut self) -> Self {
+        self.error_cannot_approve = true;
+        self
+    }
+}
+
+async fn render(
+    language: Script,
+    user: &User,
+    database: &Database,
+    course: Course,
+    finish: fn(LayoutContext) -> LayoutContext,
+) -> Result<Template, Status> {
+    let course_id = course.id;
+    let (self_enrolment, requests) = database
+        .run(move |c| {
+            Ok::<_, Error>((
+                SelfEnrolment::get(c, course_id)?,
+                EnrolmentRequest::get_all(c, course_id)?,
+            ))
+        })
+        .await?;
+
+    let context = LayoutContext::new(language, user, course, self_enrolment, requests).await?;
+
+    Ok(Template::render(
+        "routes/professor/course/enrol/self_enrolment",
+        finish(context),
+    ))
+}
+
+#[get("/course/<course>/enrol/requests")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    render(language, user, &database, course, |context| context).await
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    mode: SelfEnrolmentMode,
+    enrolment_key: String,
+}
+
+#[post("/course/<course>/enrol/requests", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let mode = form.mode;
+    let enrolment_key = Some(form.enrolment_key.trim().to_owned()).filter(|key| !key.is_empty());
+
+    // An empty key keeps the one the course has, which can't be shown
+    let course_id = course.id;
+    let saved = database
+        .run(move |c| {
+            c.transaction(|c| {
+                if mode == SelfEnrolmentMode::Key
+                    && enrolment_key.is_none()
+                    && !SelfEnrolment::get(c, course_id)?.has_key()
+                {
+                    return Ok(false);
+                }
+                SelfEnrolment::set(c, course_id, mode, enrolment_key.as_deref()).map(|()| true)
+            })
+        })
+        .await?;
+
+    if !saved {
+        return render(
+            language,
+            user,
+            &database,
+            course,
+            LayoutContext::key_is_required,
+        )
+        .await;
+    }
+
+    render(language, user, &database, course, LayoutContext::success).await
+}
+
+#[post("/course/<course>/enrol/requests/<student>/approve")]
+pub async fn post_approve(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    student: UserId,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    let approved = database
+        .run(move |c| c.transaction(|c| EnrolmentRequest::approve(c, course_id, student)))
+        .await?;
+
+    let finish = if approved {
+        LayoutContext::success
+    } else {
+        LayoutContext::cannot_approve
+    };
+    render(language, user, &database, course, finish).await
+}
+
+#[post("/course/<course>/enrol/requests/<student>/reject")]
+pub async fn post_reject(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    student: UserId,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = editable_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    database
+        .run(move |c| EnrolmentRequest::reject(c, course_id, student))
+        .await?;
+
+    render(language, user, &database, course, LayoutContext::success).await
+}
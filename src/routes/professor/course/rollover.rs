@@ -0,0 +1,162 @@
+use diesel::Connection;
+use rocket::{form::Form, get, http::Status, post, response::Redirect, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    academic_year::{AcademicYear, AcademicYears},
+    base_layout_context::BaseLayoutContext,
+    catchers::TemplateOrRedirect,
+    course::{Course, CourseStaffMember, Enrolment},
+    database::Database,
+    error::Error,
+    index::{Generation, Generations, Program, Programs},
+    localization::Script,
+    user::{Professor, User},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    academic_years: Vec<AcademicYear>,
+    programs: Vec<Program>,
+    generations: Vec<Generation>,
+    error_duplicate: bool,
+    error_year_not_open: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        database: &Database,
+        course: Course,
+    ) -> Result<Self, Error> {
+        // Only later years that are still open can be rolled over into
+        let year = course.year;
+        let (academic_years, programs, generations) = database
+            .run(move |c| {
+                let mut academic_years = AcademicYears::get_open(c)?.0;
+                academic_years.retain(|academic_year| academic_year.year > year);
+                Ok::<_, Error>((academic_years, Programs::get(c)?.0, Generations::get(c)?.0))
+            })
+            .await?;
+
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            academic_years,
+            programs,
+            generations,
+            error_duplicate: false,
+            error_year_not_open: false,
+        })
+    }
+
+    pub fn duplicate(mut self) -> Self {
+        self.error_duplicate = true;
+        self
+    }
+
+    pub fn year_not_open(mut self) -> Self {
+        self.error_year_not_open = true;
+        self
+    }
+}
+
+/// Loads the course and makes sure the user is allowed to roll it over
+async fn managed_course(
+    database: &Database,
+    user: &User,
+    course: String,
+) -> Result<Course, Status> {
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_manage(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    Ok(course)
+}
+
+#[get("/course/<course>/rollover")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = managed_course(&database, user, course).await?;
+
+    Ok(Template::render(
+        "routes/professor/course/rollover",
+        LayoutContext::new(language, user, &database, course).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    year: u32,
+    programs: Vec<u32>,
+    generations: Vec<u32>,
+}
+
+#[post("/course/<course>/rollover", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<TemplateOrRedirect, Status> {
+    let user = professor.0;
+    let course = managed_course(&database, user, course).await?;
+
+    let form = form.into_inner();
+    let rolled_over_course = course.clone();
+    let result = database
+        .run(move |c| {
+            c.transaction(|c| {
+                // Only into a later year that is still open, the same ones the
+                // form lists
+                if form.year <= rolled_over_course.year || !AcademicYear::is_open(c, form.year)? {
+                    return Err(Error::AcademicYearNotOpen);
+                }
+
+                let course = rolled_over_course.roll_over(c, form.year)?;
+                Enrolment::create_for_indices(c, course.id, &form.programs, &form.generations)?;
+                Ok(course)
+            })
+        })
+        .await;
+
+    match result {
+        Ok(new_course) => Ok(TemplateOrRedirect::Redirect(Redirect::to(format!(
+            "/course/{}",
+            new_course.url
+        )))),
+        Err(Error::DatabaseDuplicateEntry) => Ok(TemplateOrRedirect::Template(Template::render(
+            "routes/professor/course/rollover",
+            LayoutContext::new(language, user, &database, course)
+                .await?
+                .duplicate(),
+        ))),
+        Err(Error::AcademicYearNotOpen) => Ok(TemplateOrRedirect::Template(Template::render(
+            "routes/professor/course/rollover",
+            LayoutContext::new(language, user, &database, course)
+                .await?
+                .year_not_open(),
+        ))),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -0,0 +1,192 @@
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseRole, CourseStaffMember, CourseStaffMemberWithUser},
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::{AccountType, Professor, User, UserId},
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    course: Course,
+    staff: Vec<CourseStaffMemberWithUser>,
+    show_success: bool,
+    error_user_not_found: bool,
+    error_not_a_professor: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        course: Course,
+        staff: Vec<CourseStaffMemberWithUser>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            course,
+            staff,
+            show_success: false,
+            error_user_not_found: false,
+            error_not_a_professor: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn user_not_found(mut self) -> Self {
+        self.error_user_not_found = true;
+        self
+    }
+
+    pub fn not_a_professor(mut self) -> Self {
+        self.error_not_a_professor = true;
+        self
+    }
+}
+
+/// Loads the course and makes sure the user is allowed to manage its staff
+async fn managed_course(
+    database: &Database,
+    user: &User,
+    course: String,
+) -> Result<Course, Status> {
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    let (course_id, user_id) = (course.id, user.id);
+    let role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, user_id))
+        .await?;
+
+    if !course.authorized_to_manage(user, role) {
+        return Err(Status::Unauthorized);
+    }
+
+    Ok(course)
+}
+
+async fn render(
+    language: Script,
+    user: &User,
+    database: &Database,
+    course: Course,
+    finish: fn(LayoutContext) -> LayoutContext,
+) -> Result<Template, Status> {
+    let course_id = course.id;
+    let staff = database
+        .run(move |c| CourseStaffMember::get_all(c, course_id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/professor/course/staff",
+        finish(LayoutContext::new(language, user, course, staff).await?),
+    ))
+}
+
+#[get("/course/<course>/staff")]
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = managed_course(&database, user, course).await?;
+
+    render(language, user, &database, course, |context| context).await
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    email: String,
+    role: CourseRole,
+}
+
+#[post("/course/<course>/staff", data = "<form>")]
+pub async fn post(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = managed_course(&database, user, course).await?;
+
+    // The owner is changed by editing the course
+    if form.role == CourseRole::Owner {
+        return Err(Status::BadRequest);
+    }
+
+    let email = form.email.trim().to_owned();
+    let member = match database.run(move |c| User::get_by_email(c, &email)).await {
+        Ok(member) => member,
+        Err(Error::DatabaseEntryNotFound) => {
+            return render(
+                language,
+                user,
+                &database,
+                course,
+                LayoutContext::user_not_found,
+            )
+            .await
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if member.account_type == AccountType::Student {
+        return render(
+            language,
+            user,
+            &database,
+            course,
+            LayoutContext::not_a_professor,
+        )
+        .await;
+    }
+
+    let (course_id, role) = (course.id, form.role);
+    let current_role = database
+        .run(move |c| CourseStaffMember::get_role(c, course_id, member.id))
+        .await?;
+
+    // Keeps the owner from being demoted by adding them again
+    if current_role != Some(CourseRole::Owner) {
+        database
+            .run(move |c| CourseStaffMember::set(c, course_id, member.id, role))
+            .await?;
+    }
+
+    render(language, user, &database, course, LayoutContext::success).await
+}
+
+#[post("/course/<course>/staff/remove/<member>")]
+pub async fn post_remove(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+    course: String,
+    member: UserId,
+) -> Result<Template, Status> {
+    let user = professor.0;
+    let course = managed_course(&database, user, course).await?;
+
+    let course_id = course.id;
+    database
+        .run(move |c| CourseStaffMember::remove(c, course_id, member))
+        .await?;
+
+    render(language, user, &database, course, LayoutContext::success).await
+}
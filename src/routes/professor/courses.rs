@@ -1,5 +1,6 @@
 pub mod create;
 
+use chrono::{Datelike, Duration, Local, NaiveTime};
 use rocket::{
     get,
     http::{CookieJar, Status},
@@ -7,25 +8,53 @@ use rocket::{
 use rocket_dyn_templates::Template;
 
 use crate::{
-    course::Courses, database::Database, localization::Script, routes::courses, user::Professor,
+    academic_year::{AcademicYear, AcademicYears},
+    assignment::Deadlines,
+    course::Courses,
+    database::Database,
+    error::Error,
+    localization::Script,
+    routes::courses,
+    user::Professor,
 };
 
-#[get("/courses", rank = 1)]
+#[get("/courses?<year>", rank = 1)]
 pub async fn get(
     language: Script,
     professor: Professor<'_>,
     database: Database,
     _jar: &CookieJar<'_>,
+    year: Option<u32>,
 ) -> Result<Template, Status> {
     let user = professor.0;
     let user_id = user.id();
 
-    let courses = database
-        .run(move |c| Courses::get_teaching(c, user_id))
+    let (courses, academic_years, year) = database
+        .run(move |c| {
+            let year = AcademicYear::year_or_active(c, year)?;
+            Ok::<_, Error>((
+                Courses::get_teaching(c, user_id, year)?.0,
+                AcademicYears::get(c)?.0,
+                year,
+            ))
+        })
+        .await?;
+
+    // Assignments closing between now and the end of the current week
+    let now = Local::now().naive_local();
+    let days_left = 7 - i64::from(now.weekday().num_days_from_monday());
+    let end_of_week = (now.date() + Duration::days(days_left)).and_time(NaiveTime::MIN);
+
+    let course_ids: Vec<_> = courses.iter().map(|course| course.id).collect();
+    let deadlines = database
+        .run(move |c| Deadlines::get(c, &course_ids, now, Some(end_of_week)))
         .await?
         .0;
 
-    let context = courses::LayoutContext::new(language, user, courses).await?;
+    let context = courses::LayoutContext::new(language, user, courses)
+        .await?
+        .deadlines(deadlines)
+        .academic_years(academic_years, year);
 
     Ok(Template::render("routes/professor/courses", context))
 }
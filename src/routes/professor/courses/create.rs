@@ -1,8 +1,10 @@
+use diesel::Connection;
 use rocket::{form::Form, get, http::Status, post, FromForm};
 use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
+    academic_year::{AcademicYear, AcademicYears},
     base_layout_context::BaseLayoutContext,
     course::Course,
     database::Database,
@@ -15,16 +17,25 @@ use crate::{
 struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
+    academic_years: Vec<AcademicYear>,
     show_success_message: bool,
     show_course_name_is_required: bool,
+    show_duplicate: bool,
+    show_deleted_duplicate: bool,
+    show_year_not_open: bool,
 }
 
 impl LayoutContext {
-    pub async fn new(language: Script, user: &User) -> Result<Self, Error> {
+    pub async fn new(language: Script, user: &User, database: &Database) -> Result<Self, Error> {
+        let academic_years = database.run(AcademicYears::get_open).await?.0;
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
+            academic_years,
             show_success_message: false,
             show_course_name_is_required: false,
+            show_duplicate: false,
+            show_deleted_duplicate: false,
+            show_year_not_open: false,
         })
     }
 
@@ -37,14 +48,33 @@ impl LayoutContext {
         self.show_course_name_is_required = true;
         self
     }
+
+    pub fn duplicate(mut self) -> Self {
+        self.show_duplicate = true;
+        self
+    }
+
+    pub fn deleted_duplicate(mut self) -> Self {
+        self.show_deleted_duplicate = true;
+        self
+    }
+
+    pub fn year_not_open(mut self) -> Self {
+        self.show_year_not_open = true;
+        self
+    }
 }
 
 #[get("/courses/create", rank = 0)]
-pub async fn get(language: Script, professor: Professor<'_>) -> Result<Template, Status> {
+pub async fn get(
+    language: Script,
+    professor: Professor<'_>,
+    database: Database,
+) -> Result<Template, Status> {
     let user = professor.0;
     Ok(Template::render(
         "routes/professor/courses/create",
-        LayoutContext::new(language, user).await?,
+        LayoutContext::new(language, user, &database).await?,
     ))
 }
 
@@ -66,24 +96,66 @@ pub async fn post(
     if form.name.is_empty() {
         return Ok(Template::render(
             "routes/professor/courses/create",
-            LayoutContext::new(language, user)
+            LayoutContext::new(language, user, &database)
                 .await?
                 .course_name_is_required(),
         ));
     }
 
-    let url: String = crate::util::string_to_url(&form.name);
+    let url: String = Course::url_for(&form.name, form.year);
 
     println!("Url is {:?}", url);
 
     let user_id = user.id();
+    // Courses belong to the department of the professor creating them
+    let department = user.department;
 
-    database
-        .run(move |c| Course::create(c, form.year, &form.name, &url, user_id))
-        .await?;
+    let year = form.year;
+    let name = form.name.clone();
+    let created_url = url.clone();
+    match database
+        .run(move |c| {
+            c.transaction(|c| {
+                if !AcademicYear::is_open(c, year)? {
+                    return Err(Error::AcademicYearNotOpen);
+                }
+                Course::create(c, year, &name, &created_url, user_id, department)
+            })
+        })
+        .await
+    {
+        Ok(_) => (),
+        Err(Error::DatabaseDuplicateEntry) => {
+            // A soft-deleted course keeps its name and URL, only an
+            // administrator can bring it back
+            let name = form.name.clone();
+            let deleted = database
+                .run(move |c| Course::get_deleted_conflicting(c, year, &name, &url))
+                .await?;
+
+            let context = LayoutContext::new(language, user, &database).await?;
+            let context = match deleted {
+                Some(_) => context.deleted_duplicate(),
+                None => context.duplicate(),
+            };
+
+            return Ok(Template::render("routes/professor/courses/create", context));
+        }
+        Err(Error::AcademicYearNotOpen) => {
+            return Ok(Template::render(
+                "routes/professor/courses/create",
+                LayoutContext::new(language, user, &database)
+                    .await?
+                    .year_not_open(),
+            ));
+        }
+        Err(e) => return Err(e.into()),
+    }
 
     Ok(Template::render(
         "routes/professor/courses/create",
-        LayoutContext::new(language, user).await?.success(),
+        LayoutContext::new(language, user, &database)
+            .await?
+            .success(),
     ))
 }
@@ -0,0 +1,163 @@
+use rocket::{form::Form, get, http::Status, post, response::Redirect, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    academic_year::AcademicYear,
+    base_layout_context::BaseLayoutContext,
+    catchers::TemplateOrRedirect,
+    course::{Course, Courses, Enrolment, EnrolmentRequest, SelfEnrolment, SelfEnrolmentMode},
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::User,
+};
+
+#[derive(Serialize, Debug)]
+struct CatalogCourse {
+    #[serde(flatten)]
+    course: Course,
+    mode: SelfEnrolmentMode,
+    enrolled: bool,
+    requested: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    courses: Vec<CatalogCourse>,
+    error_wrong_key: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        courses: Vec<CatalogCourse>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            courses,
+            error_wrong_key: false,
+        })
+    }
+
+    pub fn wrong_key(mut self) -> Self {
+        self.error_wrong_key = true;
+        self
+    }
+}
+
+async fn render(
+    language: Script,
+    user: &User,
+    database: &Database,
+    finish: fn(LayoutContext) -> LayoutContext,
+) -> Result<Template, Status> {
+    let user_id = user.id;
+
+    // Without an active year there is nothing to enrol in
+    let (catalog, enrolled, requested) = database
+        .run(move |c| {
+            let catalog = match AcademicYear::get_active(c)? {
+                Some(active) => Courses::get_catalog(c, active.year)?,
+                None => Vec::new(),
+            };
+
+            Ok::<_, Error>((
+                catalog,
+                Courses::get_all_enrolled(c, user_id)?.0,
+                Courses::get_requested(c, user_id)?.0,
+            ))
+        })
+        .await?;
+
+    let courses = catalog
+        .into_iter()
+        .map(|(course, mode)| CatalogCourse {
+            enrolled: enrolled.iter().any(|c| c.id == course.id),
+            requested: requested.iter().any(|c| c.id == course.id),
+            course,
+            mode,
+        })
+        .collect();
+
+    let context = LayoutContext::new(language, user, courses).await?;
+
+    Ok(Template::render("routes/student/catalog", finish(context)))
+}
+
+#[get("/catalog")]
+pub async fn get(language: Script, user: &User, database: Database) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    render(language, user, &database, |context| context).await
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    enrolment_key: Option<String>,
+}
+
+#[post("/catalog/<course>", data = "<form>")]
+pub async fn post(
+    language: Script,
+    user: &User,
+    database: Database,
+    course: String,
+    form: Form<FormData>,
+) -> Result<TemplateOrRedirect, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+    if !user.is_active_student() {
+        return Err(Status::Forbidden);
+    }
+
+    let course = database
+        .run(move |c| Course::get_by_url(c, &course))
+        .await?;
+
+    // Only courses in the catalog, those of the active year, take students
+    let active = database.run(AcademicYear::get_active).await?;
+    if active.map(|active| active.year) != Some(course.year) {
+        return Err(Status::Forbidden);
+    }
+
+    let course_id = course.id;
+    let self_enrolment = database
+        .run(move |c| SelfEnrolment::get(c, course_id))
+        .await?;
+
+    let user_id = user.id;
+    match self_enrolment.mode {
+        SelfEnrolmentMode::Disabled => return Err(Status::Unauthorized),
+        SelfEnrolmentMode::Key
+            if !self_enrolment.key_matches(form.enrolment_key.as_deref().unwrap_or_default()) =>
+        {
+            return render(language, user, &database, LayoutContext::wrong_key)
+                .await
+                .map(TemplateOrRedirect::Template);
+        }
+        SelfEnrolmentMode::Open | SelfEnrolmentMode::Key => {
+            database
+                .run(move |c| Enrolment::create_many(c, course_id, &[user_id]))
+                .await?;
+        }
+        SelfEnrolmentMode::Approval => {
+            database
+                .run(move |c| EnrolmentRequest::create(c, course_id, user_id))
+                .await?;
+
+            return Ok(TemplateOrRedirect::Redirect(Redirect::to("/courses")));
+        }
+    }
+
+    Ok(TemplateOrRedirect::Redirect(Redirect::to(format!(
+        "/course/{}",
+        course.url
+    ))))
+}
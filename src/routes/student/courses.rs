@@ -1,3 +1,4 @@
+use chrono::Local;
 use rocket::{
     get,
     http::{CookieJar, Status},
@@ -6,7 +7,8 @@ use rocket_dyn_templates::Template;
 use serde::Serialize;
 
 use crate::{
-    assignment::{GradedAssignment, GradedAssignments},
+    academic_year::{AcademicYear, AcademicYears},
+    assignment::{Deadline, Deadlines, GradedAssignment, GradedAssignments},
     base_layout_context::BaseLayoutContext,
     course::{Course, Courses},
     database::Database,
@@ -27,6 +29,10 @@ struct LayoutContext {
     #[serde(flatten)]
     base_layout_context: BaseLayoutContext,
     courses: Vec<CourseWithAssignments>,
+    deadlines: Vec<Deadline>,
+    requested: Vec<Course>,
+    academic_years: Vec<AcademicYear>,
+    selected_year: Option<u32>,
 }
 
 impl LayoutContext {
@@ -34,25 +40,55 @@ impl LayoutContext {
         language: Script,
         user: &User,
         courses: Vec<CourseWithAssignments>,
+        deadlines: Vec<Deadline>,
+        requested: Vec<Course>,
     ) -> Result<Self, Error> {
         Ok(Self {
             base_layout_context: BaseLayoutContext::new(language, user).await?,
             courses,
+            deadlines,
+            requested,
+            academic_years: Vec::new(),
+            selected_year: None,
         })
     }
+
+    pub fn academic_years(
+        mut self,
+        academic_years: Vec<AcademicYear>,
+        selected_year: Option<u32>,
+    ) -> Self {
+        self.academic_years = academic_years;
+        self.selected_year = selected_year;
+        self
+    }
 }
 
-#[get("/courses", rank = 2)]
+#[get("/courses?<year>", rank = 2)]
 pub async fn get(
     language: Script,
     user: &User,
     database: Database,
     _jar: &CookieJar<'_>,
+    year: Option<u32>,
 ) -> Result<Template, Status> {
     let user_id = user.id();
 
-    let mut enrolled_courses = database
-        .run(move |c| Courses::get_enrolled(c, user_id))
+    let (mut enrolled_courses, academic_years, year) = database
+        .run(move |c| {
+            let year = AcademicYear::year_or_active(c, year)?;
+            Ok::<_, Error>((
+                Courses::get_enrolled(c, user_id, year)?.0,
+                AcademicYears::get(c)?.0,
+                year,
+            ))
+        })
+        .await?;
+
+    let course_ids: Vec<_> = enrolled_courses.iter().map(|course| course.id).collect();
+    let now = Local::now().naive_local();
+    let deadlines = database
+        .run(move |c| Deadlines::get(c, &course_ids, now, None))
         .await?
         .0;
 
@@ -71,7 +107,14 @@ pub async fn get(
         });
     }
 
-    let context = LayoutContext::new(language, user, courses).await?;
+    let requested = database
+        .run(move |c| Courses::get_requested(c, user_id))
+        .await?
+        .0;
+
+    let context = LayoutContext::new(language, user, courses, deadlines, requested)
+        .await?
+        .academic_years(academic_years, year);
 
     Ok(Template::render("routes/student/courses", context))
 }
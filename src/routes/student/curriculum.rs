@@ -0,0 +1,35 @@
+use rocket::{get, http::Status};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext, curriculum::CurriculumProgress, database::Database,
+    localization::Script, user::User,
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    progress: Option<CurriculumProgress>,
+}
+
+#[get("/curriculum")]
+pub async fn get(language: Script, user: &User, database: Database) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    let student = user.id;
+    let progress = database
+        .run(move |c| CurriculumProgress::get(c, student))
+        .await?;
+
+    Ok(Template::render(
+        "routes/student/curriculum",
+        LayoutContext {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            progress,
+        },
+    ))
+}
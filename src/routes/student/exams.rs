@@ -0,0 +1,189 @@
+use chrono::Local;
+use rocket::{get, http::Status, post};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext,
+    course::{Course, CourseId, Courses},
+    database::Database,
+    error::Error,
+    exam::{ExamPeriod, ExamPeriodId, ExamPeriods, ExamRegistration},
+    localization::Script,
+    user::User,
+};
+
+#[derive(Serialize, Debug)]
+struct ExamCourse {
+    #[serde(flatten)]
+    course: Course,
+    registered: bool,
+}
+
+#[derive(Serialize, Debug)]
+struct ExamPeriodWithCourses {
+    #[serde(flatten)]
+    exam_period: ExamPeriod,
+    courses: Vec<ExamCourse>,
+}
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    exam_periods: Vec<ExamPeriodWithCourses>,
+    show_success: bool,
+    error_registration_closed: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        exam_periods: Vec<ExamPeriodWithCourses>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            exam_periods,
+            show_success: false,
+            error_registration_closed: false,
+        })
+    }
+
+    pub fn success(mut self) -> Self {
+        self.show_success = true;
+        self
+    }
+
+    pub fn registration_closed(mut self) -> Self {
+        self.error_registration_closed = true;
+        self
+    }
+}
+
+async fn render(
+    language: Script,
+    user: &User,
+    database: &Database,
+    finish: fn(LayoutContext) -> LayoutContext,
+) -> Result<Template, Status> {
+    let user_id = user.id;
+    let now = Local::now().naive_local();
+
+    let (open_periods, enrolled, registrations) = database
+        .run(move |c| {
+            let open_periods = ExamPeriods::get_open(c, now)?.0;
+            let ids: Vec<_> = open_periods.iter().map(|period| period.id).collect();
+            Ok::<_, Error>((
+                open_periods,
+                Courses::get_enrolled(c, user_id, None)?.0,
+                ExamRegistration::get_for_student(c, user_id, &ids)?,
+            ))
+        })
+        .await?;
+
+    let exam_periods = open_periods
+        .into_iter()
+        .map(|exam_period| ExamPeriodWithCourses {
+            courses: enrolled
+                .iter()
+                .map(|course| ExamCourse {
+                    registered: registrations.iter().any(|(period, registered)| {
+                        *period == exam_period.id && registered.id == course.id
+                    }),
+                    course: course.clone(),
+                })
+                .collect(),
+            exam_period,
+        })
+        .collect();
+
+    let context = LayoutContext::new(language, user, exam_periods).await?;
+
+    Ok(Template::render("routes/student/exams", finish(context)))
+}
+
+/// Loads the period and checks that registration is open, students can
+/// only change their registrations inside that window
+async fn open_exam_period(
+    database: &Database,
+    exam_period: ExamPeriodId,
+) -> Result<Option<ExamPeriod>, Status> {
+    let exam_period = database
+        .run(move |c| ExamPeriod::get_by_id(c, exam_period))
+        .await?;
+
+    Ok(Some(exam_period).filter(|period| period.is_open(Local::now().naive_local())))
+}
+
+#[get("/exams")]
+pub async fn get(language: Script, user: &User, database: Database) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    render(language, user, &database, |context| context).await
+}
+
+#[post("/exams/<exam_period>/<course>")]
+pub async fn post(
+    language: Script,
+    user: &User,
+    database: Database,
+    exam_period: ExamPeriodId,
+    course: CourseId,
+) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+    if !user.is_active_student() {
+        return Err(Status::Forbidden);
+    }
+
+    let Some(exam_period) = open_exam_period(&database, exam_period).await? else {
+        return render(
+            language,
+            user,
+            &database,
+            LayoutContext::registration_closed,
+        )
+        .await;
+    };
+
+    let (exam_period, student) = (exam_period.id, user.id);
+    database
+        .run(move |c| ExamRegistration::create(c, exam_period, course, student))
+        .await?;
+
+    render(language, user, &database, LayoutContext::success).await
+}
+
+#[post("/exams/<exam_period>/<course>/cancel")]
+pub async fn post_cancel(
+    language: Script,
+    user: &User,
+    database: Database,
+    exam_period: ExamPeriodId,
+    course: CourseId,
+) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    let Some(exam_period) = open_exam_period(&database, exam_period).await? else {
+        return render(
+            language,
+            user,
+            &database,
+            LayoutContext::registration_closed,
+        )
+        .await;
+    };
+
+    let (exam_period, student) = (exam_period.id, user.id);
+    database
+        .run(move |c| ExamRegistration::delete(c, exam_period, course, student))
+        .await?;
+
+    render(language, user, &database, LayoutContext::success).await
+}
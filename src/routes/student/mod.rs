@@ -1,2 +1,6 @@
+pub mod catalog;
 pub mod course;
 pub mod courses;
+pub mod curriculum;
+pub mod exams;
+pub mod transcript;
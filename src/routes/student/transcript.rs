@@ -0,0 +1,40 @@
+use rocket::{get, http::ContentType, http::Status};
+use rocket_dyn_templates::Template;
+
+use crate::{
+    database::Database, localization::Script, routes::transcript, transcript::Transcript,
+    user::User,
+};
+
+#[get("/transcript")]
+pub async fn get(language: Script, user: &User, database: Database) -> Result<Template, Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    let student = user.id;
+    let transcript = database.run(move |c| Transcript::get(c, student)).await?;
+
+    let context = transcript::LayoutContext::new(
+        language,
+        user,
+        transcript,
+        "/transcript/pdf".to_owned(),
+        "/courses".to_owned(),
+    )
+    .await?;
+
+    Ok(Template::render("routes/transcript", context))
+}
+
+#[get("/transcript/pdf")]
+pub async fn get_pdf(user: &User, database: Database) -> Result<(ContentType, Vec<u8>), Status> {
+    if user.is_professor() {
+        return Err(Status::Unauthorized);
+    }
+
+    let student = user.id;
+    let transcript = database.run(move |c| Transcript::get(c, student)).await?;
+
+    Ok((ContentType::PDF, transcript.pdf()))
+}
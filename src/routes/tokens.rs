@@ -0,0 +1,134 @@
+use chrono::Local;
+use rocket::{form::Form, get, http::Status, post, FromForm};
+use rocket_dyn_templates::Template;
+use serde::Serialize;
+
+use crate::{
+    api_token::{ApiToken, ApiTokenScopes},
+    base_layout_context::BaseLayoutContext,
+    database::Database,
+    error::Error,
+    localization::Script,
+    user::User,
+    util,
+};
+
+#[derive(Serialize, Debug)]
+struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    tokens: Vec<ApiToken>,
+    created_token: Option<String>,
+    show_revoked_message: bool,
+    show_error_invalid_date: bool,
+}
+
+impl LayoutContext {
+    pub async fn new(language: Script, user: &User, database: &Database) -> Result<Self, Error> {
+        let user_id = user.id;
+        let tokens = database.run(move |c| ApiToken::get_all(c, user_id)).await?;
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            tokens,
+            created_token: None,
+            show_revoked_message: false,
+            show_error_invalid_date: false,
+        })
+    }
+
+    pub fn created_token(mut self, token: String) -> Self {
+        self.created_token = Some(token);
+        self
+    }
+
+    pub fn show_revoked_message(mut self) -> Self {
+        self.show_revoked_message = true;
+        self
+    }
+
+    pub fn show_error_invalid_date(mut self) -> Self {
+        self.show_error_invalid_date = true;
+        self
+    }
+}
+
+#[get("/tokens")]
+pub async fn get(language: Script, user: &User, database: Database) -> Result<Template, Status> {
+    Ok(Template::render(
+        "routes/tokens",
+        LayoutContext::new(language, user, &database).await?,
+    ))
+}
+
+#[derive(FromForm, Debug)]
+pub struct FormData {
+    name: String,
+    read_grades: bool,
+    write_grades: bool,
+    manage_enrolments: bool,
+    manage_users: bool,
+    expires_on: String,
+}
+
+#[post("/tokens", data = "<form>")]
+pub async fn post(
+    language: Script,
+    user: &User,
+    database: Database,
+    form: Form<FormData>,
+) -> Result<Template, Status> {
+    let expires_on = match form.expires_on.trim() {
+        "" => None,
+        date => match util::parse_date(date) {
+            Some(date) if date >= Local::now().date_naive() => Some(date),
+            _ => {
+                return Ok(Template::render(
+                    "routes/tokens",
+                    LayoutContext::new(language, user, &database)
+                        .await?
+                        .show_error_invalid_date(),
+                ))
+            }
+        },
+    };
+
+    let scopes = ApiTokenScopes {
+        read_grades: form.read_grades,
+        write_grades: form.write_grades,
+        manage_enrolments: form.manage_enrolments,
+        manage_users: form.manage_users,
+    }
+    .allowed_for(user);
+
+    let (user_id, name) = (user.id, form.into_inner().name);
+    let token = database
+        .run(move |c| ApiToken::create(c, user_id, name.trim(), scopes, expires_on))
+        .await?;
+
+    Ok(Template::render(
+        "routes/tokens",
+        LayoutContext::new(language, user, &database)
+            .await?
+            .created_token(token),
+    ))
+}
+
+#[post("/tokens/<id>/revoke")]
+pub async fn post_revoke(
+    language: Script,
+    user: &User,
+    database: Database,
+    id: u32,
+) -> Result<Template, Status> {
+    let user_id = user.id;
+    database
+        .run(move |c| ApiToken::revoke(c, user_id, id))
+        .await?;
+
+    Ok(Template::render(
+        "routes/tokens",
+        LayoutContext::new(language, user, &database)
+            .await?
+            .show_revoked_message(),
+    ))
+}
@@ -0,0 +1,32 @@
+use serde::Serialize;
+
+use crate::{
+    base_layout_context::BaseLayoutContext, error::Error, localization::Script,
+    transcript::Transcript, user::User,
+};
+
+#[derive(Serialize, Debug)]
+pub struct LayoutContext {
+    #[serde(flatten)]
+    base_layout_context: BaseLayoutContext,
+    transcript: Transcript,
+    pdf_href: String,
+    back_href: String,
+}
+
+impl LayoutContext {
+    pub async fn new(
+        language: Script,
+        user: &User,
+        transcript: Transcript,
+        pdf_href: String,
+        back_href: String,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_layout_context: BaseLayoutContext::new(language, user).await?,
+            transcript,
+            pdf_href,
+            back_href,
+        })
+    }
+}
@@ -1,15 +1,110 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    academic_years (id) {
+        id -> Unsigned<Integer>,
+        year -> Unsigned<Integer>,
+        starts -> Date,
+        ends -> Date,
+        status -> Unsigned<Tinyint>,
+    }
+}
+
+diesel::table! {
+    api_tokens (id) {
+        id -> Unsigned<Integer>,
+        user -> Unsigned<Integer>,
+        name -> Varchar,
+        token_hash -> Binary,
+        read_grades -> Bool,
+        write_grades -> Bool,
+        manage_enrolments -> Bool,
+        manage_users -> Bool,
+        expires_on -> Nullable<Date>,
+        last_used -> Nullable<Datetime>,
+        created_on -> Datetime,
+    }
+}
+
 diesel::table! {
     assignments (id) {
         id -> Unsigned<Integer>,
         course -> Unsigned<Integer>,
         name -> Varchar,
         url -> Varchar,
+        assignment_type -> Unsigned<Tinyint>,
+        position -> Unsigned<Integer>,
+        description -> Nullable<Text>,
+        opens_at -> Nullable<Datetime>,
+        due_at -> Nullable<Datetime>,
+        published -> Bool,
+        exam_period -> Nullable<Unsigned<Integer>>,
         deleted -> Bool,
     }
 }
 
+diesel::table! {
+    calendar_tokens (user) {
+        user -> Unsigned<Integer>,
+        token -> Binary,
+        script -> Varchar,
+        created_on -> Datetime,
+    }
+}
+
+diesel::table! {
+    counter_assignments (id) {
+        id -> Unsigned<Integer>,
+        assignment -> Unsigned<Integer>,
+        max_count -> Unsigned<Integer>,
+    }
+}
+
+diesel::table! {
+    counter_assignments_progress (assignment, student) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        count -> Unsigned<Integer>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    counter_assignments_progress_revisions (assignment, student, revision) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        revision -> Unsigned<Integer>,
+        created -> Nullable<Datetime>,
+        count -> Unsigned<Integer>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    course_self_enrolment (course) {
+        course -> Unsigned<Integer>,
+        mode -> Unsigned<Tinyint>,
+        enrolment_key_hash -> Nullable<Binary>,
+    }
+}
+
+diesel::table! {
+    course_staff (course, user) {
+        course -> Unsigned<Integer>,
+        user -> Unsigned<Integer>,
+        role -> Unsigned<Tinyint>,
+    }
+}
+
+diesel::table! {
+    course_url_redirects (url) {
+        url -> Varchar,
+        course -> Unsigned<Integer>,
+    }
+}
+
 diesel::table! {
     courses (id) {
         id -> Unsigned<Integer>,
@@ -18,6 +113,10 @@ diesel::table! {
         url -> Varchar,
         professor -> Unsigned<Integer>,
         deleted -> Bool,
+        ects -> Unsigned<Integer>,
+        semester -> Nullable<Unsigned<Tinyint>>,
+        department -> Nullable<Unsigned<Integer>>,
+        original -> Nullable<Unsigned<Integer>>,
     }
 }
 
@@ -31,6 +130,16 @@ diesel::table! {
         url -> Varchar,
         professor -> Unsigned<Integer>,
         deleted -> Bool,
+        ects -> Unsigned<Integer>,
+        semester -> Nullable<Unsigned<Tinyint>>,
+        department -> Nullable<Unsigned<Integer>>,
+    }
+}
+
+diesel::table! {
+    departments (id) {
+        id -> Unsigned<Integer>,
+        name -> Varchar,
     }
 }
 
@@ -41,6 +150,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    enrolment_requests (course, student) {
+        course -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        created -> Datetime,
+    }
+}
+
+diesel::table! {
+    exam_periods (id) {
+        id -> Unsigned<Integer>,
+        name -> Varchar,
+        registration_opens -> Datetime,
+        registration_closes -> Datetime,
+    }
+}
+
+diesel::table! {
+    exam_registrations (exam_period, course, student) {
+        exam_period -> Unsigned<Integer>,
+        course -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        created -> Datetime,
+    }
+}
+
 diesel::table! {
     generations (id) {
         id -> Unsigned<Integer>,
@@ -61,6 +196,21 @@ diesel::table! {
         student -> Unsigned<Integer>,
         grade_major -> Unsigned<Tinyint>,
         grade_minor -> Unsigned<Tinyint>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    grade_assignments_progress_revisions (assignment, student, revision) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        revision -> Unsigned<Integer>,
+        created -> Nullable<Datetime>,
+        grade_major -> Unsigned<Tinyint>,
+        grade_minor -> Unsigned<Tinyint>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
     }
 }
 
@@ -74,6 +224,46 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    indicies_revisions (student, revision) {
+        student -> Unsigned<Integer>,
+        revision -> Unsigned<Integer>,
+        created -> Nullable<Datetime>,
+        program -> Unsigned<Integer>,
+        generation -> Unsigned<Integer>,
+        number -> Unsigned<Integer>,
+    }
+}
+
+diesel::table! {
+    pass_fail_assignments (id) {
+        id -> Unsigned<Integer>,
+        assignment -> Unsigned<Integer>,
+    }
+}
+
+diesel::table! {
+    pass_fail_assignments_progress (assignment, student) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        passed -> Bool,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    pass_fail_assignments_progress_revisions (assignment, student, revision) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        revision -> Unsigned<Integer>,
+        created -> Nullable<Datetime>,
+        passed -> Bool,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
 diesel::table! {
     point_assignments (id) {
         id -> Unsigned<Integer>,
@@ -87,6 +277,28 @@ diesel::table! {
         assignment -> Unsigned<Integer>,
         student -> Unsigned<Integer>,
         points -> Unsigned<Integer>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    point_assignments_progress_revisions (assignment, student, revision) {
+        assignment -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        revision -> Unsigned<Integer>,
+        created -> Nullable<Datetime>,
+        points -> Unsigned<Integer>,
+        graded_by -> Nullable<Unsigned<Integer>>,
+        graded_on -> Nullable<Datetime>,
+    }
+}
+
+diesel::table! {
+    program_courses (program, course) {
+        program -> Unsigned<Integer>,
+        course -> Unsigned<Integer>,
+        mandatory -> Bool,
     }
 }
 
@@ -95,6 +307,7 @@ diesel::table! {
         id -> Unsigned<Integer>,
         short_name -> Char,
         full_name -> Varchar,
+        department -> Nullable<Unsigned<Integer>>,
     }
 }
 
@@ -108,6 +321,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    student_status_changes (id) {
+        id -> Unsigned<Integer>,
+        student -> Unsigned<Integer>,
+        status -> Unsigned<Tinyint>,
+        reason -> Text,
+        graduated_on -> Nullable<Date>,
+        thesis_grade -> Nullable<Unsigned<Tinyint>>,
+        changed_by -> Unsigned<Integer>,
+        created -> Datetime,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Unsigned<Integer>,
@@ -119,6 +345,8 @@ diesel::table! {
         last_name -> Nullable<Varchar>,
         last_login_time -> Nullable<Datetime>,
         deleted -> Bool,
+        student_status -> Unsigned<Tinyint>,
+        department -> Nullable<Unsigned<Integer>>,
     }
 }
 
@@ -135,39 +363,128 @@ diesel::table! {
         last_name -> Nullable<Varchar>,
         last_login_time -> Nullable<Datetime>,
         deleted -> Bool,
+        student_status -> Unsigned<Tinyint>,
+        department -> Nullable<Unsigned<Integer>>,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Unsigned<Integer>,
+        webhook -> Unsigned<Integer>,
+        event -> Unsigned<Tinyint>,
+        payload -> Text,
+        next_attempt_on -> Nullable<Datetime>,
+        delivered_on -> Nullable<Datetime>,
+        created_on -> Datetime,
+    }
+}
+
+diesel::table! {
+    webhook_delivery_attempts (id) {
+        id -> Unsigned<Integer>,
+        delivery -> Unsigned<Integer>,
+        response_status -> Nullable<Unsigned<Smallint>>,
+        error -> Nullable<Varchar>,
+        attempted_on -> Datetime,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Unsigned<Integer>,
+        url -> Varchar,
+        secret -> Char,
+        grade_changed -> Bool,
+        enrolment_created -> Bool,
+        user_created -> Bool,
+        course_deleted -> Bool,
+        created_on -> Datetime,
     }
 }
 
+diesel::joinable!(api_tokens -> users (user));
 diesel::joinable!(assignments -> courses (course));
+diesel::joinable!(assignments -> exam_periods (exam_period));
+diesel::joinable!(calendar_tokens -> users (user));
+diesel::joinable!(counter_assignments -> assignments (assignment));
+diesel::joinable!(counter_assignments_progress -> counter_assignments (assignment));
+diesel::joinable!(counter_assignments_progress -> users (student));
+diesel::joinable!(course_self_enrolment -> courses (course));
+diesel::joinable!(course_staff -> courses (course));
+diesel::joinable!(course_staff -> users (user));
+diesel::joinable!(course_url_redirects -> courses (course));
+diesel::joinable!(courses -> departments (department));
 diesel::joinable!(courses -> users (professor));
 diesel::joinable!(courses_revisions -> courses (id));
+diesel::joinable!(enrolment_requests -> courses (course));
+diesel::joinable!(enrolment_requests -> users (student));
 diesel::joinable!(enrolments -> courses (course));
 diesel::joinable!(enrolments -> users (student));
+diesel::joinable!(exam_registrations -> courses (course));
+diesel::joinable!(exam_registrations -> exam_periods (exam_period));
+diesel::joinable!(exam_registrations -> users (student));
 diesel::joinable!(grade_assignments -> assignments (assignment));
 diesel::joinable!(grade_assignments_progress -> grade_assignments (assignment));
 diesel::joinable!(grade_assignments_progress -> users (student));
 diesel::joinable!(indicies -> generations (generation));
 diesel::joinable!(indicies -> programs (program));
 diesel::joinable!(indicies -> users (student));
+diesel::joinable!(indicies_revisions -> generations (generation));
+diesel::joinable!(indicies_revisions -> programs (program));
+diesel::joinable!(indicies_revisions -> users (student));
+diesel::joinable!(pass_fail_assignments -> assignments (assignment));
+diesel::joinable!(pass_fail_assignments_progress -> pass_fail_assignments (assignment));
+diesel::joinable!(pass_fail_assignments_progress -> users (student));
 diesel::joinable!(point_assignments -> assignments (assignment));
 diesel::joinable!(point_assignments_progress -> point_assignments (assignment));
 diesel::joinable!(point_assignments_progress -> users (student));
+diesel::joinable!(program_courses -> courses (course));
+diesel::joinable!(program_courses -> programs (program));
+diesel::joinable!(programs -> departments (department));
 diesel::joinable!(sessions -> users (user));
+diesel::joinable!(users -> departments (department));
 diesel::joinable!(users_revisions -> users (id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook));
+diesel::joinable!(webhook_delivery_attempts -> webhook_deliveries (delivery));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    academic_years,
+    api_tokens,
     assignments,
+    calendar_tokens,
+    counter_assignments,
+    counter_assignments_progress,
+    counter_assignments_progress_revisions,
+    course_self_enrolment,
+    course_staff,
+    course_url_redirects,
     courses,
     courses_revisions,
+    departments,
+    enrolment_requests,
     enrolments,
+    exam_periods,
+    exam_registrations,
     generations,
     grade_assignments,
     grade_assignments_progress,
+    grade_assignments_progress_revisions,
     indicies,
+    indicies_revisions,
+    pass_fail_assignments,
+    pass_fail_assignments_progress,
+    pass_fail_assignments_progress_revisions,
     point_assignments,
     point_assignments_progress,
+    point_assignments_progress_revisions,
+    program_courses,
     programs,
     sessions,
+    student_status_changes,
     users,
     users_revisions,
+    webhook_deliveries,
+    webhook_delivery_attempts,
+    webhooks,
 );
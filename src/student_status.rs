@@ -0,0 +1,189 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    prelude::*,
+    serialize::ToSql,
+    sql_types::{TinyInt, Unsigned},
+    AsExpression, FromSqlRow,
+};
+use rocket::form::FromFormField;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    database::Connection,
+    error::Error,
+    schema::{student_status_changes, users},
+    user::UserId,
+};
+
+/// Where a student is in their studies. Students who are not active keep
+/// their account and transcript, but are left out of enrolment.
+#[repr(u8)]
+#[derive(
+    FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy, ToSchema,
+)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum StudentStatus {
+    Active = 0,
+    OnHold = 1,
+    Graduated = 2,
+    Expelled = 3,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for StudentStatus
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid StudentStatus value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for StudentStatus
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::Active => 0.to_sql(out),
+            Self::OnHold => 1.to_sql(out),
+            Self::Graduated => 2.to_sql(out),
+            Self::Expelled => 3.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for StudentStatus {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(StudentStatus::Active),
+            1 => Ok(StudentStatus::OnHold),
+            2 => Ok(StudentStatus::Graduated),
+            3 => Ok(StudentStatus::Expelled),
+            _ => Err(Error::InvalidStudentStatusValue),
+        }
+    }
+}
+
+impl StudentStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Active => "Активан",
+            Self::OnHold => "Мирује",
+            Self::Graduated => "Дипломирао",
+            Self::Expelled => "Исписан",
+        }
+    }
+
+    /// Graduating and expelling end the studies, only an active student or
+    /// one on hold can get there. Going back to active is always allowed,
+    /// so that a mistake can be corrected.
+    pub fn can_change_to(self, status: StudentStatus) -> bool {
+        self != status
+            && (status == StudentStatus::Active
+                || matches!(self, StudentStatus::Active | StudentStatus::OnHold))
+    }
+}
+
+/// Details of a graduation, which every graduated student has
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct Graduation {
+    pub graduated_on: NaiveDate,
+    pub thesis_grade: u8,
+}
+
+#[derive(Serialize, Debug, Queryable)]
+pub struct StudentStatusChange {
+    pub id: u32,
+    pub status: StudentStatus,
+    pub reason: String,
+    pub graduated_on: Option<NaiveDate>,
+    pub thesis_grade: Option<u8>,
+    pub created: NaiveDateTime,
+    pub changed_by_email: String,
+}
+
+impl StudentStatusChange {
+    /// Sets the student's status and records why. The caller makes sure
+    /// the change is allowed.
+    pub fn create(
+        connection: &mut Connection,
+        student: UserId,
+        status: StudentStatus,
+        reason: &str,
+        graduation: Option<Graduation>,
+        changed_by: UserId,
+    ) -> Result<(), Error> {
+        diesel::update(users::table.filter(users::id.eq(student)))
+            .set(users::student_status.eq(status))
+            .execute(connection)?;
+
+        diesel::insert_into(student_status_changes::table)
+            .values((
+                student_status_changes::student.eq(student),
+                student_status_changes::status.eq(status),
+                student_status_changes::reason.eq(reason),
+                student_status_changes::graduated_on.eq(graduation.map(|g| g.graduated_on)),
+                student_status_changes::thesis_grade.eq(graduation.map(|g| g.thesis_grade)),
+                student_status_changes::changed_by.eq(changed_by),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    /// Newest first
+    pub fn get_all(connection: &mut Connection, student: UserId) -> Result<Vec<Self>, Error> {
+        student_status_changes::table
+            .inner_join(users::table.on(users::id.eq(student_status_changes::changed_by)))
+            .filter(student_status_changes::student.eq(student))
+            .order(student_status_changes::id.desc())
+            .select((
+                student_status_changes::id,
+                student_status_changes::status,
+                student_status_changes::reason,
+                student_status_changes::graduated_on,
+                student_status_changes::thesis_grade,
+                student_status_changes::created,
+                users::email,
+            ))
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    /// The graduation of a graduated student, from the change that made
+    /// them one
+    pub fn get_graduation(
+        connection: &mut Connection,
+        student: UserId,
+    ) -> Result<Option<Graduation>, Error> {
+        let graduation = student_status_changes::table
+            .inner_join(users::table.on(users::id.eq(student_status_changes::student)))
+            .filter(student_status_changes::student.eq(student))
+            .filter(student_status_changes::status.eq(StudentStatus::Graduated))
+            .filter(users::student_status.eq(StudentStatus::Graduated))
+            .order(student_status_changes::id.desc())
+            .select((
+                student_status_changes::graduated_on,
+                student_status_changes::thesis_grade,
+            ))
+            .first::<(Option<NaiveDate>, Option<u8>)>(connection)
+            .optional()
+            .map_err(Error::from)?;
+
+        Ok(match graduation {
+            Some((Some(graduated_on), Some(thesis_grade))) => Some(Graduation {
+                graduated_on,
+                thesis_grade,
+            }),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,349 @@
+use serde::Serialize;
+
+use crate::{
+    assignment::{GradeAssignmentGrade, GradedAssignment, GradedAssignments, GRADE_MAJOR_PASSING},
+    course::{Course, Courses},
+    database::Connection,
+    error::Error,
+    localization::Script,
+    student_status::{Graduation, StudentStatus, StudentStatusChange},
+    user::{UserId, UserWithIndex},
+};
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const LINE_HEIGHT: u32 = 16;
+const ECTS_COLUMN: u32 = 420;
+const GRADE_COLUMN: u32 = 490;
+const COURSE_NAME_LIMIT: usize = 60;
+
+#[derive(Serialize, Debug)]
+pub struct TranscriptCourse {
+    #[serde(flatten)]
+    pub course: Course,
+    pub grade: Option<GradeAssignmentGrade>,
+    pub passed: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TranscriptYear {
+    pub year: u32,
+    pub courses: Vec<TranscriptCourse>,
+}
+
+/// Every course the student is enrolled in, grouped by year, with the
+/// final grade of each
+#[derive(Serialize, Debug)]
+pub struct Transcript {
+    pub student: UserWithIndex,
+    pub years: Vec<TranscriptYear>,
+    pub earned_ects: u32,
+    /// Average of the passed courses' grades, with two decimals
+    pub gpa: Option<String>,
+    pub graduation: Option<Graduation>,
+}
+
+impl Transcript {
+    pub fn get(connection: &mut Connection, student: UserId) -> Result<Self, Error> {
+        let student = UserWithIndex::get(connection, student)?;
+
+        let mut courses = Courses::get_all_enrolled(connection, student.user.id)?.0;
+        courses.sort_by(|a, b| a.year.cmp(&b.year).then_with(|| a.name.cmp(&b.name)));
+
+        let mut years: Vec<TranscriptYear> = Vec::new();
+        for course in courses {
+            let assignments = GradedAssignments::get(connection, course.id, student.user.id)?.0;
+            let grade = final_grade(assignments);
+            let transcript_course = TranscriptCourse {
+                passed: grade
+                    .as_ref()
+                    .is_some_and(|grade| grade.major >= GRADE_MAJOR_PASSING),
+                grade,
+                course,
+            };
+
+            match years.last_mut() {
+                Some(year) if year.year == transcript_course.course.year => {
+                    year.courses.push(transcript_course);
+                }
+                _ => years.push(TranscriptYear {
+                    year: transcript_course.course.year,
+                    courses: vec![transcript_course],
+                }),
+            }
+        }
+
+        let passed: Vec<_> = years
+            .iter()
+            .flat_map(|year| &year.courses)
+            .filter(|course| course.passed)
+            .collect();
+
+        let earned_ects = passed.iter().map(|course| course.course.ects).sum();
+        let grades: Vec<u32> = passed
+            .iter()
+            .filter_map(|course| course.grade.as_ref())
+            .map(|grade| u32::from(grade.major))
+            .collect();
+        let gpa = (!grades.is_empty()).then(|| {
+            let count = u32::try_from(grades.len()).unwrap_or(u32::MAX);
+            format!(
+                "{:.2}",
+                f64::from(grades.iter().sum::<u32>()) / f64::from(count)
+            )
+        });
+
+        let graduation = StudentStatusChange::get_graduation(connection, student.user.id)?;
+
+        Ok(Self {
+            student,
+            years,
+            earned_ects,
+            gpa,
+            graduation,
+        })
+    }
+
+    /// Lays the transcript out on A4 pages. The standard PDF fonts have no
+    /// Cyrillic glyphs, so everything is written in Latin.
+    pub fn pdf(&self) -> Vec<u8> {
+        let script = Script::Latin;
+        let mut document = Document::new();
+
+        let user = &self.student.user;
+        let name = format!(
+            "{} {}",
+            user.first_name.as_deref().unwrap_or_default(),
+            user.last_name.as_deref().unwrap_or_default()
+        );
+
+        document.text(MARGIN, 18, true, &script.localize("Препис оцена"));
+        document.line(LINE_HEIGHT * 2);
+        document.text(MARGIN, 11, false, &script.localize(name.trim()));
+        document.line(LINE_HEIGHT);
+        document.text(MARGIN, 11, false, &user.email);
+        if let Some(index) = &self.student.index {
+            document.line(LINE_HEIGHT);
+            document.text(
+                MARGIN,
+                11,
+                false,
+                &format!(
+                    "{} - {}",
+                    index.text(),
+                    script.localize(&index.program.full_name)
+                ),
+            );
+        }
+        if user.student_status != StudentStatus::Active {
+            document.line(LINE_HEIGHT);
+            document.text(
+                MARGIN,
+                11,
+                false,
+                &format!(
+                    "{}: {}",
+                    script.localize("Статус"),
+                    script.localize(user.student_status.name())
+                ),
+            );
+        }
+        if let Some(graduation) = &self.graduation {
+            document.line(LINE_HEIGHT);
+            document.text(
+                MARGIN,
+                11,
+                false,
+                &format!(
+                    "{}: {}, {}: {}",
+                    script.localize("Датум дипломирања"),
+                    graduation.graduated_on.format("%d.%m.%Y."),
+                    script.localize("оцена завршног рада"),
+                    graduation.thesis_grade
+                ),
+            );
+        }
+
+        for year in &self.years {
+            document.line(LINE_HEIGHT * 2);
+            document.text(MARGIN, 12, true, &year.year.to_string());
+            document.text(ECTS_COLUMN, 12, true, &script.localize("ЕСПБ"));
+            document.text(GRADE_COLUMN, 12, true, &script.localize("Оцена"));
+
+            for course in &year.courses {
+                document.line(LINE_HEIGHT);
+
+                let name: String = script
+                    .localize(&course.course.name)
+                    .chars()
+                    .take(COURSE_NAME_LIMIT)
+                    .collect();
+                document.text(MARGIN, 11, false, &name);
+                document.text(ECTS_COLUMN, 11, false, &course.course.ects.to_string());
+                let grade = course
+                    .grade
+                    .as_ref()
+                    .map_or_else(|| "-".to_owned(), |grade| grade.major.to_string());
+                document.text(GRADE_COLUMN, 11, false, &grade);
+            }
+        }
+
+        document.line(LINE_HEIGHT * 2);
+        document.text(
+            MARGIN,
+            12,
+            true,
+            &format!(
+                "{}: {}",
+                script.localize("Остварено ЕСПБ"),
+                self.earned_ects
+            ),
+        );
+        document.line(LINE_HEIGHT);
+        document.text(
+            MARGIN,
+            12,
+            true,
+            &format!(
+                "{}: {}",
+                script.localize("Просечна оцена"),
+                self.gpa.as_deref().unwrap_or("-")
+            ),
+        );
+
+        document.finish()
+    }
+}
+
+/// The grade of the last published grade assignment the student was graded
+/// in, by position in the course
+fn final_grade(assignments: Vec<GradedAssignment>) -> Option<GradeAssignmentGrade> {
+    assignments
+        .into_iter()
+        .rev()
+        .find_map(|assignment| match assignment {
+            GradedAssignment::Grade(graded) => graded.grade,
+            _ => None,
+        })
+}
+
+/// A PDF document written with the built in Helvetica fonts, one content
+/// stream per page
+struct Document {
+    pages: Vec<Vec<u8>>,
+    y: u32,
+}
+
+impl Document {
+    fn new() -> Self {
+        Self {
+            pages: vec![Vec::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Moves down by the given height, starting a new page when the current
+    /// one is full
+    fn line(&mut self, height: u32) {
+        if self.y < MARGIN + height {
+            self.pages.push(Vec::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        } else {
+            self.y -= height;
+        }
+    }
+
+    fn text(&mut self, x: u32, size: u32, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        let page = self.pages.last_mut().expect("a document has a page");
+
+        page.extend_from_slice(format!("BT /{font} {size} Tf {x} {} Td (", self.y).as_bytes());
+        page.extend(encode_text(text));
+        page.extend_from_slice(b") Tj ET\n");
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let page_count = self.pages.len();
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+                (0..page_count)
+                    .map(|page| format!("{} 0 R", 5 + page * 2))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_vec(),
+        ];
+
+        for (page, content) in self.pages.into_iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    6 + page * 2
+                )
+                .into_bytes(),
+            );
+
+            let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            stream.extend(content);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut pdf = b"%PDF-1.4\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (number, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", number + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        pdf.extend_from_slice(format!("xref\n0 {}\n", objects.len() + 1).as_bytes());
+        pdf.extend_from_slice(b"0000000000 65535 f \n");
+        for offset in offsets {
+            pdf.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+        }
+        pdf.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+                objects.len() + 1
+            )
+            .as_bytes(),
+        );
+
+        pdf
+    }
+}
+
+/// Converts Latin text to `WinAnsiEncoding`, which has š and ž but not the
+/// other Serbian letters, so those lose their diacritics
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => encoded.extend_from_slice(&[b'\\', c as u8]),
+            ' '..='~' => encoded.push(c as u8),
+            'Š' => encoded.push(0x8A),
+            'š' => encoded.push(0x9A),
+            'Ž' => encoded.push(0x8E),
+            'ž' => encoded.push(0x9E),
+            'Č' | 'Ć' => encoded.push(b'C'),
+            'č' | 'ć' => encoded.push(b'c'),
+            'Đ' => encoded.extend_from_slice(b"Dj"),
+            'đ' => encoded.extend_from_slice(b"dj"),
+            _ => encoded.push(b'?'),
+        }
+    }
+
+    encoded
+}
@@ -18,23 +18,33 @@ use rocket::{
     Request,
 };
 use serde::Serialize;
+use utoipa::ToSchema;
 
 use crate::{
+    api_token::ApiToken,
     assignment::GradeAssignmentGrade,
+    course::CourseId,
     database::{Connection, Database, SortDirection},
     error::Error,
+    exam::ExamPeriodId,
     index::{Generation, Index, IndexNumber, Program},
     models::Session,
     schema::{
-        assignments, enrolments, generations, grade_assignments, grade_assignments_progress,
-        indicies, point_assignments, point_assignments_progress, programs, users,
+        assignments, counter_assignments, counter_assignments_progress, enrolments,
+        exam_registrations, generations, grade_assignments, grade_assignments_progress, indicies,
+        pass_fail_assignments, pass_fail_assignments_progress, point_assignments,
+        point_assignments_progress, programs, users,
     },
+    student_status::StudentStatus,
+    webhook::{self, WebhookEvent},
 };
 
 pub const SESSION_KEY_COOKIE_NAME: &'static str = "session_key";
 
 #[repr(u8)]
-#[derive(FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy)]
+#[derive(
+    FromFormField, AsExpression, FromSqlRow, Serialize, PartialEq, Debug, Clone, Copy, ToSchema,
+)]
 #[diesel(sql_type = Unsigned<TinyInt>)]
 pub enum AccountType {
     Student = 0,
@@ -82,10 +92,14 @@ impl TryFrom<u8> for AccountType {
 
 pub type UserId = u32;
 
-#[derive(Clone, Debug, Queryable, Serialize, Insertable, Selectable, AsChangeset, Identifiable)]
+#[derive(
+    Clone, Debug, Queryable, Serialize, Insertable, Selectable, AsChangeset, Identifiable, ToSchema,
+)]
 #[diesel(table_name = users)]
 pub struct User {
+    #[schema(value_type = u32)]
     pub id: UserId,
+    #[serde(skip_serializing)]
     pub password: String,
     pub email: String,
     pub account_type: AccountType,
@@ -94,20 +108,34 @@ pub struct User {
     pub last_name: Option<String>,
     pub last_login_time: Option<NaiveDateTime>,
     pub deleted: bool,
+    pub student_status: StudentStatus,
+    /// The department of the user, for administrators the only one they
+    /// manage. Administrators without one manage every department.
+    pub department: Option<u32>,
 }
 
-#[derive(Serialize, Debug, Clone, Selectable, Queryable)]
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct IndexGenerationProgram {
     #[diesel(embed)]
-    index: Index,
+    pub index: Index,
     #[diesel(embed)]
-    generation: Generation,
+    pub generation: Generation,
     #[diesel(embed)]
-    program: Program,
+    pub program: Program,
 }
 
-#[derive(Serialize, Debug, Clone, Selectable, Queryable)]
+impl IndexGenerationProgram {
+    /// The index the way it is written, such as `RA 12/2022`
+    pub fn text(&self) -> String {
+        format!(
+            "{} {}/{}",
+            self.program.short_name, self.index.number, self.generation.year
+        )
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct UserWithIndex {
     #[serde(flatten)]
@@ -118,6 +146,39 @@ pub struct UserWithIndex {
     pub index: Option<IndexGenerationProgram>,
 }
 
+impl UserWithIndex {
+    pub fn get(connection: &mut Connection, id: UserId) -> Result<Self, Error> {
+        Users::query_new(false)
+            .filter(users::id.eq(id))
+            .select(UserWithIndex::as_select())
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Same as `get`, but finds soft-deleted users too
+    pub fn get_including_deleted(connection: &mut Connection, id: UserId) -> Result<Self, Error> {
+        users::table
+            .left_join(
+                indicies::table
+                    .inner_join(generations::table)
+                    .inner_join(programs::table),
+            )
+            .filter(users::id.eq(id))
+            .select(UserWithIndex::as_select())
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Same as `RetrievalFilters::filter_department`
+    pub fn in_department(&self, department: u32) -> bool {
+        self.user.department == Some(department)
+            || self
+                .index
+                .as_ref()
+                .is_some_and(|index| index.program.department == Some(department))
+    }
+}
+
 impl User {
     pub fn builder<'a>(email: String, password: String) -> UserBuilder {
         UserBuilder {
@@ -128,6 +189,7 @@ impl User {
             first_name: None,
             last_name: None,
             last_login_time: None,
+            department: None,
         }
     }
 
@@ -140,6 +202,28 @@ impl User {
             .map_err(Error::from)
     }
 
+    pub fn get_deleted_by_id(connection: &mut Connection, id: UserId) -> Result<Self, Error> {
+        users::table
+            .filter(users::id.eq(id))
+            .filter(users::deleted.eq(true))
+            .first::<User>(connection)
+            .map_err(Error::from)
+    }
+
+    /// Finds the soft-deleted user that still holds an email address, which
+    /// keeps it from being used for a new account
+    pub fn get_deleted_by_email(
+        connection: &mut Connection,
+        email: &str,
+    ) -> Result<Option<Self>, Error> {
+        users::table
+            .filter(users::email.eq(email))
+            .filter(users::deleted.eq(true))
+            .first::<User>(connection)
+            .optional()
+            .map_err(Error::from)
+    }
+
     pub fn get_by_email<'a>(
         connection: &mut diesel::MysqlConnection,
         email: &'a str,
@@ -208,6 +292,18 @@ impl User {
             .map_err(Error::from)
     }
 
+    pub fn update_department(
+        &self,
+        connection: &mut Connection,
+        department: Option<u32>,
+    ) -> Result<(), Error> {
+        diesel::update(self)
+            .set(users::department.eq(department))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
     pub fn account_type(&self) -> AccountType {
         self.account_type
     }
@@ -220,6 +316,18 @@ impl User {
         self.account_type == AccountType::Administrator
     }
 
+    /// Whether the user is an administrator of the department, global
+    /// administrators administer every department and data outside of them
+    pub fn administers(&self, department: Option<u32>) -> bool {
+        self.is_administrator() && (self.department.is_none() || self.department == department)
+    }
+
+    /// Students on hold, graduated or expelled can't enrol in courses or
+    /// register for exams
+    pub fn is_active_student(&self) -> bool {
+        self.account_type == AccountType::Student && self.student_status == StudentStatus::Active
+    }
+
     pub fn id(&self) -> u32 {
         self.id
     }
@@ -239,15 +347,17 @@ pub struct NewUser<'a> {
     pub first_name: Option<&'a str>,
     pub last_name: Option<&'a str>,
     pub last_login_time: Option<NaiveDateTime>,
+    pub department: Option<u32>,
 }
 
 impl NewUser<'_> {
     pub fn create(&self, connection: &mut Connection) -> Result<(), Error> {
         diesel::insert_into(users::table)
             .values(self)
-            .execute(connection)
-            .map(|_| ())
-            .map_err(Error::from)
+            .execute(connection)?;
+
+        let user = User::get_by_email(connection, self.email)?;
+        webhook::trigger(connection, WebhookEvent::UserCreated, &user)
     }
 }
 
@@ -259,6 +369,7 @@ pub struct UserBuilder {
     first_name: Option<String>,
     last_name: Option<String>,
     last_login_time: Option<NaiveDateTime>,
+    department: Option<u32>,
 }
 
 impl UserBuilder {
@@ -277,6 +388,11 @@ impl UserBuilder {
         self
     }
 
+    pub fn with_department(mut self, department: Option<u32>) -> Self {
+        self.department = department;
+        self
+    }
+
     pub fn build<'a>(&'a self) -> NewUser<'a> {
         NewUser {
             password: &self.password,
@@ -286,6 +402,7 @@ impl UserBuilder {
             first_name: self.first_name.as_deref(),
             last_name: self.last_name.as_deref(),
             last_login_time: self.last_login_time,
+            department: self.department,
         }
     }
 }
@@ -299,6 +416,14 @@ pub struct RetrievalFilters {
     pub filter_program: Option<String>,
     pub filter_generation: Option<u32>,
     pub filter_index_number: Option<IndexNumber>,
+    pub filter_student_status: Option<StudentStatus>,
+    /// Users of the department, or students with an index in one of its
+    /// programs
+    pub filter_department: Option<u32>,
+    /// Students enrolled in the course
+    pub filter_enrolled_in: Option<CourseId>,
+    /// Lists only soft-deleted users instead of only active ones
+    pub filter_deleted: bool,
 }
 
 impl RetrievalFilters {
@@ -311,8 +436,20 @@ impl RetrievalFilters {
             filter_program: None,
             filter_generation: None,
             filter_index_number: None,
+            filter_student_status: None,
+            filter_department: None,
+            filter_enrolled_in: None,
+            filter_deleted: false,
         }
     }
+
+    /// Leaves out students who are not active unless the filters ask for
+    /// another status, as they shouldn't be enrolled by accident
+    pub fn active_students_by_default(mut self) -> Self {
+        self.filter_student_status
+            .get_or_insert(StudentStatus::Active);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -368,14 +505,39 @@ type UsersQuery = Filter<
 type BoxedUsersQuery<'a> = IntoBoxed<'a, UsersQuery, Mysql>;
 
 impl Users {
-    pub fn query_new<'a>() -> BoxedUsersQuery<'a> {
+    /// Everyone who can be put in charge of a course
+    pub fn get_professors(connection: &mut Connection) -> Result<Self, Error> {
+        users::table
+            .filter(users::deleted.eq(false))
+            .filter(users::account_type.ne(AccountType::Student))
+            .order(users::email.asc())
+            .load::<User>(connection)
+            .map(Users)
+            .map_err(Error::from)
+    }
+
+    /// Every student matching the filters, regardless of pagination
+    pub fn get_student_ids(
+        connection: &mut Connection,
+        filters: RetrievalFilters,
+    ) -> Result<Vec<UserId>, Error> {
+        let query = Users::query_new(false);
+        let query = Users::query_apply_filters(query, filters);
+        query
+            .filter(users::account_type.eq(AccountType::Student))
+            .select(users::id)
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn query_new<'a>(deleted: bool) -> BoxedUsersQuery<'a> {
         users::table
             .left_join(
                 indicies::table
                     .inner_join(generations::table)
                     .inner_join(programs::table),
             )
-            .filter(users::deleted.eq(false))
+            .filter(users::deleted.eq(deleted))
             .into_boxed()
     }
 
@@ -410,6 +572,28 @@ impl Users {
         if let Some(filter) = filters.filter_index_number {
             query = query.filter(indicies::number.eq(filter))
         }
+
+        if let Some(filter) = filters.filter_student_status {
+            query = query.filter(users::student_status.eq(filter))
+        }
+
+        if let Some(filter) = filters.filter_department {
+            query = query.filter(
+                users::department
+                    .eq(filter)
+                    .or(programs::department.eq(filter)),
+            )
+        }
+
+        if let Some(filter) = filters.filter_enrolled_in {
+            query = query.filter(
+                users::id.eq_any(
+                    enrolments::table
+                        .filter(enrolments::course.eq(filter))
+                        .select(enrolments::student),
+                ),
+            )
+        }
         query
     }
 
@@ -468,7 +652,27 @@ impl Users {
     ) -> BoxedUsersQuery<'a> {
         query
             .limit(max_per_page as i64)
-            .offset((max_per_page * page) as i64)
+            .offset(i64::from(max_per_page).saturating_mul(i64::from(page)))
+    }
+
+    /// Leaves only the students registered for the exam when the assignment
+    /// is bound to an exam period
+    pub fn query_apply_exam_registrations<'a>(
+        query: BoxedUsersQuery<'a>,
+        (course, exam_period): (CourseId, Option<ExamPeriodId>),
+    ) -> BoxedUsersQuery<'a> {
+        let Some(exam_period) = exam_period else {
+            return query;
+        };
+
+        query.filter(
+            users::id.eq_any(
+                exam_registrations::table
+                    .filter(exam_registrations::exam_period.eq(exam_period))
+                    .filter(exam_registrations::course.eq(course))
+                    .select(exam_registrations::student),
+            ),
+        )
     }
 }
 
@@ -479,7 +683,7 @@ impl UsersWithIndex {
         connection: &mut Connection,
         options: UsersRetrievalOptions,
     ) -> Result<UsersWithIndex, Error> {
-        let query = Users::query_new();
+        let query = Users::query_new(options.filters.filter_deleted);
         let query = Users::query_apply_filters(query, options.filters);
         let query = Users::query_apply_sorts(query, options.sorts);
         let query = Users::query_apply_pagination(query, options.max_per_page, options.page);
@@ -490,18 +694,30 @@ impl UsersWithIndex {
             .map_err(Error::from)
     }
 
+    /// Progress lists count only the students registered for the exam of
+    /// an exam-bound assignment, the same as they list
     pub fn get_number_of_pages(
         connection: &mut Connection,
         filters: RetrievalFilters,
+        exam: Option<(CourseId, Option<ExamPeriodId>)>,
         max_per_page: u32,
     ) -> Result<u32, Error> {
-        let query = Users::query_new();
+        let query = Users::query_new(filters.filter_deleted);
         let query = Users::query_apply_filters(query, filters);
+        let query = match exam {
+            Some(exam) => Users::query_apply_exam_registrations(query, exam),
+            None => query,
+        };
         query
             .count()
             .get_result(connection)
             .map_err(Error::from)
-            .map(|c: i64| (c as u32) / max_per_page + 1)
+            .map(|c: i64| {
+                u32::try_from(c)
+                    .unwrap_or(u32::MAX)
+                    .div_ceil(max_per_page)
+                    .max(1)
+            })
     }
 }
 
@@ -527,7 +743,7 @@ impl UsersWithIndexAndEnrolment {
         options: UsersRetrievalOptions,
         course: u32,
     ) -> Result<Self, Error> {
-        let query = Users::query_new();
+        let query = Users::query_new(options.filters.filter_deleted);
 
         let query = Users::query_apply_filters(query, options.filters);
         let query = Users::query_apply_sorts(query, options.sorts);
@@ -546,7 +762,7 @@ impl UsersWithIndexAndEnrolment {
     }
 }
 
-#[derive(Serialize, Debug, Clone, Selectable, Queryable)]
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct UserWithIndexAndPointProgress {
     #[serde(flatten)]
@@ -563,14 +779,31 @@ pub struct UserWithIndexAndPointProgress {
 pub struct UsersWithIndexAndPointProgress(pub Vec<UserWithIndexAndPointProgress>);
 
 impl UsersWithIndexAndPointProgress {
+    /// The course and the exam period of the assignment, which the list
+    /// is limited to the registered students of
+    pub fn exam(
+        connection: &mut Connection,
+        point_assignment: u32,
+    ) -> Result<(CourseId, Option<ExamPeriodId>), Error> {
+        point_assignments::table
+            .inner_join(assignments::table)
+            .filter(point_assignments::id.eq(point_assignment))
+            .select((assignments::course, assignments::exam_period))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
     pub fn get(
         connection: &mut Connection,
         options: UsersRetrievalOptions,
         point_assignment: u32,
     ) -> Result<Self, Error> {
-        let query = Users::query_new();
+        let exam = Self::exam(connection, point_assignment)?;
+
+        let query = Users::query_new(options.filters.filter_deleted);
 
         let query = Users::query_apply_filters(query, options.filters);
+        let query = Users::query_apply_exam_registrations(query, exam);
         let query = Users::query_apply_sorts(query, options.sorts);
         let query = Users::query_apply_pagination(query, options.max_per_page, options.page);
 
@@ -597,7 +830,7 @@ impl UsersWithIndexAndPointProgress {
     }
 }
 
-#[derive(Serialize, Debug, Clone, Selectable, Queryable)]
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
 #[diesel(check_for_backend(diesel::mysql::Mysql))]
 pub struct UserWithIndexAndGradeProgress {
     #[serde(flatten)]
@@ -613,14 +846,31 @@ pub struct UserWithIndexAndGradeProgress {
 pub struct UsersWithIndexAndGradeProgress(pub Vec<UserWithIndexAndGradeProgress>);
 
 impl UsersWithIndexAndGradeProgress {
+    /// The course and the exam period of the assignment, which the list
+    /// is limited to the registered students of
+    pub fn exam(
+        connection: &mut Connection,
+        grade_assignment: u32,
+    ) -> Result<(CourseId, Option<ExamPeriodId>), Error> {
+        grade_assignments::table
+            .inner_join(assignments::table)
+            .filter(grade_assignments::id.eq(grade_assignment))
+            .select((assignments::course, assignments::exam_period))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
     pub fn get(
         connection: &mut Connection,
         options: UsersRetrievalOptions,
         grade_assignment: u32,
     ) -> Result<Self, Error> {
-        let query = Users::query_new();
+        let exam = Self::exam(connection, grade_assignment)?;
+
+        let query = Users::query_new(options.filters.filter_deleted);
 
         let query = Users::query_apply_filters(query, options.filters);
+        let query = Users::query_apply_exam_registrations(query, exam);
         let query = Users::query_apply_sorts(query, options.sorts);
         let query = Users::query_apply_pagination(query, options.max_per_page, options.page);
 
@@ -647,6 +897,142 @@ impl UsersWithIndexAndGradeProgress {
     }
 }
 
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct UserWithIndexAndPassFailProgress {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub user: User,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub index: Option<IndexGenerationProgram>,
+    #[diesel(select_expression = pass_fail_assignments_progress::passed.nullable())]
+    #[diesel(select_expression_type = diesel::dsl::Nullable<pass_fail_assignments_progress::passed>)]
+    pub passed: Option<bool>,
+}
+
+pub struct UsersWithIndexAndPassFailProgress(pub Vec<UserWithIndexAndPassFailProgress>);
+
+impl UsersWithIndexAndPassFailProgress {
+    /// The course and the exam period of the assignment, which the list
+    /// is limited to the registered students of
+    pub fn exam(
+        connection: &mut Connection,
+        pass_fail_assignment: u32,
+    ) -> Result<(CourseId, Option<ExamPeriodId>), Error> {
+        pass_fail_assignments::table
+            .inner_join(assignments::table)
+            .filter(pass_fail_assignments::id.eq(pass_fail_assignment))
+            .select((assignments::course, assignments::exam_period))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get(
+        connection: &mut Connection,
+        options: UsersRetrievalOptions,
+        pass_fail_assignment: u32,
+    ) -> Result<Self, Error> {
+        let exam = Self::exam(connection, pass_fail_assignment)?;
+
+        let query = Users::query_new(options.filters.filter_deleted);
+
+        let query = Users::query_apply_filters(query, options.filters);
+        let query = Users::query_apply_exam_registrations(query, exam);
+        let query = Users::query_apply_sorts(query, options.sorts);
+        let query = Users::query_apply_pagination(query, options.max_per_page, options.page);
+
+        query
+            .inner_join(
+                pass_fail_assignments::table
+                    .inner_join(
+                        assignments::table.on(pass_fail_assignments::assignment
+                            .eq(assignments::id)
+                            .and(pass_fail_assignments::id.eq(pass_fail_assignment))),
+                    )
+                    .inner_join(enrolments::table.on(enrolments::course.eq(assignments::course)))
+                    .on(enrolments::student.eq(users::id)),
+            )
+            .left_join(
+                pass_fail_assignments_progress::table.on(users::id
+                    .eq(pass_fail_assignments_progress::student)
+                    .and(pass_fail_assignments_progress::assignment.eq(pass_fail_assignment))),
+            )
+            .select(UserWithIndexAndPassFailProgress::as_select())
+            .load::<UserWithIndexAndPassFailProgress>(connection)
+            .map_err(Error::from)
+            .map(|u| Self { 0: u })
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Selectable, Queryable, ToSchema)]
+#[diesel(check_for_backend(diesel::mysql::Mysql))]
+pub struct UserWithIndexAndCounterProgress {
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub user: User,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub index: Option<IndexGenerationProgram>,
+    #[diesel(select_expression = counter_assignments_progress::count.nullable())]
+    #[diesel(select_expression_type = diesel::dsl::Nullable<counter_assignments_progress::count>)]
+    pub count: Option<u32>,
+}
+
+pub struct UsersWithIndexAndCounterProgress(pub Vec<UserWithIndexAndCounterProgress>);
+
+impl UsersWithIndexAndCounterProgress {
+    /// The course and the exam period of the assignment, which the list
+    /// is limited to the registered students of
+    pub fn exam(
+        connection: &mut Connection,
+        counter_assignment: u32,
+    ) -> Result<(CourseId, Option<ExamPeriodId>), Error> {
+        counter_assignments::table
+            .inner_join(assignments::table)
+            .filter(counter_assignments::id.eq(counter_assignment))
+            .select((assignments::course, assignments::exam_period))
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get(
+        connection: &mut Connection,
+        options: UsersRetrievalOptions,
+        counter_assignment: u32,
+    ) -> Result<Self, Error> {
+        let exam = Self::exam(connection, counter_assignment)?;
+
+        let query = Users::query_new(options.filters.filter_deleted);
+
+        let query = Users::query_apply_filters(query, options.filters);
+        let query = Users::query_apply_exam_registrations(query, exam);
+        let query = Users::query_apply_sorts(query, options.sorts);
+        let query = Users::query_apply_pagination(query, options.max_per_page, options.page);
+
+        query
+            .inner_join(
+                counter_assignments::table
+                    .inner_join(
+                        assignments::table.on(counter_assignments::assignment
+                            .eq(assignments::id)
+                            .and(counter_assignments::id.eq(counter_assignment))),
+                    )
+                    .inner_join(enrolments::table.on(enrolments::course.eq(assignments::course)))
+                    .on(enrolments::student.eq(users::id)),
+            )
+            .left_join(
+                counter_assignments_progress::table.on(users::id
+                    .eq(counter_assignments_progress::student)
+                    .and(counter_assignments_progress::assignment.eq(counter_assignment))),
+            )
+            .select(UserWithIndexAndCounterProgress::as_select())
+            .load::<UserWithIndexAndCounterProgress>(connection)
+            .map_err(Error::from)
+            .map(|u| Self { 0: u })
+    }
+}
+
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for &'r User {
     type Error = &'r Error;
@@ -654,6 +1040,32 @@ impl<'r> FromRequest<'r> for &'r User {
     async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
         let user_result: &Result<Option<User>, Error> = request
             .local_cache_async(async {
+                if let Some(token) = request
+                    .headers()
+                    .get_one("Authorization")
+                    .and_then(|header| header.strip_prefix("Bearer "))
+                {
+                    // Tokens only sign in to the API, where their scopes are
+                    // checked, the pages keep requiring a session
+                    if !request.uri().path().as_str().starts_with("/api/") {
+                        return Ok(None);
+                    }
+
+                    let token = token.trim().to_owned();
+                    let database = request.guard::<Database>().await.unwrap();
+                    return match database
+                        .run(move |c| ApiToken::authenticate(c, &token))
+                        .await
+                    {
+                        Ok((token, user)) => {
+                            request.local_cache(|| Some(token.scopes.allowed_for(&user)));
+                            Ok(Some(user))
+                        }
+                        Err(Error::DatabaseEntryNotFound) => Ok(None),
+                        Err(e) => Err(e),
+                    };
+                }
+
                 let jar = request.guard::<&CookieJar>().await.unwrap();
                 let session_key = match jar
                     .get_pending(SESSION_KEY_COOKIE_NAME)
@@ -723,8 +1135,36 @@ impl<'r> FromRequest<'r> for Professor<'r> {
     }
 }
 
+/// An administrator of every department, or of only one when their
+/// account has a department
 pub struct Administrator<'r>(pub &'r User);
 
+impl Administrator<'_> {
+    /// `None` for administrators of every department
+    pub fn department(&self) -> Option<u32> {
+        self.0.department
+    }
+
+    pub fn administers(&self, department: Option<u32>) -> bool {
+        self.0.administers(department)
+    }
+
+    /// The department chosen in a form, administrators of one department
+    /// always get their own
+    pub fn assign_department(&self, chosen: Option<u32>) -> Option<u32> {
+        self.department().or(chosen)
+    }
+
+    /// Whether the administrator manages the user, which they do for every
+    /// user of their department
+    pub fn manages_user(&self, user: &UserWithIndex) -> bool {
+        match self.department() {
+            Some(department) => user.in_department(department),
+            None => true,
+        }
+    }
+}
+
 #[rocket::async_trait]
 impl<'r> FromRequest<'r> for Administrator<'r> {
     type Error = &'r Error;
@@ -1,3 +1,5 @@
+use chrono::{NaiveDate, NaiveDateTime};
+
 pub fn string_to_url(string: &str) -> String {
     string
         .to_string()
@@ -9,3 +11,16 @@ pub fn string_to_url(string: &str) -> String {
         })
         .collect()
 }
+
+/// Parses the value of a `datetime-local` input, which browsers send
+/// with or without seconds
+pub fn parse_datetime_local(string: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+}
+
+/// Parses the value of a `date` input
+pub fn parse_date(string: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(string, "%Y-%m-%d").ok()
+}
@@ -0,0 +1,626 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{Local, NaiveDateTime};
+use diesel::{
+    backend::Backend,
+    deserialize::FromSql,
+    prelude::*,
+    serialize::ToSql,
+    sql_types::{TinyInt, Unsigned},
+    AsExpression, FromSqlRow,
+};
+use hmac::{Hmac, Mac};
+use log::{error, warn};
+use rand::{Fill, SeedableRng};
+use rocket::{fairing::AdHoc, serde::json::serde_json, tokio};
+use rocket_sync_db_pools::ConnectionPool;
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::{
+    assignment::GradeAssignmentGrade,
+    course::CourseId,
+    database::{Connection, Database},
+    error::Error,
+    schema::{webhook_deliveries, webhook_delivery_attempts, webhooks},
+    user::UserId,
+};
+
+/// How often the queue is checked for deliveries that are due
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// How long a receiver has to answer before the attempt counts as failed
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Deliveries sent by a single check of the queue
+const DELIVERIES_PER_POLL: i64 = 50;
+const MAX_ATTEMPTS: usize = 6;
+/// Minutes to wait after each failed attempt, the delivery is given up on
+/// after the last one
+const RETRY_DELAYS: [i64; MAX_ATTEMPTS - 1] = [1, 5, 30, 120, 720];
+
+/// Something that happened which outside systems can be told about
+#[repr(u8)]
+#[derive(Serialize, Debug, AsExpression, FromSqlRow, PartialEq, Clone, Copy)]
+#[diesel(sql_type = Unsigned<TinyInt>)]
+pub enum WebhookEvent {
+    #[serde(rename = "grade.changed")]
+    GradeChanged = 0,
+    #[serde(rename = "enrolment.created")]
+    EnrolmentCreated = 1,
+    #[serde(rename = "user.created")]
+    UserCreated = 2,
+    #[serde(rename = "course.deleted")]
+    CourseDeleted = 3,
+}
+
+impl<DB: Backend> FromSql<Unsigned<TinyInt>, DB> for WebhookEvent
+where
+    u8: FromSql<Unsigned<TinyInt>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        Self::try_from(u8::from_sql(bytes)?).map_err(|_| "Invalid WebhookEvent value".into())
+    }
+}
+
+impl<DB: Backend> ToSql<Unsigned<TinyInt>, DB> for WebhookEvent
+where
+    u8: ToSql<Unsigned<TinyInt>, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            Self::GradeChanged => 0.to_sql(out),
+            Self::EnrolmentCreated => 1.to_sql(out),
+            Self::UserCreated => 2.to_sql(out),
+            Self::CourseDeleted => 3.to_sql(out),
+        }
+    }
+}
+
+impl TryFrom<u8> for WebhookEvent {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(WebhookEvent::GradeChanged),
+            1 => Ok(WebhookEvent::EnrolmentCreated),
+            2 => Ok(WebhookEvent::UserCreated),
+            3 => Ok(WebhookEvent::CourseDeleted),
+            _ => Err(Error::InvalidWebhookEventValue),
+        }
+    }
+}
+
+impl WebhookEvent {
+    /// The name in the payload and the `X-EStudent-Event` header
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::GradeChanged => "grade.changed",
+            Self::EnrolmentCreated => "enrolment.created",
+            Self::UserCreated => "user.created",
+            Self::CourseDeleted => "course.deleted",
+        }
+    }
+}
+
+/// The `grade.changed` data, only the field of the assignment's type is set
+#[derive(Serialize, Debug, Default)]
+pub struct GradeChanged {
+    pub course: CourseId,
+    pub assignment: u32,
+    pub student: UserId,
+    /// Unset for grades given before graders were recorded
+    pub graded_by: Option<UserId>,
+    pub grade: Option<GradeAssignmentGrade>,
+    pub points: Option<u32>,
+    pub passed: Option<bool>,
+    pub count: Option<u32>,
+}
+
+/// The events a webhook is sent
+#[derive(Serialize, Debug, Clone, Copy, Queryable, Selectable)]
+#[diesel(table_name = webhooks)]
+pub struct WebhookEvents {
+    pub grade_changed: bool,
+    pub enrolment_created: bool,
+    pub user_created: bool,
+    pub course_deleted: bool,
+}
+
+impl WebhookEvents {
+    pub fn has(self, event: WebhookEvent) -> bool {
+        match event {
+            WebhookEvent::GradeChanged => self.grade_changed,
+            WebhookEvent::EnrolmentCreated => self.enrolment_created,
+            WebhookEvent::UserCreated => self.user_created,
+            WebhookEvent::CourseDeleted => self.course_deleted,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable)]
+#[diesel(table_name = webhooks)]
+pub struct Webhook {
+    pub id: u32,
+    pub url: String,
+    #[serde(skip_serializing)]
+    pub secret: String,
+    #[serde(flatten)]
+    #[diesel(embed)]
+    pub events: WebhookEvents,
+    pub created_on: NaiveDateTime,
+}
+
+impl Webhook {
+    /// Creates the webhook and returns the secret its payloads are signed
+    /// with
+    pub fn create(
+        connection: &mut Connection,
+        url: &str,
+        events: WebhookEvents,
+    ) -> Result<String, Error> {
+        let mut secret = [0u8; 32];
+        let mut rng = rand::rngs::StdRng::from_entropy();
+        secret.try_fill(&mut rng).map_err(Error::from)?;
+        let secret = hex::encode(secret);
+
+        diesel::insert_into(webhooks::table)
+            .values((
+                webhooks::url.eq(url),
+                webhooks::secret.eq(&secret),
+                webhooks::grade_changed.eq(events.grade_changed),
+                webhooks::enrolment_created.eq(events.enrolment_created),
+                webhooks::user_created.eq(events.user_created),
+                webhooks::course_deleted.eq(events.course_deleted),
+            ))
+            .execute(connection)?;
+
+        Ok(secret)
+    }
+
+    pub fn get_all(connection: &mut Connection) -> Result<Vec<Self>, Error> {
+        webhooks::table
+            .order(webhooks::id.desc())
+            .select(Webhook::as_select())
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    pub fn get_by_id(connection: &mut Connection, id: u32) -> Result<Self, Error> {
+        webhooks::table
+            .filter(webhooks::id.eq(id))
+            .select(Webhook::as_select())
+            .first(connection)
+            .map_err(Error::from)
+    }
+
+    /// Deletes the webhook along with its delivery log, queued deliveries
+    /// aren't sent
+    pub fn delete(connection: &mut Connection, id: u32) -> Result<(), Error> {
+        let deleted =
+            diesel::delete(webhooks::table.filter(webhooks::id.eq(id))).execute(connection)?;
+
+        if deleted == 0 {
+            return Err(Error::DatabaseEntryNotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct Payload<'a, T: Serialize> {
+    event: WebhookEvent,
+    occurred_on: NaiveDateTime,
+    data: &'a T,
+}
+
+/// Queues a delivery of the event to every webhook that is sent it, they're
+/// sent in the background
+pub fn trigger(
+    connection: &mut Connection,
+    event: WebhookEvent,
+    data: &impl Serialize,
+) -> Result<(), Error> {
+    let webhooks: Vec<u32> = webhooks::table
+        .select((webhooks::id, WebhookEvents::as_select()))
+        .load::<(u32, WebhookEvents)>(connection)?
+        .into_iter()
+        .filter(|(_, events)| events.has(event))
+        .map(|(id, _)| id)
+        .collect();
+
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let now = Local::now().naive_local();
+    let payload = serde_json::to_string(&Payload {
+        event,
+        occurred_on: now,
+        data,
+    })?;
+
+    let deliveries: Vec<_> = webhooks
+        .into_iter()
+        .map(|webhook| {
+            (
+                webhook_deliveries::webhook.eq(webhook),
+                webhook_deliveries::event.eq(event),
+                webhook_deliveries::payload.eq(&payload),
+                webhook_deliveries::next_attempt_on.eq(now),
+            )
+        })
+        .collect();
+
+    diesel::insert_into(webhook_deliveries::table)
+        .values(&deliveries)
+        .execute(connection)
+        .map(|_| ())
+        .map_err(Error::from)
+}
+
+/// The signature of a payload, sent in the `X-EStudent-Signature` header so
+/// receivers can check it came from us with the webhook's secret
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[derive(Serialize, Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct WebhookDelivery {
+    pub id: u32,
+    pub webhook: u32,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub next_attempt_on: Option<NaiveDateTime>,
+    pub delivered_on: Option<NaiveDateTime>,
+    pub created_on: NaiveDateTime,
+}
+
+#[derive(Serialize, Debug, Queryable, Selectable)]
+#[diesel(table_name = webhook_delivery_attempts)]
+pub struct WebhookDeliveryAttempt {
+    pub id: u32,
+    pub delivery: u32,
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub attempted_on: NaiveDateTime,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WebhookDeliveryWithAttempts {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+}
+
+/// What came of one attempt at a delivery
+#[derive(Debug)]
+pub struct Attempt {
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl Attempt {
+    pub fn succeeded(&self) -> bool {
+        matches!(self.response_status, Some(200..=299))
+    }
+}
+
+/// When to try again after the given number of failed attempts, `None` once
+/// there are no retries left
+fn next_attempt_on(attempts: usize, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    RETRY_DELAYS
+        .get(attempts.checked_sub(1)?)
+        .map(|minutes| now + chrono::Duration::minutes(*minutes))
+}
+
+impl WebhookDelivery {
+    /// The latest deliveries to the webhook with their attempts, newest
+    /// first
+    pub fn get_latest(
+        connection: &mut Connection,
+        webhook: u32,
+        limit: i64,
+    ) -> Result<Vec<WebhookDeliveryWithAttempts>, Error> {
+        let deliveries = webhook_deliveries::table
+            .filter(webhook_deliveries::webhook.eq(webhook))
+            .order(webhook_deliveries::id.desc())
+            .limit(limit)
+            .select(WebhookDelivery::as_select())
+            .load::<WebhookDelivery>(connection)?;
+
+        let ids: Vec<u32> = deliveries.iter().map(|delivery| delivery.id).collect();
+        let mut attempts: HashMap<u32, Vec<WebhookDeliveryAttempt>> = HashMap::new();
+        for attempt in webhook_delivery_attempts::table
+            .filter(webhook_delivery_attempts::delivery.eq_any(ids))
+            .order(webhook_delivery_attempts::id.asc())
+            .select(WebhookDeliveryAttempt::as_select())
+            .load::<WebhookDeliveryAttempt>(connection)?
+        {
+            attempts.entry(attempt.delivery).or_default().push(attempt);
+        }
+
+        Ok(deliveries
+            .into_iter()
+            .map(|delivery| WebhookDeliveryWithAttempts {
+                attempts: attempts.remove(&delivery.id).unwrap_or_default(),
+                delivery,
+            })
+            .collect())
+    }
+
+    /// Deliveries whose next attempt is due, along with their webhooks
+    fn get_due(
+        connection: &mut Connection,
+        now: NaiveDateTime,
+    ) -> Result<Vec<(Self, Webhook)>, Error> {
+        webhook_deliveries::table
+            .inner_join(webhooks::table)
+            .filter(webhook_deliveries::next_attempt_on.le(now))
+            .order(webhook_deliveries::next_attempt_on.asc())
+            .limit(DELIVERIES_PER_POLL)
+            .select((WebhookDelivery::as_select(), Webhook::as_select()))
+            .load(connection)
+            .map_err(Error::from)
+    }
+
+    /// Stores the attempt and either marks the delivery as delivered, or
+    /// schedules the next attempt unless it has run out of them
+    fn record_attempt(&self, connection: &mut Connection, attempt: &Attempt) -> Result<(), Error> {
+        diesel::Connection::transaction(connection, |connection| {
+            diesel::insert_into(webhook_delivery_attempts::table)
+                .values((
+                    webhook_delivery_attempts::delivery.eq(self.id),
+                    webhook_delivery_attempts::response_status.eq(attempt.response_status),
+                    webhook_delivery_attempts::error.eq(&attempt.error),
+                ))
+                .execute(connection)?;
+
+            let now = Local::now().naive_local();
+            let update = diesel::update(
+                webhook_deliveries::table.filter(webhook_deliveries::id.eq(self.id)),
+            );
+            if attempt.succeeded() {
+                update
+                    .set((
+                        webhook_deliveries::next_attempt_on.eq(None::<NaiveDateTime>),
+                        webhook_deliveries::delivered_on.eq(now),
+                    ))
+                    .execute(connection)?;
+            } else {
+                let attempts: i64 = webhook_delivery_attempts::table
+                    .filter(webhook_delivery_attempts::delivery.eq(self.id))
+                    .count()
+                    .get_result(connection)?;
+                let next = next_attempt_on(usize::try_from(attempts).unwrap_or(MAX_ATTEMPTS), now);
+                update
+                    .set(webhook_deliveries::next_attempt_on.eq(next))
+                    .execute(connection)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Queues the payload of a logged delivery again, as a new delivery with
+    /// its own attempts
+    pub fn redeliver(connection: &mut Connection, webhook: u32, id: u32) -> Result<(), Error> {
+        let delivery = webhook_deliveries::table
+            .filter(webhook_deliveries::id.eq(id))
+            .filter(webhook_deliveries::webhook.eq(webhook))
+            .select(WebhookDelivery::as_select())
+            .first::<WebhookDelivery>(connection)?;
+
+        diesel::insert_into(webhook_deliveries::table)
+            .values((
+                webhook_deliveries::webhook.eq(delivery.webhook),
+                webhook_deliveries::event.eq(delivery.event),
+                webhook_deliveries::payload.eq(delivery.payload),
+                webhook_deliveries::next_attempt_on.eq(Local::now().naive_local()),
+            ))
+            .execute(connection)
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+}
+
+/// Posts the payload to the webhook's URL, any response other than a 2xx
+/// counts as a failed attempt
+async fn send(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    delivery: &WebhookDelivery,
+) -> Attempt {
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("X-EStudent-Event", delivery.event.name())
+        .header("X-EStudent-Delivery", delivery.id)
+        .header("X-EStudent-Signature", sign(secret, &delivery.payload))
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => Attempt {
+            response_status: Some(response.status().as_u16()),
+            error: None,
+        },
+        Err(e) => Attempt {
+            response_status: None,
+            error: Some(e.to_string().chars().take(255).collect()),
+        },
+    }
+}
+
+async fn deliver_due(
+    pool: &ConnectionPool<Database, Connection>,
+    client: &reqwest::Client,
+) -> Result<(), Error> {
+    let Some(database) = pool.get().await else {
+        return Ok(());
+    };
+
+    let due = database
+        .run(|c| WebhookDelivery::get_due(c, Local::now().naive_local()))
+        .await?;
+    for (delivery, webhook) in due {
+        let attempt = send(client, &webhook.url, &webhook.secret, &delivery).await;
+        database
+            .run(move |c| delivery.record_attempt(c, &attempt))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Sends the queued deliveries in the background for as long as the server
+/// runs
+pub fn fairing() -> AdHoc {
+    AdHoc::on_liftoff("Webhook deliveries", |rocket| {
+        Box::pin(async move {
+            let Some(pool) = Database::pool(rocket).cloned() else {
+                error!("Webhooks won't be delivered, the database isn't attached");
+                return;
+            };
+            let client = match reqwest::Client::builder().timeout(TIMEOUT).build() {
+                Ok(client) => client,
+                Err(e) => {
+                    error!("Webhooks won't be delivered, no HTTP client: {e:?}");
+                    return;
+                }
+            };
+
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = deliver_due(&pool, &client).await {
+                        warn!("Failed to deliver webhooks: {e:?}");
+                    }
+                }
+            });
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use chrono::Local;
+
+    use super::{next_attempt_on, send, sign, WebhookDelivery, WebhookEvent, MAX_ATTEMPTS};
+
+    const SECRET: &str = "secret";
+    const PAYLOAD: &str = r#"{"event":"enrolment.created","data":{"course":1,"student":2}}"#;
+
+    /// A stand-in for a receiver, answers one request with the status and
+    /// hands back the request it got
+    fn receiver(status: u16) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let request = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).ends_with(PAYLOAD) {
+                let read = stream.read(&mut buffer).unwrap();
+                assert_ne!(read, 0, "the request ended before its body");
+                request.extend_from_slice(&buffer[..read]);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {status} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        (url, request)
+    }
+
+    fn delivery() -> WebhookDelivery {
+        let now = Local::now().naive_local();
+        WebhookDelivery {
+            id: 7,
+            webhook: 1,
+            event: WebhookEvent::EnrolmentCreated,
+            payload: PAYLOAD.to_owned(),
+            next_attempt_on: Some(now),
+            delivered_on: None,
+            created_on: now,
+        }
+    }
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[rocket::async_test]
+    async fn delivers_signed_payload() {
+        let (url, request) = receiver(204);
+        let attempt = send(&reqwest::Client::new(), &url, SECRET, &delivery()).await;
+        assert!(attempt.succeeded(), "{attempt:?}");
+
+        let request = request.join().unwrap().to_lowercase();
+        assert!(request.starts_with("post /hook "));
+        assert!(request.contains("content-type: application/json\r\n"));
+        assert!(request.contains("x-estudent-event: enrolment.created\r\n"));
+        assert!(request.contains("x-estudent-delivery: 7\r\n"));
+        assert!(request.contains(&format!(
+            "x-estudent-signature: {}\r\n",
+            sign(SECRET, PAYLOAD)
+        )));
+        assert!(request.ends_with(&PAYLOAD.to_lowercase()));
+    }
+
+    #[rocket::async_test]
+    async fn error_responses_fail_the_attempt() {
+        let (url, request) = receiver(500);
+        let attempt = send(&reqwest::Client::new(), &url, SECRET, &delivery()).await;
+        request.join().unwrap();
+
+        assert!(!attempt.succeeded());
+        assert_eq!(attempt.response_status, Some(500));
+    }
+
+    #[rocket::async_test]
+    async fn unreachable_receivers_fail_the_attempt() {
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/hook", listener.local_addr().unwrap())
+        };
+        let attempt = send(&reqwest::Client::new(), &url, SECRET, &delivery()).await;
+
+        assert!(!attempt.succeeded());
+        assert_eq!(attempt.response_status, None);
+        assert!(attempt.error.is_some());
+    }
+
+    #[test]
+    fn retries_until_out_of_attempts() {
+        let now = Local::now().naive_local();
+        for attempts in 1..MAX_ATTEMPTS {
+            assert!(next_attempt_on(attempts, now).unwrap() > now);
+        }
+        assert_eq!(next_attempt_on(MAX_ATTEMPTS, now), None);
+    }
+}
@@ -0,0 +1,27 @@
+// Lets the draggable rows of a [data-reorder] form be rearranged. The form
+// submits the rows' hidden inputs in document order, so moving a row is all
+// that's needed to change the order sent to the server.
+document.querySelectorAll("[data-reorder]").forEach((list) => {
+  let dragged = null;
+
+  list.querySelectorAll("[draggable]").forEach((row) => {
+    row.addEventListener("dragstart", () => {
+      dragged = row;
+    });
+
+    row.addEventListener("dragover", (event) => {
+      event.preventDefault();
+      if (dragged === null || dragged === row) {
+        return;
+      }
+
+      const bounds = row.getBoundingClientRect();
+      const after = event.clientY > bounds.top + bounds.height / 2;
+      row.parentNode.insertBefore(dragged, after ? row.nextSibling : row);
+    });
+
+    row.addEventListener("dragend", () => {
+      dragged = null;
+    });
+  });
+});